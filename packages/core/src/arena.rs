@@ -1,4 +1,7 @@
-use crate::{nodes::RenderReturn, nodes::VNode, virtual_dom::VirtualDom, DynamicNode, ScopeId};
+use crate::{
+    dirty_scope::DirtyScope, nodes::RenderReturn, nodes::VNode, virtual_dom::VirtualDom,
+    DynamicNode, ScopeId,
+};
 use bumpalo::boxed::Box as BumpBox;
 
 /// An Element's unique identifier.
@@ -73,7 +76,7 @@ impl VirtualDom {
         self.elements[el.0].template = unsafe { std::mem::transmute(node) };
     }
 
-    // Drop a scope and all its children
+    // Drop a scope and all its children, reclaiming their elements
     pub(crate) fn drop_scope(&mut self, id: ScopeId) {
         if let Some(root) = self.scopes[id.0].as_ref().try_root_node() {
             if let RenderReturn::Sync(Ok(node)) = unsafe { root.extend_lifetime_ref() } {
@@ -81,10 +84,31 @@ impl VirtualDom {
             }
         }
 
+        self.unmount_scope(id);
+    }
+
+    /// Drop the props, hooks and tasks of a scope whose nodes were already removed, so it can't render again
+    pub(crate) fn unmount_scope(&mut self, id: ScopeId) {
         self.scopes[id.0].props.take();
 
+        // A scope that was marked dirty before it was unmounted has nothing left to render
+        let height = self.scopes[id.0].height;
+        self.dirty_scopes.remove(&DirtyScope { height, id });
+
+        // Let renderers know the scope is gone
+        self.mutations.dirty_scopes.insert(id);
+
+        // The scope's tasks can't wake it up anymore
+        self.scheduler
+            .tasks
+            .borrow_mut()
+            .retain(|_, task| task.scope != id);
+
         let scope = &mut self.scopes[id.0];
 
+        // Handles to the scope stop working, even if its id is reused
+        scope.mounted.take();
+
         // Drop all the hooks once the children are dropped
         // this means we'll drop hooks bottom-up
         for hook in scope.hook_list.get_mut().drain(..) {
@@ -60,6 +60,16 @@ impl<'b> VirtualDom {
             return self.light_diff_templates(left_template, right_template);
         }
 
+        // Nodes borrowed from a parent's props (like `children`) are shared between renders of the child if the
+        // parent didn't re-render. They're the exact same node, so there's nothing to diff - and diffing them would
+        // try to move the props of any components inside out of the node twice.
+        if std::ptr::eq(left_template.root_ids, right_template.root_ids)
+            && std::ptr::eq(left_template.dynamic_nodes, right_template.dynamic_nodes)
+            && std::ptr::eq(left_template.dynamic_attrs, right_template.dynamic_attrs)
+        {
+            return;
+        }
+
         for (left_attr, right_attr) in left_template
             .dynamic_attrs
             .iter()
@@ -279,10 +289,12 @@ impl<'b> VirtualDom {
             }
             Some(Component(comp)) => {
                 let scope = comp.scope.get().unwrap();
-                match unsafe { self.scopes[scope.0].root_node().extend_lifetime_ref() } {
+                let id = match unsafe { self.scopes[scope.0].root_node().extend_lifetime_ref() } {
                     RenderReturn::Sync(Ok(t)) => self.replace_inner(t),
                     _ => todo!("cannot handle nonstandard nodes"),
-                }
+                };
+                self.unmount_scope(scope);
+                id
             }
         };
 
@@ -314,6 +326,7 @@ impl<'b> VirtualDom {
                         RenderReturn::Sync(Ok(t)) => self.clean_up_node(t),
                         _ => todo!("cannot handle nonstandard nodes"),
                     };
+                    self.unmount_scope(scope);
                 }
                 Text(t) => self.reclaim(t.id.get()),
                 Placeholder(t) => self.reclaim(t.get()),
@@ -360,6 +373,7 @@ impl<'b> VirtualDom {
                     RenderReturn::Sync(Ok(t)) => self.remove_node(t),
                     _ => todo!("cannot handle nonstandard nodes"),
                 };
+                self.unmount_scope(scope);
             }
             None => {
                 let id = node.root_ids[idx].get();
@@ -780,6 +794,8 @@ impl<'b> VirtualDom {
             RenderReturn::Sync(Ok(t)) => self.remove_node(t),
             _ => todo!("cannot handle nonstandard nodes"),
         }
+
+        self.unmount_scope(scope_id);
     }
 
     /// Push all the real nodes on the stack
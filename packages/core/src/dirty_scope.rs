@@ -8,12 +8,13 @@ pub struct DirtyScope {
 
 impl PartialOrd for DirtyScope {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.height.cmp(&other.height))
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for DirtyScope {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.height.cmp(&other.height)
+        // Scopes at the same height are still different scopes
+        self.height.cmp(&other.height).then(self.id.cmp(&other.id))
     }
 }
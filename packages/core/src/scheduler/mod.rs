@@ -35,6 +35,9 @@ pub(crate) struct Scheduler {
 
     /// Async components
     pub leaves: RefCell<Slab<Rc<SuspenseLeaf>>>,
+
+    /// Callbacks queued with cx.queue_layout_effect, run once the renderer applied the mutations
+    pub layout_effects: RefCell<Vec<Box<dyn FnOnce()>>>,
 }
 
 impl Scheduler {
@@ -43,6 +46,7 @@ impl Scheduler {
             sender,
             tasks: RefCell::new(Slab::new()),
             leaves: RefCell::new(Slab::new()),
+            layout_effects: RefCell::new(Vec::new()),
         })
     }
 }
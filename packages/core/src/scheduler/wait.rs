@@ -41,13 +41,11 @@ impl VirtualDom {
     }
 
     pub(crate) fn handle_suspense_wakeup(&mut self, id: SuspenseId) {
-        let leaf = self
-            .scheduler
-            .leaves
-            .borrow_mut()
-            .get(id.0)
-            .unwrap()
-            .clone();
+        // The leaf might have already resolved if it was woken more than once before we got to it
+        let leaf = match self.scheduler.leaves.borrow().get(id.0) {
+            Some(leaf) => leaf.clone(),
+            None => return,
+        };
 
         let scope_id = leaf.scope_id;
 
@@ -74,6 +72,9 @@ impl VirtualDom {
 
             fiber.waiting_on.borrow_mut().remove(&id);
 
+            // The leaf is done, so it no longer counts as suspended work
+            self.scheduler.leaves.borrow_mut().remove(id.0);
+
             if let RenderReturn::Sync(Ok(template)) = ret {
                 let mutations_ref = &mut fiber.mutations.borrow_mut();
                 let mutations = &mut **mutations_ref;
@@ -97,6 +98,12 @@ impl VirtualDom {
 
                 std::mem::swap(&mut self.mutations, mutations);
 
+                // The scopes are already updated even though the edits wait for the rest of the boundary
+                self.mutations.dirty_scopes.insert(scope_id);
+                self.mutations
+                    .dirty_scopes
+                    .extend(mutations.dirty_scopes.drain());
+
                 if fiber.waiting_on.borrow().is_empty() {
                     self.finished_fibers.push(fiber.id);
                 }
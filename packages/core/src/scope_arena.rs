@@ -5,7 +5,7 @@ use crate::{
     innerlude::{SuspenseId, SuspenseLeaf},
     nodes::RenderReturn,
     scheduler::RcWake,
-    scopes::{ScopeId, ScopeState},
+    scopes::{set_current_scope, MountedScope, ScopeId, ScopeState},
     virtual_dom::VirtualDom,
 };
 use futures_util::FutureExt;
@@ -43,6 +43,7 @@ impl VirtualDom {
             hook_idx: Default::default(),
             shared_contexts: Default::default(),
             tasks: self.scheduler.clone(),
+            mounted: Some(MountedScope::new(id, self.scheduler.sender.clone())),
         }))
     }
 
@@ -59,6 +60,8 @@ impl VirtualDom {
         // Remove all the outdated listeners
         self.ensure_drop_safety(scope_id);
 
+        let previous_scope = set_current_scope(Some(self.scopes[scope_id.0].handle()));
+
         let mut new_nodes = unsafe {
             let scope = self.scopes[scope_id.0].as_mut();
 
@@ -122,6 +125,8 @@ impl VirtualDom {
             }
         };
 
+        set_current_scope(previous_scope);
+
         let scope = &self.scopes[scope_id.0];
 
         // We write on top of the previous frame and then make it the current by pushing the generation forward
@@ -134,6 +139,9 @@ impl VirtualDom {
         // And move the render generation forward by one
         scope.render_cnt.set(scope.render_cnt.get() + 1);
 
+        // Let renderers know this scope produced new nodes
+        self.mutations.dirty_scopes.insert(scope_id);
+
         // remove this scope from dirty scopes
         self.dirty_scopes.remove(&DirtyScope {
             height: scope.height,
@@ -10,13 +10,14 @@ use crate::{
     Attribute, AttributeValue, Element, Event, Properties, TaskId,
 };
 use bumpalo::{boxed::Box as BumpBox, Bump};
+use futures_channel::mpsc::UnboundedSender;
 use std::{
     any::{Any, TypeId},
     cell::{Cell, RefCell},
     collections::{HashMap, HashSet},
     fmt::Arguments,
     future::Future,
-    rc::Rc,
+    rc::{Rc, Weak},
     sync::Arc,
 };
 
@@ -54,6 +55,83 @@ impl<'a, T> std::ops::Deref for Scoped<'a, T> {
     }
 }
 
+thread_local! {
+    /// The component that's rendering on this thread
+    static RENDERING_SCOPE: RefCell<Option<ScopeHandle>> = const { RefCell::new(None) };
+}
+
+/// Get the id of the component that's rendering on this thread, if a component is rendering.
+pub fn current_scope_id() -> Option<ScopeId> {
+    RENDERING_SCOPE.with(|scope| scope.borrow().as_ref().and_then(ScopeHandle::id))
+}
+
+/// Get a handle to the component that's rendering on this thread, if a component is rendering.
+///
+/// Values read while a component renders, like signals, use this to know which component to update when they change.
+pub fn current_scope() -> Option<ScopeHandle> {
+    RENDERING_SCOPE.with(|scope| scope.borrow().clone())
+}
+
+/// Set the component that's rendering, returning the one that was
+pub(crate) fn set_current_scope(scope: Option<ScopeHandle>) -> Option<ScopeHandle> {
+    RENDERING_SCOPE.with(|current| current.replace(scope))
+}
+
+/// A handle to a mounted component that stops working once the component is unmounted.
+///
+/// A [`ScopeId`] is reused once its component is unmounted, and only means something to the VirtualDom that created
+/// it. A handle always points to the same component of the same VirtualDom, so it can be kept around to update the
+/// component later without updating whichever component took its place.
+#[derive(Clone)]
+pub struct ScopeHandle(Weak<MountedScope>);
+
+/// Lives as long as a component is mounted
+pub(crate) struct MountedScope {
+    id: ScopeId,
+    sender: UnboundedSender<SchedulerMsg>,
+}
+
+impl ScopeHandle {
+    /// Get the id of the component, if it's still mounted.
+    pub fn id(&self) -> Option<ScopeId> {
+        self.0.upgrade().map(|scope| scope.id)
+    }
+
+    /// Check whether the component is still mounted.
+    pub fn is_mounted(&self) -> bool {
+        self.0.strong_count() > 0
+    }
+
+    /// Schedule a render of the component. Does nothing once the component is unmounted.
+    pub fn needs_update(&self) {
+        if let Some(scope) = self.0.upgrade() {
+            _ = scope
+                .sender
+                .unbounded_send(SchedulerMsg::Immediate(scope.id));
+        }
+    }
+}
+
+impl PartialEq for ScopeHandle {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.ptr_eq(&other.0)
+    }
+}
+
+impl Eq for ScopeHandle {}
+
+impl std::hash::Hash for ScopeHandle {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.as_ptr().hash(state)
+    }
+}
+
+impl std::fmt::Debug for ScopeHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ScopeHandle").field(&self.id()).finish()
+    }
+}
+
 /// A component's unique identifier.
 ///
 /// `ScopeId` is a `usize` that acts a key for the internal slab of Scopes. This means that the key is not unqiue across
@@ -89,6 +167,15 @@ pub struct ScopeState {
 
     pub(crate) props: Option<Box<dyn AnyProps<'static>>>,
     pub(crate) placeholder: Cell<Option<ElementId>>,
+
+    /// Dropped when the scope is unmounted, which stops its [`ScopeHandle`]s
+    pub(crate) mounted: Option<Rc<MountedScope>>,
+}
+
+impl MountedScope {
+    pub(crate) fn new(id: ScopeId, sender: UnboundedSender<SchedulerMsg>) -> Rc<Self> {
+        Rc::new(Self { id, sender })
+    }
 }
 
 impl<'src> ScopeState {
@@ -222,6 +309,14 @@ impl<'src> ScopeState {
         self.id
     }
 
+    /// Get a [`ScopeHandle`] to this component, which stops working once the component is unmounted.
+    pub fn handle(&self) -> ScopeHandle {
+        match &self.mounted {
+            Some(mounted) => ScopeHandle(Rc::downgrade(mounted)),
+            None => ScopeHandle(Weak::new()),
+        }
+    }
+
     /// Create a subscription that schedules a future render for the reference component
     ///
     /// ## Notice: you should prefer using [`Self::schedule_update_any`] and [`Self::scope_id`]
@@ -353,6 +448,18 @@ impl<'src> ScopeState {
         self.tasks.remove(id);
     }
 
+    /// Queue a callback to run once the renderer applied the mutations of this render, like to measure an element or
+    /// to attach a resource to it.
+    ///
+    /// Renderers run the queued callbacks with [`crate::VirtualDom::run_layout_effects`]. Renderers without a real dom,
+    /// like server side rendering, drop them instead.
+    pub fn queue_layout_effect(&self, effect: impl FnOnce() + 'static) {
+        self.tasks
+            .layout_effects
+            .borrow_mut()
+            .push(Box::new(effect));
+    }
+
     /// Take a lazy [`crate::VNode`] structure and actually build it with the context of the efficient [`bumpalo::Bump`] allocator.
     ///
     /// ## Example
@@ -167,6 +167,9 @@ pub struct VirtualDom {
     pub(crate) rx: futures_channel::mpsc::UnboundedReceiver<SchedulerMsg>,
 
     pub(crate) mutations: Mutations<'static>,
+
+    // Whether the renderer runs the layout effects, or they're dropped at the end of every render
+    pub(crate) keep_layout_effects: bool,
 }
 
 impl VirtualDom {
@@ -237,6 +240,7 @@ impl VirtualDom {
             collected_leaves: Vec::new(),
             finished_fibers: Vec::new(),
             mutations: Mutations::default(),
+            keep_layout_effects: false,
         };
 
         let root = dom.new_scope(
@@ -281,12 +285,25 @@ impl VirtualDom {
         self
     }
 
+    /// Keep the callbacks queued with [`ScopeState::queue_layout_effect`] until [`VirtualDom::run_layout_effects`] runs
+    /// them.
+    ///
+    /// Renderers that apply the mutations to a real dom enable this before the first render. Without it, like when
+    /// rendering on a server, the callbacks are dropped at the end of every render, since nothing would run them.
+    pub fn with_layout_effects(mut self) -> Self {
+        self.keep_layout_effects = true;
+        self
+    }
+
     /// Manually mark a scope as requiring a re-render
     ///
     /// Whenever the VirtualDom "works", it will re-render this scope
     pub fn mark_dirty(&mut self, id: ScopeId) {
-        let height = self.scopes[id.0].height;
-        self.dirty_scopes.insert(DirtyScope { height, id });
+        // Updates can be scheduled for scopes that were dropped in the meantime
+        if let Some(scope) = self.scopes.get(id.0) {
+            let height = scope.height;
+            self.dirty_scopes.insert(DirtyScope { height, id });
+        }
     }
 
     /// Determine whether or not a scope is currently in a suspended state
@@ -487,6 +504,14 @@ impl VirtualDom {
                     id: ElementId(0),
                     m,
                 });
+
+                // Any suspended components that weren't caught by a boundary are caught by the root
+                if !self.collected_leaves.is_empty() {
+                    self.acquire_suspense_boundary(ScopeId(0))
+                        .waiting_on
+                        .borrow_mut()
+                        .extend(self.collected_leaves.drain(..));
+                }
             }
             // If an error occurs, we should try to render the default error component and context where the error occured
             RenderReturn::Sync(Err(e)) => panic!("Cannot catch errors during rebuild {:?}", e),
@@ -536,11 +561,14 @@ impl VirtualDom {
                     .edits
                     .append(&mut context.mutations.borrow_mut().edits);
 
+                // The root boundary doesn't have a placeholder - its suspended leaves replace their own placeholders
                 // TODO: count how many nodes are on the stack?
-                self.mutations.push(Mutation::ReplaceWith {
-                    id: context.placeholder.get().unwrap(),
-                    m: 1,
-                })
+                if let Some(placeholder) = context.placeholder.get() {
+                    self.mutations.push(Mutation::ReplaceWith {
+                        id: placeholder,
+                        m: 1,
+                    })
+                }
             }
 
             // Next, diff any dirty scopes
@@ -548,6 +576,11 @@ impl VirtualDom {
             if let Some(dirty) = self.dirty_scopes.iter().next().cloned() {
                 self.dirty_scopes.remove(&dirty);
 
+                // Scopes that were unmounted after they were marked dirty have nothing to render
+                if self.scopes[dirty.id.0].props.is_none() {
+                    continue;
+                }
+
                 // if the scope is currently suspended, then we should skip it, ignoring any tasks calling for an update
                 if self.is_scope_suspended(dirty.id) {
                     continue;
@@ -610,8 +643,41 @@ impl VirtualDom {
         }
     }
 
+    /// Run the callbacks components queued with [`ScopeState::queue_layout_effect`] while rendering.
+    ///
+    /// Renderers call this right after they applied the mutations returned by [`VirtualDom::rebuild`],
+    /// [`VirtualDom::render_immediate`] or [`VirtualDom::render_with_deadline`], so the callbacks see the real dom in
+    /// the state the render produced. The callbacks are only kept for this if the VirtualDom was built
+    /// [`VirtualDom::with_layout_effects`].
+    ///
+    /// ```rust, ignore
+    /// let mut dom = VirtualDom::new(app).with_layout_effects();
+    ///
+    /// real_dom.apply(dom.render_immediate());
+    /// dom.run_layout_effects();
+    /// ```
+    pub fn run_layout_effects(&mut self) {
+        // Effects can queue more effects, which run in the same pass
+        loop {
+            let effects = std::mem::take(&mut *self.scheduler.layout_effects.borrow_mut());
+            if effects.is_empty() {
+                break;
+            }
+
+            for effect in effects {
+                effect();
+            }
+        }
+    }
+
     /// Swap the current mutations with a new
     fn finalize(&mut self) -> Mutations {
+        // Nothing will run the layout effects, so they'd pile up forever
+        if !self.keep_layout_effects {
+            let effects = std::mem::take(&mut *self.scheduler.layout_effects.borrow_mut());
+            drop(effects);
+        }
+
         // todo: make this a routine
         let mut out = Mutations::default();
         std::mem::swap(&mut self.mutations, &mut out);
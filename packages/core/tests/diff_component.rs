@@ -100,3 +100,31 @@ fn component_swap() {
         ]
     );
 }
+
+/// Children borrowed from the props of a parent that didn't re-render are the exact same nodes as last time, so
+/// re-rendering the child alone shouldn't touch them
+#[test]
+fn borrowed_children_are_not_diffed() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            children_wrapper {
+                inner_child {}
+            }
+        })
+    }
+
+    #[inline_props]
+    fn children_wrapper<'a>(cx: Scope<'a>, children: Element<'a>) -> Element {
+        cx.render(rsx! { div { children } })
+    }
+
+    fn inner_child(cx: Scope) -> Element {
+        cx.render(rsx! { "inner" })
+    }
+
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+
+    dom.mark_dirty(ScopeId(1));
+    assert_eq!(dom.render_immediate().santize().edits, []);
+}
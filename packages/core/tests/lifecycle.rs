@@ -166,3 +166,59 @@ fn events_generate() {
 //         ]
 //     );
 // }
+
+#[test]
+fn siblings_marked_dirty_both_rerender() {
+    thread_local! {
+        static RENDERS: std::cell::RefCell<Vec<&'static str>> = Default::default();
+    }
+
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            Child { name: "left" }
+            Child { name: "right" }
+        })
+    }
+
+    #[inline_props]
+    fn Child(cx: Scope, name: &'static str) -> Element {
+        RENDERS.with(|renders| renders.borrow_mut().push(name));
+        cx.render(rsx! { "{name}" })
+    }
+
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+    RENDERS.with(|renders| renders.borrow_mut().clear());
+
+    // Both children are at the same height, so they must not be mistaken for the same dirty scope
+    dom.mark_dirty(ScopeId(1));
+    dom.mark_dirty(ScopeId(2));
+    let _ = dom.render_immediate();
+
+    RENDERS.with(|renders| assert_eq!(*renders.borrow(), ["left", "right"]));
+}
+
+#[test]
+fn layout_effects_are_dropped_without_a_renderer() {
+    thread_local! {
+        static RAN: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    fn app(cx: Scope) -> Element {
+        cx.queue_layout_effect(|| RAN.with(|ran| ran.set(ran.get() + 1)));
+        render!(div {})
+    }
+
+    // Nothing keeps the effects of a VirtualDom that wasn't built for a renderer that runs them
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+    dom.run_layout_effects();
+    assert_eq!(RAN.with(std::cell::Cell::get), 0);
+
+    let mut dom = VirtualDom::new(app).with_layout_effects();
+    _ = dom.rebuild();
+    dom.mark_dirty(ScopeId(0));
+    _ = dom.render_immediate();
+    dom.run_layout_effects();
+    assert_eq!(RAN.with(std::cell::Cell::get), 2);
+}
@@ -90,3 +90,41 @@ async fn async_text(cx: Scope<'_>) -> Element {
 
     cx.render(rsx!( div { "Hello! {username}, you are {age}, {_user} {_age}" } ))
 }
+
+/// Suspended components without a boundary of their own are caught by the root, and stop counting as suspended work
+/// once they resolve
+#[tokio::test]
+async fn suspends_into_the_root_without_a_boundary() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx!(div { async_leaf {} }))
+    }
+
+    async fn async_leaf(cx: Scope<'_>) -> Element {
+        use_future!(cx, || tokio::time::sleep(Duration::from_millis(10))).await;
+        cx.render(rsx!("loaded"))
+    }
+
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+    assert!(dom.has_suspended_work());
+
+    let (mut replaced_root, mut replaced_leaf) = (false, false);
+    while dom.has_suspended_work() {
+        tokio::time::timeout(Duration::from_secs(1), dom.wait_for_work())
+            .await
+            .unwrap();
+
+        let mutations = dom.render_with_deadline(std::future::ready(())).await;
+        for edit in mutations.santize().edits {
+            match edit {
+                ReplaceWith { id: ElementId(0), .. } => replaced_root = true,
+                ReplaceWith { .. } => replaced_leaf = true,
+                _ => {}
+            }
+        }
+    }
+
+    // The leaf replaces its own placeholder, and there's no boundary placeholder to replace
+    assert!(replaced_leaf);
+    assert!(!replaced_root);
+}
@@ -40,3 +40,52 @@ fn app(cx: Scope) -> Element {
 
     cx.render(rsx!(()))
 }
+
+/// Removing a component drops the tasks it spawned, and tells the renderer that its scope is gone
+#[test]
+fn tasks_of_removed_components_are_dropped() {
+    use std::cell::Cell;
+
+    thread_local! {
+        static SHOW_CHILD: Cell<bool> = const { Cell::new(true) };
+        static TASK_DROPPED: Cell<bool> = const { Cell::new(false) };
+    }
+
+    struct DropFlag;
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            TASK_DROPPED.with(|dropped| dropped.set(true));
+        }
+    }
+
+    fn app(cx: Scope) -> Element {
+        let show_child = SHOW_CHILD.with(|show| show.get());
+        cx.render(rsx! {
+            div {
+                show_child.then(|| rsx!(task_child {}))
+            }
+        })
+    }
+
+    fn task_child(cx: Scope) -> Element {
+        cx.use_hook(|| {
+            let flag = DropFlag;
+            cx.spawn(async move {
+                let _flag = flag;
+                std::future::pending::<()>().await;
+            })
+        });
+
+        cx.render(rsx!("child"))
+    }
+
+    let mut dom = VirtualDom::new(app);
+    let _ = dom.rebuild();
+
+    SHOW_CHILD.with(|show| show.set(false));
+    dom.mark_dirty(ScopeId(0));
+    let mutations = dom.render_immediate();
+
+    assert!(mutations.dirty_scopes.contains(&ScopeId(1)));
+    assert!(TASK_DROPPED.with(|dropped| dropped.get()));
+}
@@ -1,7 +1,10 @@
-use crate::desktop_context::{DesktopContext, UserWindowEvent};
+use crate::build_webview;
+use crate::cfg::Config;
+use crate::desktop_context::{DesktopContext, EventData, UserWindowEvent};
 use crate::events::{decode_event, EventMessage};
 use dioxus_core::*;
-use futures_channel::mpsc::{unbounded, UnboundedSender};
+use dioxus_html::head::{HeadContext, HEAD_MARKER};
+use futures_channel::mpsc::{unbounded, UnboundedReceiver};
 use futures_util::StreamExt;
 #[cfg(target_os = "ios")]
 use objc::runtime::Object;
@@ -13,113 +16,119 @@ use std::{
 };
 use wry::{
     self,
-    application::{event_loop::ControlFlow, event_loop::EventLoopProxy, window::WindowId},
+    application::{
+        event_loop::{ControlFlow, EventLoopProxy, EventLoopWindowTarget},
+        window::{WindowBuilder, WindowId},
+    },
     webview::WebView,
 };
 
 pub(super) struct DesktopController {
-    pub(super) webviews: HashMap<WindowId, WebView>,
-    pub(super) pending_edits: Arc<Mutex<Vec<String>>>,
+    pub(super) windows: HashMap<WindowId, DesktopWindow>,
+    pub(super) cfg: Config,
     pub(super) quit_app_on_close: bool,
-    pub(super) is_ready: Arc<AtomicBool>,
     pub(super) proxy: EventLoopProxy<UserWindowEvent>,
-    pub(super) event_tx: UnboundedSender<serde_json::Value>,
 
     #[cfg(target_os = "ios")]
     pub(super) views: Vec<*mut Object>,
 }
 
+/// A window, with the edits of its VirtualDom that haven't been applied yet
+pub(super) struct DesktopWindow {
+    pub(super) webview: WebView,
+    pending_edits: Arc<Mutex<Vec<String>>>,
+    is_ready: Arc<AtomicBool>,
+}
+
 impl DesktopController {
-    // Launch the virtualdom on its own thread managed by tokio
-    // returns the desktop state
-    pub(super) fn new_on_tokio<P: Send + 'static>(
+    pub(super) fn new(cfg: Config, proxy: EventLoopProxy<UserWindowEvent>) -> Self {
+        Self {
+            windows: HashMap::new(),
+            cfg,
+            quit_app_on_close: true,
+            proxy,
+            #[cfg(target_os = "ios")]
+            views: vec![],
+        }
+    }
+
+    /// Open a window and launch a VirtualDom for it on its own thread managed by tokio
+    ///
+    /// Every window has a VirtualDom of its own, so it has its own state, contexts and router.
+    pub(super) fn open_window<P: Send + 'static>(
+        &mut self,
+        event_loop: &EventLoopWindowTarget<UserWindowEvent>,
+        window: WindowBuilder,
         root: Component<P>,
         props: P,
-        proxy: EventLoopProxy<UserWindowEvent>,
-    ) -> Self {
-        let edit_queue = Arc::new(Mutex::new(Vec::new()));
-        let (event_tx, mut event_rx) = unbounded();
-        let proxy2 = proxy.clone();
-
-        let pending_edits = edit_queue.clone();
-        let desktop_context_proxy = proxy.clone();
-
-        std::thread::spawn(move || {
-            // We create the runtime as multithreaded, so you can still "tokio::spawn" onto multiple threads
-            // I'd personally not require tokio to be built-in to Dioxus-Desktop, but the DX is worse without it
-            let runtime = tokio::runtime::Builder::new_multi_thread()
-                .enable_all()
-                .build()
-                .unwrap();
-
-            runtime.block_on(async move {
-                let mut dom = VirtualDom::new_with_props(root, props)
-                    .with_root_context(DesktopContext::new(desktop_context_proxy));
-                {
-                    let edits = dom.rebuild();
-                    let mut queue = edit_queue.lock().unwrap();
-                    queue.push(serde_json::to_string(&edits).unwrap());
-                    proxy.send_event(UserWindowEvent::EditsReady).unwrap();
-                }
+        init: impl FnOnce(VirtualDom) -> VirtualDom + Send + 'static,
+    ) {
+        let pending_edits = Arc::new(Mutex::new(Vec::new()));
+        let is_ready = Arc::new(AtomicBool::new(false));
+        let (event_tx, event_rx) = unbounded();
+        let (applied_tx, applied_rx) = unbounded();
 
-                loop {
-                    tokio::select! {
-                        _ = dom.wait_for_work() => {}
-                        Some(json_value) = event_rx.next() => {
-                            if let Ok(value) = serde_json::from_value::<EventMessage>(json_value) {
-                                let name = value.event.clone();
-                                let el_id = ElementId(value.mounted_dom_id);
-                                if let Some(evt) = decode_event(value) {
-                                    dom.handle_event(&name,  evt, el_id,  dioxus_html::events::event_bubbles(&name));
-                                }
-                            }
-                        }
-                    }
+        // The title the window gets back when no component sets one
+        let default_title = window.window.title.clone();
 
-                    let muts = dom
-                        .render_with_deadline(tokio::time::sleep(Duration::from_millis(16)))
-                        .await;
+        let webview = build_webview(
+            &mut self.cfg,
+            window,
+            event_loop,
+            is_ready.clone(),
+            self.proxy.clone(),
+            event_tx,
+            applied_tx,
+        );
+        let id = webview.window().id();
 
-                    edit_queue.lock().unwrap().push(serde_json::to_string(&muts).unwrap());
-                    let _ = proxy.send_event(UserWindowEvent::EditsReady);
-                }
-            })
-        });
+        let desktop = DesktopContext::new(self.proxy.clone(), id);
+        spawn_dom(
+            root,
+            props,
+            init,
+            desktop,
+            default_title,
+            pending_edits.clone(),
+            event_rx,
+            applied_rx,
+        );
 
-        Self {
-            pending_edits,
-            webviews: HashMap::new(),
-            is_ready: Arc::new(AtomicBool::new(false)),
-            quit_app_on_close: true,
-            proxy: proxy2,
-            event_tx,
-            #[cfg(target_os = "ios")]
-            views: vec![],
-        }
+        self.windows.insert(
+            id,
+            DesktopWindow {
+                webview,
+                pending_edits,
+                is_ready,
+            },
+        );
     }
 
     pub(super) fn close_window(&mut self, window_id: WindowId, control_flow: &mut ControlFlow) {
-        self.webviews.remove(&window_id);
+        // Dropping the webview closes the window and stops its VirtualDom
+        self.windows.remove(&window_id);
 
-        if self.webviews.is_empty() && self.quit_app_on_close {
+        if self.windows.is_empty() && self.quit_app_on_close {
             *control_flow = ControlFlow::Exit;
         }
     }
 
-    pub(super) fn try_load_ready_webviews(&mut self) {
-        if self.is_ready.load(std::sync::atomic::Ordering::Relaxed) {
+    pub(super) fn try_load_ready_webview(&self, window_id: WindowId) {
+        let window = match self.windows.get(&window_id) {
+            Some(window) => window,
+            None => return,
+        };
+
+        if window.is_ready.load(std::sync::atomic::Ordering::Relaxed) {
             let mut new_queue = Vec::new();
 
             {
-                let mut queue = self.pending_edits.lock().unwrap();
+                let mut queue = window.pending_edits.lock().unwrap();
                 std::mem::swap(&mut new_queue, &mut *queue);
             }
 
-            let (_id, view) = self.webviews.iter_mut().next().unwrap();
-
-            for edit in new_queue.drain(..) {
-                view.evaluate_script(&format!("window.interpreter.handleEdits({})", edit))
-                    .unwrap();
+            for script in new_queue.drain(..) {
+                window.webview.evaluate_script(&script).unwrap();
             }
         }
     }
@@ -128,3 +137,125 @@ impl DesktopController {
         todo!("hot reloading currently WIP")
     }
 }
+
+/// Run the VirtualDom of a window on its own thread, until the window is closed
+fn spawn_dom<P: Send + 'static>(
+    root: Component<P>,
+    props: P,
+    init: impl FnOnce(VirtualDom) -> VirtualDom + Send + 'static,
+    desktop: DesktopContext,
+    default_title: String,
+    edit_queue: Arc<Mutex<Vec<String>>>,
+    mut event_rx: UnboundedReceiver<serde_json::Value>,
+    mut applied_rx: UnboundedReceiver<()>,
+) {
+    std::thread::spawn(move || {
+        // We create the runtime as multithreaded, so you can still "tokio::spawn" onto multiple threads
+        // I'd personally not require tokio to be built-in to Dioxus-Desktop, but the DX is worse without it
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async move {
+            let head = HeadContext::new();
+            let mut dom = init(
+                VirtualDom::new_with_props(root, props)
+                    .with_root_context(desktop.clone())
+                    .with_root_context(head.clone())
+                    .with_layout_effects(),
+            );
+
+            // The batches of edits sent to the webview that it didn't acknowledge yet. The layout effects run once
+            // the webview applied all of them, since they were queued by the renders that produced them.
+            let mut unapplied = 0;
+
+            {
+                let edits = dom.rebuild();
+                let mut queue = edit_queue.lock().unwrap();
+                queue.push(edits_script(&edits));
+                if head.take_changed() {
+                    queue.push(head_script(&head, &desktop, &default_title));
+                }
+                queue.push(EDITS_APPLIED_SCRIPT.to_string());
+                unapplied += 1;
+                desktop.send(EventData::EditsReady);
+            }
+
+            loop {
+                tokio::select! {
+                    _ = dom.wait_for_work() => {}
+                    Some(()) = applied_rx.next() => {
+                        unapplied -= 1;
+                        if unapplied == 0 {
+                            dom.run_layout_effects();
+                        }
+                        continue;
+                    }
+                    json_value = event_rx.next() => match json_value {
+                        Some(json_value) => {
+                            if let Ok(value) = serde_json::from_value::<EventMessage>(json_value) {
+                                let name = value.event.clone();
+                                let el_id = ElementId(value.mounted_dom_id);
+                                if let Some(evt) = decode_event(value) {
+                                    dom.handle_event(&name,  evt, el_id,  dioxus_html::events::event_bubbles(&name));
+                                }
+                            }
+                        }
+                        // The window was closed
+                        None => break,
+                    }
+                }
+
+                let muts = dom
+                    .render_with_deadline(tokio::time::sleep(Duration::from_millis(16)))
+                    .await;
+
+                {
+                    let mut queue = edit_queue.lock().unwrap();
+                    queue.push(edits_script(&muts));
+                    if head.take_changed() {
+                        queue.push(head_script(&head, &desktop, &default_title));
+                    }
+                    queue.push(EDITS_APPLIED_SCRIPT.to_string());
+                    unapplied += 1;
+                }
+                desktop.send(EventData::EditsReady);
+            }
+        })
+    });
+}
+
+/// Runs in the webview after a batch of edits, to tell the VirtualDom they were applied
+const EDITS_APPLIED_SCRIPT: &str =
+    r#"window.ipc.postMessage(JSON.stringify({ method: "edits_applied", params: {} }))"#;
+
+/// The script that applies a set of mutations in the webview
+fn edits_script(edits: &Mutations) -> String {
+    format!(
+        "window.interpreter.handleEdits({})",
+        serde_json::to_string(edits).unwrap()
+    )
+}
+
+/// The script that replaces the managed elements in the document head with the current entries
+///
+/// The native window title doesn't follow `document.title`, so we also ask the event loop to update it. When no mounted
+/// component sets a title anymore, the window gets back the title it was opened with.
+fn head_script(head: &HeadContext, desktop: &DesktopContext, default_title: &str) -> String {
+    let mut script = format!(
+        "document.head.querySelectorAll('[{}]').forEach(function (el) {{ el.remove(); }});\
+        document.head.insertAdjacentHTML('beforeend', {});",
+        HEAD_MARKER,
+        serde_json::to_string(&head.render_elements()).unwrap()
+    );
+
+    let title = head.title().unwrap_or_else(|| default_title.to_string());
+    script.push_str(&format!(
+        "document.title = {};",
+        serde_json::to_string(&title).unwrap()
+    ));
+    desktop.set_title(&title);
+
+    script
+}
@@ -1,14 +1,16 @@
 use std::rc::Rc;
 
 use crate::controller::DesktopController;
-use dioxus_core::ScopeState;
+use dioxus_core::{Component, ScopeState, VirtualDom};
 use wry::application::event_loop::ControlFlow;
 use wry::application::event_loop::EventLoopProxy;
+use wry::application::event_loop::EventLoopWindowTarget;
 #[cfg(target_os = "ios")]
 use wry::application::platform::ios::WindowExtIOS;
 use wry::application::window::Fullscreen as WryFullscreen;
+use wry::application::window::{WindowBuilder, WindowId};
 
-use UserWindowEvent::*;
+use EventData::*;
 
 pub type ProxyType = EventLoopProxy<UserWindowEvent>;
 
@@ -45,11 +47,67 @@ pub fn use_eval(cx: &ScopeState) -> &Rc<dyn Fn(String)> {
 pub struct DesktopContext {
     /// The wry/tao proxy to the current window
     pub proxy: ProxyType,
+
+    /// The id of the current window
+    pub(crate) id: WindowId,
 }
 
 impl DesktopContext {
-    pub(crate) fn new(proxy: ProxyType) -> Self {
-        Self { proxy }
+    pub(crate) fn new(proxy: ProxyType, id: WindowId) -> Self {
+        Self { proxy, id }
+    }
+
+    /// Get the id of the current window
+    pub fn id(&self) -> WindowId {
+        self.id
+    }
+
+    pub(crate) fn send(&self, event: EventData) {
+        let _ = self.proxy.send_event(UserWindowEvent(event, self.id));
+    }
+
+    /// Open a new window running its own VirtualDom with the given app.
+    ///
+    /// The window is built from the window of the app's [`Config`](crate::Config), changed by the given closure. It
+    /// shares the resource directory, custom head and index of the app, but not its custom protocols or file drop
+    /// handler.
+    ///
+    /// ```rust, ignore
+    /// desktop.new_window(editor, |window| window.with_title("Untitled"));
+    /// ```
+    pub fn new_window(
+        &self,
+        app: Component,
+        window: impl FnOnce(WindowBuilder) -> WindowBuilder + Send + 'static,
+    ) {
+        self.new_window_with(app, window, |dom| dom);
+    }
+
+    /// Open a new window like [`new_window`](Self::new_window), with a closure that sets up the VirtualDom before
+    /// it's first rendered, like to add root contexts.
+    ///
+    /// The closure runs on the thread of the new VirtualDom, so contexts that can't be sent between threads are
+    /// created inside of it. With `dioxus-router`, this opens a window directly at a route:
+    ///
+    /// ```rust, ignore
+    /// desktop.new_window_with(app, |window| window.with_title("User 3"), |dom| {
+    ///     dom.with_root_context(RouterCfg {
+    ///         initial_url: Some("/users/3".to_string()),
+    ///         ..Default::default()
+    ///     })
+    /// });
+    /// ```
+    pub fn new_window_with(
+        &self,
+        app: Component,
+        window: impl FnOnce(WindowBuilder) -> WindowBuilder + Send + 'static,
+        init: impl FnOnce(VirtualDom) -> VirtualDom + Send + 'static,
+    ) {
+        self.send(NewWindow(Box::new(NewWindowRequest {
+            app,
+            window: Box::new(window),
+            init: Box::new(init),
+        })));
     }
 
     /// trigger the drag-window event
@@ -61,112 +119,119 @@ impl DesktopContext {
     /// onmousedown: move |_| { desktop.drag_window(); }
     /// ```
     pub fn drag(&self) {
-        let _ = self.proxy.send_event(DragWindow);
+        self.send(DragWindow);
     }
 
     /// set window minimize state
     pub fn set_minimized(&self, minimized: bool) {
-        let _ = self.proxy.send_event(Minimize(minimized));
+        self.send(Minimize(minimized));
     }
 
     /// set window maximize state
     pub fn set_maximized(&self, maximized: bool) {
-        let _ = self.proxy.send_event(Maximize(maximized));
+        self.send(Maximize(maximized));
     }
 
     /// toggle window maximize state
     pub fn toggle_maximized(&self) {
-        let _ = self.proxy.send_event(MaximizeToggle);
+        self.send(MaximizeToggle);
     }
 
     /// set window visible or not
     pub fn set_visible(&self, visible: bool) {
-        let _ = self.proxy.send_event(Visible(visible));
+        self.send(Visible(visible));
     }
 
     /// close window
     pub fn close(&self) {
-        let _ = self.proxy.send_event(CloseWindow);
+        self.send(CloseWindow);
     }
 
     /// set window to focus
     pub fn focus(&self) {
-        let _ = self.proxy.send_event(FocusWindow);
+        self.send(FocusWindow);
     }
 
     /// change window to fullscreen
     pub fn set_fullscreen(&self, fullscreen: bool) {
-        let _ = self.proxy.send_event(Fullscreen(fullscreen));
+        self.send(Fullscreen(fullscreen));
     }
 
     /// set resizable state
     pub fn set_resizable(&self, resizable: bool) {
-        let _ = self.proxy.send_event(Resizable(resizable));
+        self.send(Resizable(resizable));
     }
 
     /// set the window always on top
     pub fn set_always_on_top(&self, top: bool) {
-        let _ = self.proxy.send_event(AlwaysOnTop(top));
+        self.send(AlwaysOnTop(top));
     }
 
     /// set cursor visible or not
     pub fn set_cursor_visible(&self, visible: bool) {
-        let _ = self.proxy.send_event(CursorVisible(visible));
+        self.send(CursorVisible(visible));
     }
 
     /// set cursor grab
     pub fn set_cursor_grab(&self, grab: bool) {
-        let _ = self.proxy.send_event(CursorGrab(grab));
+        self.send(CursorGrab(grab));
     }
 
     /// set window title
     pub fn set_title(&self, title: &str) {
-        let _ = self.proxy.send_event(SetTitle(String::from(title)));
+        self.send(SetTitle(String::from(title)));
     }
 
     /// change window to borderless
     pub fn set_decorations(&self, decoration: bool) {
-        let _ = self.proxy.send_event(SetDecorations(decoration));
+        self.send(SetDecorations(decoration));
     }
 
     /// set window zoom level
     pub fn set_zoom_level(&self, scale_factor: f64) {
-        let _ = self.proxy.send_event(SetZoomLevel(scale_factor));
+        self.send(SetZoomLevel(scale_factor));
     }
 
     /// launch print modal
     pub fn print(&self) {
-        let _ = self.proxy.send_event(Print);
+        self.send(Print);
     }
 
     /// opens DevTool window
     pub fn devtool(&self) {
-        let _ = self.proxy.send_event(DevTool);
+        self.send(DevTool);
     }
 
     /// run (evaluate) a script in the WebView context
     pub fn eval(&self, script: impl std::string::ToString) {
-        let _ = self.proxy.send_event(Eval(script.to_string()));
+        self.send(Eval(script.to_string()));
     }
 
     /// Push view
     #[cfg(target_os = "ios")]
     pub fn push_view(&self, view: objc_id::ShareId<objc::runtime::Object>) {
-        let _ = self.proxy.send_event(PushView(view));
+        self.send(PushView(view));
     }
 
     /// Push view
     #[cfg(target_os = "ios")]
     pub fn pop_view(&self) {
-        let _ = self.proxy.send_event(PopView);
+        self.send(PopView);
     }
 }
 
+/// An event for the window with the given id
+#[derive(Debug)]
+pub struct UserWindowEvent(pub EventData, pub WindowId);
+
 #[derive(Debug)]
-pub enum UserWindowEvent {
+pub enum EventData {
     EditsReady,
     Initialize,
 
+    /// Open a new window with a VirtualDom of its own
+    NewWindow(Box<NewWindowRequest>),
+
     CloseWindow,
     DragWindow,
     FocusWindow,
@@ -203,28 +268,50 @@ pub enum UserWindowEvent {
     PopView,
 }
 
+/// A window to open, from [`DesktopContext::new_window_with`]
+pub struct NewWindowRequest {
+    app: Component,
+    window: Box<dyn FnOnce(WindowBuilder) -> WindowBuilder + Send>,
+    init: Box<dyn FnOnce(VirtualDom) -> VirtualDom + Send>,
+}
+
+impl std::fmt::Debug for NewWindowRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NewWindowRequest").finish_non_exhaustive()
+    }
+}
+
 impl DesktopController {
     pub(super) fn handle_event(
         &mut self,
         user_event: UserWindowEvent,
+        event_loop: &EventLoopWindowTarget<UserWindowEvent>,
         control_flow: &mut ControlFlow,
     ) {
-        // currently dioxus-desktop supports a single window only,
-        // so we can grab the only webview from the map;
+        let UserWindowEvent(event, id) = user_event;
+
+        if let NewWindow(request) = event {
+            let NewWindowRequest { app, window, init } = *request;
+            let window = window(self.cfg.window.clone());
+            self.open_window(event_loop, window, app, (), init);
+            return;
+        }
+
         // on wayland it is possible that a user event is emitted
         // before the webview is initialized. ignore the event.
-        let webview = if let Some(webview) = self.webviews.values().next() {
-            webview
+        let webview = if let Some(window) = self.windows.get(&id) {
+            &window.webview
         } else {
             return;
         };
 
         let window = webview.window();
 
-        match user_event {
-            Initialize | EditsReady => self.try_load_ready_webviews(),
+        match event {
+            NewWindow(_) => {}
+            Initialize | EditsReady => self.try_load_ready_webview(id),
             SetTemplate(template) => self.set_template(template),
-            CloseWindow => *control_flow = ControlFlow::Exit,
+            CloseWindow => self.close_window(id, control_flow),
             DragWindow => {
                 // if the drag_window has any errors, we don't do anything
                 window.fullscreen().is_none().then(|| window.drag_window());
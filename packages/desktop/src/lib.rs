@@ -16,8 +16,8 @@ mod hot_reload;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
-use desktop_context::UserWindowEvent;
 pub use desktop_context::{use_eval, use_window, DesktopContext};
+use desktop_context::{EventData, UserWindowEvent};
 use futures_channel::mpsc::UnboundedSender;
 pub use wry;
 pub use wry::application as tao;
@@ -102,25 +102,34 @@ pub fn launch_cfg(root: Component, config_builder: Config) {
 ///     })
 /// }
 /// ```
-pub fn launch_with_props<P: 'static + Send>(root: Component<P>, props: P, mut cfg: Config) {
+pub fn launch_with_props<P: 'static + Send>(root: Component<P>, props: P, cfg: Config) {
     let event_loop = EventLoop::with_user_event();
-    let mut desktop = DesktopController::new_on_tokio(root, props, event_loop.create_proxy());
+    let mut desktop = DesktopController::new(cfg, event_loop.create_proxy());
+    let mut app = Some((root, props));
 
     event_loop.run(move |window_event, event_loop, control_flow| {
         *control_flow = ControlFlow::Wait;
 
         match window_event {
-            Event::NewEvents(StartCause::Init) => desktop.start(&mut cfg, event_loop),
+            Event::NewEvents(StartCause::Init) => {
+                if let Some((root, props)) = app.take() {
+                    let window = desktop.cfg.window.clone();
+                    desktop.open_window(event_loop, window, root, props, |dom| dom);
+                }
+            }
 
             Event::WindowEvent {
                 event, window_id, ..
             } => match event {
-                WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
-                WindowEvent::Destroyed { .. } => desktop.close_window(window_id, control_flow),
+                WindowEvent::CloseRequested | WindowEvent::Destroyed { .. } => {
+                    desktop.close_window(window_id, control_flow)
+                }
                 _ => {}
             },
 
-            Event::UserEvent(user_event) => desktop.handle_event(user_event, control_flow),
+            Event::UserEvent(user_event) => {
+                desktop.handle_event(user_event, event_loop, control_flow)
+            }
             Event::MainEventsCleared => {}
             Event::Resumed => {}
             Event::Suspended => {}
@@ -131,32 +140,17 @@ pub fn launch_with_props<P: 'static + Send>(root: Component<P>, props: P, mut cf
     })
 }
 
-impl DesktopController {
-    fn start(
-        &mut self,
-        cfg: &mut Config,
-        event_loop: &tao::event_loop::EventLoopWindowTarget<UserWindowEvent>,
-    ) {
-        let webview = build_webview(
-            cfg,
-            event_loop,
-            self.is_ready.clone(),
-            self.proxy.clone(),
-            self.event_tx.clone(),
-        );
-
-        self.webviews.insert(webview.window().id(), webview);
-    }
-}
-
-fn build_webview(
+pub(crate) fn build_webview(
     cfg: &mut Config,
+    builder: WindowBuilder,
     event_loop: &tao::event_loop::EventLoopWindowTarget<UserWindowEvent>,
     is_ready: Arc<AtomicBool>,
     proxy: tao::event_loop::EventLoopProxy<UserWindowEvent>,
     event_tx: UnboundedSender<serde_json::Value>,
+    applied_tx: UnboundedSender<()>,
 ) -> wry::webview::WebView {
-    let builder = cfg.window.clone();
+    let default_icon = builder.window.window_icon.is_none();
+    let transparent = builder.window.transparent;
     let window = builder.build(event_loop).unwrap();
     let file_handler = cfg.file_drop_handler.take();
     let custom_head = cfg.custom_head.clone();
@@ -164,7 +158,7 @@ fn build_webview(
     let index_file = cfg.custom_index.clone();
 
     // We assume that if the icon is None in cfg, then the user just didnt set it
-    if cfg.window.window.window_icon.is_none() {
+    if default_icon {
         window.set_window_icon(Some(
             tao::window::Icon::from_rgba(
                 include_bytes!("./assets/default_icon.bin").to_vec(),
@@ -177,18 +171,22 @@ fn build_webview(
 
     let mut webview = WebViewBuilder::new(window)
         .unwrap()
-        .with_transparent(cfg.window.window.transparent)
+        .with_transparent(transparent)
         .with_url("dioxus://index.html/")
         .unwrap()
-        .with_ipc_handler(move |_window: &Window, payload: String| {
+        .with_ipc_handler(move |window: &Window, payload: String| {
             parse_ipc_message(&payload)
                 .map(|message| match message.method() {
                     "user_event" => {
                         _ = event_tx.unbounded_send(message.params());
                     }
+                    "edits_applied" => {
+                        _ = applied_tx.unbounded_send(());
+                    }
                     "initialize" => {
                         is_ready.store(true, std::sync::atomic::Ordering::Relaxed);
-                        let _ = proxy.send_event(UserWindowEvent::EditsReady);
+                        let _ =
+                            proxy.send_event(UserWindowEvent(EventData::EditsReady, window.id()));
                     }
                     "browser_open" => {
                         let data = message.params();
@@ -0,0 +1,131 @@
+use crate::{AtomId, AtomRoot, Readable, SelectorBuilder};
+use std::{future::Future, pin::Pin, rc::Rc};
+
+/// A value loaded asynchronously from other atoms and selectors.
+///
+/// The selector reads the atoms it depends on through its [`SelectorBuilder`] and returns the future that loads its
+/// value. The [`AtomRoot`] runs the future and keeps the result. When one of the dependencies is set, the value is
+/// loaded again. An async selector that doesn't read any atoms is an async atom, loaded once.
+///
+/// Reading the selector gives a [`Loadable`], and async components can wait for the value with
+/// [`use_read_async`](crate::use_read_async), which suspends them until it's loaded. Failures are part of the value,
+/// by loading a `Result`.
+///
+/// ```rust, ignore
+/// static USER_ID: Atom<u32> = |_| 1;
+/// static USER: AsyncSelector<Result<User, Error>> = |cx| {
+///     let id = *cx.get(USER_ID);
+///     Box::pin(async move { fetch_user(id).await })
+/// };
+///
+/// fn Profile(cx: Scope) -> Element {
+///     match use_read(cx, USER) {
+///         Loadable::Loading => cx.render(rsx!{ "loading..." }),
+///         Loadable::Ready(Ok(user)) | Loadable::Reloading(Ok(user)) => cx.render(rsx!{ "{user.name}" }),
+///         Loadable::Ready(Err(err)) | Loadable::Reloading(Err(err)) => cx.render(rsx!{ "{err}" }),
+///     }
+/// }
+/// ```
+pub type AsyncSelector<T> = fn(&SelectorBuilder) -> Pin<Box<dyn Future<Output = T>>>;
+
+/// The state of an [`AsyncSelector`].
+#[derive(Debug)]
+pub enum Loadable<T> {
+    /// The value is being loaded for the first time
+    Loading,
+
+    /// A dependency changed and the value is being loaded again, this is the previous value
+    Reloading(Rc<T>),
+
+    /// The value is loaded
+    Ready(Rc<T>),
+}
+
+impl<T> Loadable<T> {
+    /// Get the latest value that was loaded, even if a new value is being loaded.
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Loadable::Loading => None,
+            Loadable::Reloading(value) | Loadable::Ready(value) => Some(value),
+        }
+    }
+
+    /// Check whether a value is being loaded.
+    pub fn is_loading(&self) -> bool {
+        !matches!(self, Loadable::Ready(_))
+    }
+
+    pub(crate) fn value_rc(&self) -> Option<Rc<T>> {
+        match self {
+            Loadable::Loading => None,
+            Loadable::Reloading(value) | Loadable::Ready(value) => Some(value.clone()),
+        }
+    }
+}
+
+impl<T> Clone for Loadable<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Loadable::Loading => Loadable::Loading,
+            Loadable::Reloading(value) => Loadable::Reloading(value.clone()),
+            Loadable::Ready(value) => Loadable::Ready(value.clone()),
+        }
+    }
+}
+
+impl<T: 'static> Readable<Loadable<T>> for AsyncSelector<T> {
+    /// An async selector can't be loaded without a root, so it starts out loading.
+    fn init(&self) -> Loadable<T> {
+        Loadable::Loading
+    }
+
+    fn compute(&self, root: &AtomRoot) -> Loadable<T> {
+        load(*self, root);
+        Loadable::Loading
+    }
+
+    fn unique_id(&self, _root: &AtomRoot) -> AtomId {
+        AtomId {
+            ptr: *self as *const (),
+            type_id: std::any::TypeId::of::<Loadable<T>>(),
+            key: None,
+        }
+    }
+}
+
+/// Start loading an async selector in the root, tracking the atoms it reads
+fn load<T: 'static>(selector: AsyncSelector<T>, root: &AtomRoot) {
+    let builder = SelectorBuilder::new(Some(root));
+    let future = selector(&builder);
+    let id = selector.unique_id(root);
+
+    root.set_dependencies(
+        id,
+        builder.dependencies.into_inner(),
+        Rc::new(move |root| {
+            // Keep showing the previous value while the new one is loading
+            let reloading = match root.stored::<Loadable<T>>(id) {
+                Some(previous) => match previous.value_rc() {
+                    Some(value) => Loadable::Reloading(value),
+                    None => Loadable::Loading,
+                },
+                None => Loadable::Loading,
+            };
+            root.replace(id, reloading);
+            load(selector, root);
+            true
+        }),
+    );
+
+    root.spawn_load(id, async move { Loadable::Ready(Rc::new(future.await)) });
+}
+
+#[test]
+fn async_selector_compiles() {
+    static COUNT: crate::Atom<u32> = |_| 2;
+    static DOUBLED: AsyncSelector<u32> = |cx| {
+        let count = *cx.get(COUNT);
+        Box::pin(async move { count * 2 })
+    };
+    assert!(DOUBLED.init().is_loading());
+}
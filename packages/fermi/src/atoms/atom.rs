@@ -4,25 +4,19 @@ pub type Atom<T> = fn(AtomBuilder) -> T;
 pub struct AtomBuilder;
 
 impl<V: 'static> Readable<V> for Atom<V> {
-    fn read(&self, _root: AtomRoot) -> Option<V> {
-        todo!()
-    }
     fn init(&self) -> V {
         (*self)(AtomBuilder)
     }
-    fn unique_id(&self) -> AtomId {
+    fn unique_id(&self, _root: &AtomRoot) -> AtomId {
         AtomId {
             ptr: *self as *const (),
             type_id: std::any::TypeId::of::<V>(),
+            key: None,
         }
     }
 }
 
-impl<V: 'static> Writable<V> for Atom<V> {
-    fn write(&self, _root: AtomRoot, _value: V) {
-        todo!()
-    }
-}
+impl<V: 'static> Writable<V> for Atom<V> {}
 
 #[test]
 fn atom_compiles() {
@@ -1,28 +1,93 @@
 use crate::{AtomId, AtomRoot, Readable, Writable};
-use im_rc::HashMap as ImMap;
+use std::{any::Any, collections::HashMap, hash::Hash};
 
 pub struct AtomFamilyBuilder;
-pub type AtomFamily<K, V> = fn(AtomFamilyBuilder) -> ImMap<K, V>;
 
-impl<K, V: 'static> Readable<ImMap<K, V>> for AtomFamily<K, V> {
-    fn read(&self, _root: AtomRoot) -> Option<ImMap<K, V>> {
-        todo!()
+/// A family of atoms with an atom for every key.
+///
+/// The atom of a key is initialized from its key the first time it's read, and has its own value and subscribers,
+/// so writing one key only updates the components that read that key. The atom of a key is the family paired with
+/// the key, so it works anywhere an atom does:
+///
+/// ```rust, ignore
+/// static ROWS: AtomFamily<usize, Row> = |_, id| Row::new(*id);
+///
+/// fn RowView(cx: Scope<RowProps>) -> Element {
+///     let row = use_atom_family(cx, ROWS, cx.props.id);
+///     cx.render(rsx!{ "{row.label}" })
+/// }
+///
+/// static FIRST_LABEL: Selector<String> = |cx| cx.get((ROWS, 0)).label.clone();
+/// ```
+pub type AtomFamily<K, V> = fn(AtomFamilyBuilder, &K) -> V;
+
+impl<K: Hash + Eq + Clone + 'static, V: 'static> Readable<V> for (AtomFamily<K, V>, K) {
+    fn init(&self) -> V {
+        (self.0)(AtomFamilyBuilder, &self.1)
     }
 
-    fn init(&self) -> ImMap<K, V> {
-        (*self)(AtomFamilyBuilder)
+    fn unique_id(&self, root: &AtomRoot) -> AtomId {
+        root.family_id::<K, V>(self.0 as *const (), &self.1)
     }
+}
+
+impl<K: Hash + Eq + Clone + 'static, V: 'static> Writable<V> for (AtomFamily<K, V>, K) {}
+
+/// The keys of a family a root has seen, with the id the root gave the atom of every key
+pub(crate) struct FamilyKeys<K> {
+    ids: HashMap<K, u64>,
+    keys: HashMap<u64, K>,
+}
 
-    fn unique_id(&self) -> AtomId {
-        AtomId {
-            ptr: *self as *const (),
-            type_id: std::any::TypeId::of::<V>(),
+impl<K> Default for FamilyKeys<K> {
+    fn default() -> Self {
+        Self {
+            ids: HashMap::new(),
+            keys: HashMap::new(),
         }
     }
 }
 
-impl<K, V: 'static> Writable<ImMap<K, V>> for AtomFamily<K, V> {
-    fn write(&self, _root: AtomRoot, _value: ImMap<K, V>) {
-        todo!()
+impl<K: Hash + Eq + Clone> FamilyKeys<K> {
+    /// Get the id of a key, giving it the next id if the family doesn't know it yet
+    pub(crate) fn id(&mut self, key: &K, next: impl FnOnce() -> u64) -> u64 {
+        if let Some(id) = self.ids.get(key) {
+            return *id;
+        }
+
+        let id = next();
+        self.ids.insert(key.clone(), id);
+        self.keys.insert(id, key.clone());
+        id
     }
 }
+
+/// The keys of a family, whatever their type
+pub(crate) trait AnyFamilyKeys {
+    /// Forget the key with an id, so it gets a new one the next time it's read
+    fn forget(&mut self, id: u64);
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<K: Hash + Eq + 'static> AnyFamilyKeys for FamilyKeys<K> {
+    fn forget(&mut self, id: u64) {
+        if let Some(key) = self.keys.remove(&id) {
+            self.ids.remove(&key);
+        }
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[test]
+fn atom_family_compiles() {
+    static LABELS: AtomFamily<usize, String> = |_, id| format!("row {}", id);
+    assert_eq!((LABELS, 3).init(), "row 3");
+
+    let root = AtomRoot::new(std::sync::Arc::new(|_| {}));
+    assert_ne!((LABELS, 3).unique_id(&root), (LABELS, 4).unique_id(&root));
+    assert_eq!((LABELS, 3).unique_id(&root), (LABELS, 3).unique_id(&root));
+}
@@ -5,18 +5,15 @@ pub struct AtomRefBuilder;
 pub type AtomRef<T> = fn(AtomRefBuilder) -> T;
 
 impl<V: 'static> Readable<RefCell<V>> for AtomRef<V> {
-    fn read(&self, _root: AtomRoot) -> Option<RefCell<V>> {
-        todo!()
-    }
-
     fn init(&self) -> RefCell<V> {
         RefCell::new((*self)(AtomRefBuilder))
     }
 
-    fn unique_id(&self) -> AtomId {
+    fn unique_id(&self, _root: &AtomRoot) -> AtomId {
         AtomId {
             ptr: *self as *const (),
             type_id: std::any::TypeId::of::<V>(),
+            key: None,
         }
     }
 }
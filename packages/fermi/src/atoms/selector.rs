@@ -1 +1,84 @@
+use crate::{AtomId, AtomRoot, Readable};
+use std::{cell::RefCell, collections::HashSet, rc::Rc};
 
+/// A value derived from other atoms and selectors.
+///
+/// A selector reads the atoms it's computed from through its [`SelectorBuilder`], which tracks them as its
+/// dependencies. The [`AtomRoot`] keeps the result and only recomputes it when one of its dependencies is set. The
+/// subscribers of the selector are only updated when the result changed.
+///
+/// ```rust, ignore
+/// static COUNT: Atom<u32> = |_| 0;
+/// static DOUBLED: Selector<u32> = |cx| *cx.get(COUNT) * 2;
+///
+/// fn Doubled(cx: Scope) -> Element {
+///     let doubled = use_read(cx, DOUBLED);
+///     cx.render(rsx!{ "{doubled}" })
+/// }
+/// ```
+pub type Selector<T> = fn(&SelectorBuilder) -> T;
+
+/// Reads the values a [`Selector`] is computed from.
+pub struct SelectorBuilder<'a> {
+    root: Option<&'a AtomRoot>,
+    pub(crate) dependencies: RefCell<HashSet<AtomId>>,
+}
+
+impl<'a> SelectorBuilder<'a> {
+    pub(crate) fn new(root: Option<&'a AtomRoot>) -> Self {
+        Self {
+            root,
+            dependencies: RefCell::new(HashSet::new()),
+        }
+    }
+
+    /// Read the value of an atom or another selector, and recompute this selector when it changes.
+    pub fn get<V: 'static>(&self, atom: impl Readable<V>) -> Rc<V> {
+        match self.root {
+            Some(root) => {
+                self.dependencies.borrow_mut().insert(atom.unique_id(root));
+                root.read(atom)
+            }
+            None => Rc::new(atom.init()),
+        }
+    }
+}
+
+impl<V: PartialEq + 'static> Readable<V> for Selector<V> {
+    /// Compute the selector from the initial values of its dependencies.
+    fn init(&self) -> V {
+        (*self)(&SelectorBuilder::new(None))
+    }
+
+    fn compute(&self, root: &AtomRoot) -> V {
+        let builder = SelectorBuilder::new(Some(root));
+        let value = (*self)(&builder);
+
+        let selector = *self;
+        root.set_dependencies(
+            self.unique_id(root),
+            builder.dependencies.into_inner(),
+            Rc::new(move |root| {
+                let value = selector.compute(root);
+                root.replace_if_changed(selector.unique_id(root), value)
+            }),
+        );
+
+        value
+    }
+
+    fn unique_id(&self, _root: &AtomRoot) -> AtomId {
+        AtomId {
+            ptr: *self as *const (),
+            type_id: std::any::TypeId::of::<V>(),
+            key: None,
+        }
+    }
+}
+
+#[test]
+fn selector_compiles() {
+    static COUNT: crate::Atom<u32> = |_| 2;
+    static DOUBLED: Selector<u32> = |cx| *cx.get(COUNT) * 2;
+    assert_eq!(DOUBLED.init(), 4);
+}
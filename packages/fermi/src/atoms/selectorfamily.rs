@@ -1 +1,63 @@
+use crate::{AtomId, AtomRoot, Readable, SelectorBuilder};
+use std::{hash::Hash, rc::Rc};
 
+/// A family of selectors with a selector for every key.
+///
+/// The selector of a key is the family paired with the key. Every key tracks its own dependencies and keeps its own
+/// result, so it's only recomputed when an atom it read is set:
+///
+/// ```rust, ignore
+/// static ROWS: AtomFamily<usize, Row> = |_, id| Row::new(*id);
+/// static ROW_LABEL: SelectorFamily<usize, String> = |cx, id| cx.get((ROWS, *id)).label.to_uppercase();
+///
+/// fn RowLabel(cx: Scope<RowProps>) -> Element {
+///     let label = use_read(cx, (ROW_LABEL, cx.props.id));
+///     cx.render(rsx!{ "{label}" })
+/// }
+/// ```
+pub type SelectorFamily<K, V> = fn(&SelectorBuilder, &K) -> V;
+
+impl<K, V> Readable<V> for (SelectorFamily<K, V>, K)
+where
+    K: Hash + Eq + Clone + 'static,
+    V: PartialEq + 'static,
+{
+    /// Compute the selector of the key from the initial values of its dependencies.
+    fn init(&self) -> V {
+        (self.0)(&SelectorBuilder::new(None), &self.1)
+    }
+
+    fn compute(&self, root: &AtomRoot) -> V {
+        let builder = SelectorBuilder::new(Some(root));
+        let value = (self.0)(&builder, &self.1);
+
+        let selector = (self.0, self.1.clone());
+        root.set_dependencies(
+            self.unique_id(root),
+            builder.dependencies.into_inner(),
+            Rc::new(move |root| {
+                let value = selector.compute(root);
+                root.replace_if_changed(selector.unique_id(root), value)
+            }),
+        );
+
+        value
+    }
+
+    fn unique_id(&self, root: &AtomRoot) -> AtomId {
+        root.family_id::<K, V>(self.0 as *const (), &self.1)
+    }
+}
+
+#[test]
+fn selector_family_compiles() {
+    static LABELS: crate::AtomFamily<usize, String> = |_, id| format!("row {}", id);
+    static UPPERCASE: SelectorFamily<usize, String> = |cx, id| cx.get((LABELS, *id)).to_uppercase();
+    assert_eq!((UPPERCASE, 3).init(), "ROW 3");
+
+    let root = AtomRoot::new(std::sync::Arc::new(|_| {}));
+    assert_ne!(
+        (UPPERCASE, 3).unique_id(&root),
+        (UPPERCASE, 4).unique_id(&root)
+    );
+}
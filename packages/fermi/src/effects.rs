@@ -0,0 +1,138 @@
+use crate::{AtomId, AtomRoot, Readable};
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+
+/// Cleans up after an effect, before it runs again or when it's removed.
+pub type Cleanup = Box<dyn FnOnce()>;
+
+/// Identifies an effect added with [`AtomRoot::add_effect`], to remove it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EffectId(usize);
+
+type RunEffect = Box<dyn FnMut(&AtomRoot, &dyn Any) -> Option<Cleanup>>;
+
+struct Effect {
+    id: EffectId,
+    run: RefCell<RunEffect>,
+    cleanup: Cell<Option<Cleanup>>,
+}
+
+impl Effect {
+    fn clean_up(&self) {
+        if let Some(cleanup) = self.cleanup.take() {
+            cleanup();
+        }
+    }
+}
+
+/// The effects added to the atoms of a root
+#[derive(Default)]
+pub(crate) struct Effects {
+    next_id: Cell<usize>,
+    atoms: RefCell<HashMap<AtomId, Vec<Rc<Effect>>>>,
+}
+
+impl AtomRoot {
+    /// Run a callback with the value of an atom when it's initialized and every time it changes, like to log it, to
+    /// sync it to a backend or to validate it.
+    ///
+    /// If the atom is already initialized, the effect runs right away. The cleanup the effect returns is called before
+    /// it runs again, when the atom is removed and when the effect is removed. An effect can write to the atom it's
+    /// added to, which doesn't run it again.
+    ///
+    /// ```rust, ignore
+    /// root.add_effect(VOLUME, |root, volume| {
+    ///     if *volume > 100 {
+    ///         VOLUME.write(root, 100);
+    ///     }
+    ///     None
+    /// });
+    /// ```
+    pub fn add_effect<V: 'static>(
+        &self,
+        atom: impl Readable<V>,
+        mut effect: impl FnMut(&AtomRoot, &V) -> Option<Cleanup> + 'static,
+    ) -> EffectId {
+        let id = EffectId(self.effects.next_id.get());
+        self.effects.next_id.set(id.0 + 1);
+
+        let ptr = atom.unique_id(self);
+        self.effects
+            .atoms
+            .borrow_mut()
+            .entry(ptr)
+            .or_default()
+            .push(Rc::new(Effect {
+                id,
+                run: RefCell::new(Box::new(move |root, value| {
+                    effect(root, value.downcast_ref::<V>()?)
+                })),
+                cleanup: Cell::new(None),
+            }));
+
+        if self.atoms.borrow().contains_key(&ptr) {
+            self.run_effects(ptr);
+        }
+
+        id
+    }
+
+    /// Remove an effect, calling its cleanup.
+    pub fn remove_effect(&self, id: EffectId) {
+        let mut removed = None;
+
+        self.effects.atoms.borrow_mut().retain(|_, effects| {
+            if let Some(idx) = effects.iter().position(|effect| effect.id == id) {
+                removed = Some(effects.remove(idx));
+            }
+            !effects.is_empty()
+        });
+
+        if let Some(effect) = removed {
+            effect.clean_up();
+        }
+    }
+
+    /// Run the effects of a slot with its current value
+    pub(crate) fn run_effects(&self, ptr: AtomId) {
+        let effects = match self.effects.atoms.borrow().get(&ptr) {
+            Some(effects) => effects.clone(),
+            None => return,
+        };
+
+        let value = match self.atoms.borrow().get(&ptr) {
+            Some(slot) => slot.value.clone(),
+            None => return,
+        };
+
+        for effect in effects {
+            // An effect that's already running is writing to its own atom
+            if let Ok(mut run) = effect.run.try_borrow_mut() {
+                effect.clean_up();
+                let cleanup = run(self, value.as_ref());
+                effect.cleanup.set(cleanup);
+            }
+        }
+    }
+
+    /// Clean up the effects of a slot that was removed
+    pub(crate) fn clean_up_effects(&self, ptr: AtomId) {
+        let effects = match self.effects.atoms.borrow().get(&ptr) {
+            Some(effects) => effects.clone(),
+            None => return,
+        };
+
+        for effect in effects {
+            effect.clean_up();
+        }
+    }
+
+    /// Check whether a slot has effects observing it
+    pub(crate) fn has_effects(&self, ptr: AtomId) -> bool {
+        self.effects.atoms.borrow().contains_key(&ptr)
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{root::ExternalWrite, Writable};
+use futures_channel::mpsc::UnboundedSender;
+
+/// A handle to write to the atoms of an [`AtomRoot`](crate::AtomRoot) from any thread, created with
+/// [`AtomRoot::handle`](crate::AtomRoot::handle).
+///
+/// The writes are queued and applied on the thread of the app, in the order they were sent, waking the app up to
+/// render the components reading the atoms. Writes sent after the app is gone are dropped.
+///
+/// ```rust, ignore
+/// static MESSAGES: Atom<Vec<String>> = |_| Vec::new();
+///
+/// fn app(cx: Scope) -> Element {
+///     let root = use_init_atom_root(cx);
+///
+///     cx.use_hook(|| {
+///         let handle = root.handle();
+///         std::thread::spawn(move || {
+///             for message in listen_for_messages() {
+///                 handle.modify(MESSAGES, move |messages| {
+///                     let mut messages = messages.clone();
+///                     messages.push(message);
+///                     messages
+///                 });
+///             }
+///         });
+///     });
+///
+///     ...
+/// }
+/// ```
+#[derive(Clone)]
+pub struct AtomHandle {
+    writes: UnboundedSender<ExternalWrite>,
+}
+
+impl AtomHandle {
+    pub(crate) fn new(writes: UnboundedSender<ExternalWrite>) -> Self {
+        Self { writes }
+    }
+
+    /// Set the value of an atom.
+    pub fn set<V: Send + 'static>(&self, atom: impl Writable<V> + Send + 'static, value: V) {
+        self.send(Box::new(move |root| atom.write(root, value)));
+    }
+
+    /// Set the value of an atom to a value computed from its current value.
+    pub fn modify<V: 'static>(
+        &self,
+        atom: impl Writable<V> + Copy + Send + 'static,
+        f: impl FnOnce(&V) -> V + Send + 'static,
+    ) {
+        self.send(Box::new(move |root| {
+            let value = f(&root.read(atom));
+            atom.write(root, value)
+        }));
+    }
+
+    fn send(&self, write: ExternalWrite) {
+        // The root is gone along with the app
+        let _ = self.writes.unbounded_send(write);
+    }
+}
+
+impl std::fmt::Debug for AtomHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AtomHandle").finish_non_exhaustive()
+    }
+}
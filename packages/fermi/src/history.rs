@@ -0,0 +1,287 @@
+use crate::{AtomId, AtomRoot};
+use dioxus_core::ScopeId;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt::Debug,
+    rc::Rc,
+};
+
+/// The values of the atoms of an [`AtomRoot`] at some point, taken with [`AtomRoot::snapshot`].
+///
+/// Taking a snapshot is cheap: values aren't cloned, and snapshots share what they have in common. Values that are
+/// changed in place, like the value of an [`AtomRef`](crate::AtomRef), are shared with the root and aren't restored.
+#[derive(Clone, Default)]
+pub struct Snapshot {
+    values: im_rc::HashMap<AtomId, Rc<dyn Any>>,
+}
+
+impl Snapshot {
+    /// Check whether the snapshot has a value for an atom.
+    pub fn contains(&self, atom: AtomId) -> bool {
+        self.values.contains_key(&atom)
+    }
+
+    /// Get the value the snapshot has for an atom.
+    pub fn get<V: 'static>(&self, atom: AtomId) -> Option<Rc<V>> {
+        self.values.get(&atom)?.clone().downcast().ok()
+    }
+}
+
+impl Debug for Snapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Snapshot")
+            .field("atoms", &self.values.len())
+            .finish()
+    }
+}
+
+/// A write to an atom, recorded by an [`AtomRoot`] with a history.
+#[derive(Debug, Clone)]
+pub struct AtomWrite {
+    /// The atom that was written
+    pub atom: AtomId,
+
+    /// The value that was written, formatted with `Debug` if the type of the value is known to the root
+    pub value: Option<String>,
+
+    /// The component the write came from, if it came from a component
+    pub scope: Option<ScopeId>,
+}
+
+/// The writes recorded by a root, with the state after each of them
+#[derive(Default)]
+pub(crate) struct History {
+    start: Snapshot,
+    writes: Vec<(AtomWrite, Snapshot)>,
+
+    /// How many of the writes are applied, less than all of them after stepping back
+    position: usize,
+
+    /// The values of the atoms in the root
+    current: Snapshot,
+}
+
+/// Formats a value with `Debug`, if it's of the type the function was made for
+pub(crate) type DebugValue = fn(&dyn Any) -> Option<String>;
+
+fn debug<V: Debug + 'static>(value: &dyn Any) -> Option<String> {
+    Some(format!("{:?}", value.downcast_ref::<V>()?))
+}
+
+/// The types whose values a root formats with `Debug` before any are registered with [`AtomRoot::with_debug`]
+pub(crate) fn default_debug() -> HashMap<TypeId, DebugValue> {
+    let mut formatters = HashMap::new();
+
+    macro_rules! debug_types {
+        ($($ty:ty),*) => {
+            $(formatters.insert(TypeId::of::<$ty>(), debug::<$ty> as DebugValue);)*
+        };
+    }
+    debug_types!(
+        bool,
+        char,
+        u8,
+        u16,
+        u32,
+        u64,
+        u128,
+        usize,
+        i8,
+        i16,
+        i32,
+        i64,
+        i128,
+        isize,
+        f32,
+        f64,
+        String,
+        &'static str
+    );
+
+    formatters
+}
+
+impl AtomRoot {
+    /// Record every write to the atoms of the root, to step back and forward through them.
+    ///
+    /// Values of primitive types and strings are recorded with their `Debug` formatting. Other types are formatted once
+    /// they're registered with [`AtomRoot::with_debug`].
+    pub fn with_history(self) -> Self {
+        *self.history.borrow_mut() = Some(History::default());
+        self
+    }
+
+    /// Format the values of type `V` with `Debug` in the recorded writes. This can be called before or after
+    /// [`AtomRoot::with_history`].
+    pub fn with_debug<V: Debug + 'static>(mut self) -> Self {
+        self.debug.insert(TypeId::of::<V>(), debug::<V>);
+        self
+    }
+
+    /// Get the writes the root recorded, including the writes that were stepped back over.
+    pub fn writes(&self) -> Vec<AtomWrite> {
+        match self.history.borrow().as_ref() {
+            Some(history) => history
+                .writes
+                .iter()
+                .map(|(write, _)| write.clone())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get how many of the recorded writes are applied.
+    pub fn history_position(&self) -> usize {
+        self.history
+            .borrow()
+            .as_ref()
+            .map_or(0, |history| history.position)
+    }
+
+    /// Go back to the state before the last applied write, returning whether there was one.
+    pub fn step_back(&self) -> bool {
+        match self.history_position() {
+            0 => false,
+            position => self.travel_to(position - 1),
+        }
+    }
+
+    /// Apply the next recorded write again, returning whether there was one.
+    pub fn step_forward(&self) -> bool {
+        self.travel_to(self.history_position() + 1)
+    }
+
+    /// Go to the state after the given number of recorded writes, returning whether there were that many.
+    ///
+    /// Writing to an atom after stepping back drops the writes that were stepped back over.
+    pub fn travel_to(&self, position: usize) -> bool {
+        let snapshot = {
+            let mut history = self.history.borrow_mut();
+            let history = match history.as_mut() {
+                Some(history) => history,
+                None => return false,
+            };
+
+            let snapshot = match position {
+                0 => history.start.clone(),
+                _ => match history.writes.get(position - 1) {
+                    Some((_, snapshot)) => snapshot.clone(),
+                    None => return false,
+                },
+            };
+
+            history.position = position;
+            history.current = snapshot.clone();
+            snapshot
+        };
+
+        self.apply_snapshot(&snapshot);
+        true
+    }
+
+    /// Take a snapshot of the values of every atom in the root.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            values: self
+                .atoms
+                .borrow()
+                .iter()
+                .filter(|(id, _)| !self.is_selector(**id))
+                .map(|(id, slot)| (*id, slot.value.clone()))
+                .collect(),
+        }
+    }
+
+    /// Restore the atoms of the root to a snapshot, updating the components reading the ones that changed.
+    ///
+    /// Atoms that weren't initialized when the snapshot was taken go back to their initial value, and selectors are
+    /// recomputed. Restoring a snapshot isn't recorded as a write, it's where the writes recorded after it start from.
+    pub fn restore_snapshot(&self, snapshot: &Snapshot) {
+        if let Some(history) = self.history.borrow_mut().as_mut() {
+            history.writes.truncate(history.position);
+            history.current = snapshot.clone();
+        }
+
+        self.apply_snapshot(snapshot);
+    }
+
+    fn apply_snapshot(&self, snapshot: &Snapshot) {
+        let removed: Vec<AtomId> = self
+            .atoms
+            .borrow()
+            .keys()
+            .filter(|id| !self.is_selector(**id) && !snapshot.contains(**id))
+            .copied()
+            .collect();
+
+        for id in removed {
+            self.remove_slot(id);
+        }
+
+        for (id, value) in snapshot.values.iter() {
+            if self.replace_value(*id, value.clone()) {
+                self.notify(*id);
+
+                #[cfg(feature = "persist")]
+                self.persist(*id);
+
+                self.update_dependents(*id);
+            }
+        }
+    }
+
+    /// Keep track of the initial value of an atom, so it's part of the state of the history
+    pub(crate) fn record_initial(&self, id: AtomId, value: Rc<dyn Any>) {
+        if let Some(history) = self.history.borrow_mut().as_mut() {
+            history
+                .start
+                .values
+                .entry(id)
+                .or_insert_with(|| value.clone());
+            history.current.values.insert(id, value);
+        }
+    }
+
+    /// Record a write to an atom
+    pub(crate) fn record_write(&self, id: AtomId) {
+        let mut history = self.history.borrow_mut();
+        let history = match history.as_mut() {
+            Some(history) => history,
+            None => return,
+        };
+
+        // Selectors are derived from the atoms, so their values aren't part of the history
+        if self.is_selector(id) {
+            return;
+        }
+
+        let value = match self.atoms.borrow().get(&id) {
+            Some(slot) => slot.value.clone(),
+            None => return,
+        };
+
+        let write = AtomWrite {
+            atom: id,
+            value: self
+                .debug
+                .get(&(*value).type_id())
+                .and_then(|debug| debug(value.as_ref())),
+            scope: self.writing_scope.get(),
+        };
+        log::trace!("recorded write {:?}", write);
+
+        history.current.values.insert(id, value);
+        history.writes.truncate(history.position);
+        history.writes.push((write, history.current.clone()));
+        history.position += 1;
+    }
+
+    /// Attribute the writes made in `write` to a component
+    pub(crate) fn write_from<R>(&self, scope: ScopeId, write: impl FnOnce() -> R) -> R {
+        let previous = self.writing_scope.replace(Some(scope));
+        let result = write();
+        self.writing_scope.set(previous);
+        result
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{AtomFamily, AtomState, Readable};
+use dioxus_core::ScopeState;
+use std::hash::Hash;
+
+/// Store the state of one key of an [`AtomFamily`].
+///
+/// This works like [`use_atom_state`](crate::use_atom_state) for the atom of the key: the component only re-renders
+/// when the value of its key changes, not when other keys of the family do. The component can switch to a different
+/// key between renders.
+///
+/// ```ignore
+/// static ROWS: AtomFamily<usize, String> = |_, id| format!("row {}", id);
+///
+/// #[inline_props]
+/// fn Row(cx: Scope, id: usize) -> Element {
+///     let label = use_atom_family(cx, ROWS, *id);
+///
+///     cx.render(rsx! {
+///         input { value: "{label}", oninput: move |evt| label.set(evt.value.clone()) }
+///     })
+/// }
+/// ```
+///
+/// Keys that aren't needed anymore are removed with [`AtomRoot::remove`](crate::AtomRoot::remove).
+pub fn use_atom_family<K: Hash + Eq + Clone + 'static, V: 'static>(
+    cx: &ScopeState,
+    family: AtomFamily<K, V>,
+    key: K,
+) -> &AtomState<V> {
+    let root = crate::use_atom_root(cx);
+    let atom = (family, key);
+    let id = atom.unique_id(root);
+
+    let inner = cx.use_hook(|| AtomState {
+        value: None,
+        root: root.clone(),
+        scope_id: cx.scope_id(),
+        id,
+    });
+
+    // Stop listening to the key the component used to read
+    if inner.id != id {
+        inner.root.unsubscribe(inner.id, inner.scope_id);
+        inner.id = id;
+    }
+
+    inner.value = Some(inner.root.register(atom, cx.scope_id()));
+
+    inner
+}
@@ -20,13 +20,13 @@ pub fn use_atom_ref<T: 'static>(cx: &ScopeState, atom: AtomRef<T>) -> &UseAtomRe
         root.initialize(atom);
         (
             UseAtomRef {
-                ptr: atom.unique_id(),
+                ptr: atom.unique_id(root),
                 root: root.clone(),
                 scope_id: cx.scope_id(),
                 value: root.register(atom, cx.scope_id()),
             },
             AtomRefSubscription {
-                ptr: atom.unique_id(),
+                ptr: atom.unique_id(root),
                 root: root.clone(),
                 scope_id: cx.scope_id(),
             },
@@ -71,7 +71,8 @@ impl<T: 'static> UseAtomRef<T> {
     }
 
     pub fn write(&self) -> RefMut<T> {
-        self.root.force_update(self.ptr);
+        self.root
+            .write_from(self.scope_id, || self.root.force_update(self.ptr));
         self.value.borrow_mut()
     }
 
@@ -81,6 +82,7 @@ impl<T: 'static> UseAtomRef<T> {
 
     pub fn set(&self, new: T) {
         self.root.force_update(self.ptr);
-        self.root.set(self.ptr, new);
+        self.root
+            .write_from(self.scope_id, || self.root.set(self.ptr, new));
     }
 }
@@ -4,8 +4,24 @@ use std::rc::Rc;
 
 // Initializes the atom root and retuns it;
 pub fn use_init_atom_root(cx: &ScopeState) -> &Rc<AtomRoot> {
+    use_init_atom_root_with(cx, |root| root)
+}
+
+/// Initialize the atom root, configuring it before it's used.
+///
+/// ```rust, ignore
+/// use_init_atom_root_with(cx, |root| root.with_storage(LocalStorage));
+/// ```
+pub fn use_init_atom_root_with(
+    cx: &ScopeState,
+    init: impl FnOnce(AtomRoot) -> AtomRoot,
+) -> &Rc<AtomRoot> {
     cx.use_hook(|| match cx.consume_context::<Rc<AtomRoot>>() {
         Some(ctx) => ctx,
-        None => cx.provide_context(Rc::new(AtomRoot::new(cx.schedule_update_any()))),
+        None => {
+            let root = Rc::new(init(AtomRoot::new(cx.schedule_update_any())));
+            cx.push_future(root.clone().run());
+            cx.provide_context(root)
+        }
     })
 }
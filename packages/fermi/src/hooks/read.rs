@@ -22,13 +22,21 @@ pub fn use_read_rc<V: 'static>(cx: &ScopeState, f: impl Readable<V>) -> &Rc<V> {
         }
     }
 
+    let id = f.unique_id(root);
+
     let inner = cx.use_hook(|| UseReadInner {
         value: None,
         root: root.clone(),
         scope_id: cx.scope_id(),
-        id: f.unique_id(),
+        id,
     });
 
+    // The atom of a key of a family gets a new id once it's removed
+    if inner.id != id {
+        inner.root.unsubscribe(inner.id, inner.scope_id);
+        inner.id = id;
+    }
+
     let value = inner.root.register(f, cx.scope_id());
 
     inner.value = Some(value);
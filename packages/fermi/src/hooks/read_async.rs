@@ -0,0 +1,59 @@
+use crate::{use_atom_root, AsyncSelector, AtomId, AtomRoot, Loadable, Readable};
+use dioxus_core::{ScopeId, ScopeState};
+use std::{future::Future, rc::Rc, task::Poll};
+
+/// Wait for the value of an [`AsyncSelector`], suspending the component until it's loaded.
+///
+/// Once the value is loaded, the component is subscribed to the selector, so it renders again when the selector is
+/// loaded again. While a new value is loading, the previous one is used.
+///
+/// ```rust, ignore
+/// async fn Profile(cx: Scope<'_>) -> Element {
+///     let user = use_read_async(cx, USER).await;
+///     cx.render(rsx!{ "{user.name}" })
+/// }
+/// ```
+pub fn use_read_async<'a, T: 'static>(
+    cx: &'a ScopeState,
+    f: AsyncSelector<T>,
+) -> impl Future<Output = Rc<T>> + 'a {
+    let root = use_atom_root(cx);
+
+    struct UseReadAsyncInner {
+        root: Rc<AtomRoot>,
+        id: AtomId,
+        scope_id: ScopeId,
+    }
+
+    impl Drop for UseReadAsyncInner {
+        fn drop(&mut self) {
+            self.root.unsubscribe(self.id, self.scope_id)
+        }
+    }
+
+    let inner = cx.use_hook(|| UseReadAsyncInner {
+        root: root.clone(),
+        id: f.unique_id(root),
+        scope_id: cx.scope_id(),
+    });
+
+    // A suspended component can't be rendered again, so it only subscribes once the value is there
+    inner.root.initialize(f);
+    let (root, id, scope_id) = (&inner.root, inner.id, inner.scope_id);
+
+    std::future::poll_fn(move |task| {
+        match root
+            .stored::<Loadable<T>>(id)
+            .and_then(|loadable| loadable.value_rc())
+        {
+            Some(value) => {
+                root.register(f, scope_id);
+                Poll::Ready(value)
+            }
+            None => {
+                root.wait_for_load(id, task.waker().clone());
+                Poll::Pending
+            }
+        }
+    })
+}
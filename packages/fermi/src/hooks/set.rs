@@ -2,12 +2,15 @@ use crate::{use_atom_root, Writable};
 use dioxus_core::ScopeState;
 use std::rc::Rc;
 
-pub fn use_set<T: 'static>(cx: &ScopeState, f: impl Writable<T>) -> &Rc<dyn Fn(T)> {
+pub fn use_set<T: 'static>(cx: &ScopeState, f: impl Writable<T> + 'static) -> &Rc<dyn Fn(T)> {
     let root = use_atom_root(cx);
     cx.use_hook(|| {
-        let id = f.unique_id();
+        let scope_id = cx.scope_id();
         let root = root.clone();
-        root.initialize(f);
-        Rc::new(move |new| root.set(id, new)) as Rc<dyn Fn(T)>
+        root.value(&f);
+
+        // The id is looked up when writing, since the atom of a key of a family gets a new one once it's removed
+        Rc::new(move |new| root.write_from(scope_id, || root.set(f.unique_id(&root), new)))
+            as Rc<dyn Fn(T)>
     })
 }
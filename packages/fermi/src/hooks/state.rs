@@ -33,23 +33,31 @@ use std::{
 pub fn use_atom_state<T: 'static>(cx: &ScopeState, f: impl Writable<T>) -> &AtomState<T> {
     let root = crate::use_atom_root(cx);
 
+    let id = f.unique_id(root);
+
     let inner = cx.use_hook(|| AtomState {
         value: None,
         root: root.clone(),
         scope_id: cx.scope_id(),
-        id: f.unique_id(),
+        id,
     });
 
+    // The atom of a key of a family gets a new id once it's removed
+    if inner.id != id {
+        inner.root.unsubscribe(inner.id, inner.scope_id);
+        inner.id = id;
+    }
+
     inner.value = Some(inner.root.register(f, cx.scope_id()));
 
     inner
 }
 
 pub struct AtomState<V: 'static> {
-    root: Rc<AtomRoot>,
-    id: AtomId,
-    scope_id: ScopeId,
-    value: Option<Rc<V>>,
+    pub(crate) root: Rc<AtomRoot>,
+    pub(crate) id: AtomId,
+    pub(crate) scope_id: ScopeId,
+    pub(crate) value: Option<Rc<V>>,
 }
 
 impl<V> Drop for AtomState<V> {
@@ -61,7 +69,8 @@ impl<V> Drop for AtomState<V> {
 impl<T: 'static> AtomState<T> {
     /// Set the state to a new value.
     pub fn set(&self, new: T) {
-        self.root.set(self.id, new)
+        self.root
+            .write_from(self.scope_id, || self.root.set(self.id, new))
     }
 
     /// Get the current value of the state by cloning its container Rc.
@@ -114,7 +123,8 @@ impl<T: 'static> AtomState<T> {
     pub fn setter(&self) -> Rc<dyn Fn(T)> {
         let root = self.root.clone();
         let id = self.id;
-        Rc::new(move |new_val| root.set(id, new_val))
+        let scope_id = self.scope_id;
+        Rc::new(move |new_val| root.write_from(scope_id, || root.set(id, new_val)))
     }
 
     /// Set the state to a new value, using the current state value as a reference.
@@ -145,10 +155,8 @@ impl<T: 'static> AtomState<T> {
     /// }
     /// ```
     pub fn modify(&self, f: impl FnOnce(&T) -> T) {
-        self.root.clone().set(self.id, {
-            let current = self.value.as_ref().unwrap();
-            f(current.as_ref())
-        });
+        let current = self.value.as_ref().unwrap();
+        self.set(f(current.as_ref()));
     }
 
     /// Get the value of the state when this handle was created.
@@ -201,7 +209,8 @@ impl<T: 'static> AtomState<T> {
     /// }
     /// ```
     pub fn needs_update(&self) {
-        self.root.force_update(self.id)
+        self.root
+            .write_from(self.scope_id, || self.root.force_update(self.id))
     }
 }
 
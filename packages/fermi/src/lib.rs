@@ -4,19 +4,35 @@ pub mod prelude {
     pub use crate::*;
 }
 
+mod effects;
+mod handle;
+mod history;
 mod root;
 
+#[cfg(feature = "persist")]
+mod persist;
+
+use std::rc::Rc;
+
 pub use atoms::*;
+pub use effects::*;
+pub use handle::*;
+pub use history::*;
 pub use hooks::*;
 pub use root::*;
 
+#[cfg(feature = "persist")]
+pub use persist::*;
+
 mod atoms {
+    mod asyncselector;
     mod atom;
     mod atomfamily;
     mod atomref;
     mod selector;
     mod selectorfamily;
 
+    pub use asyncselector::*;
     pub use atom::*;
     pub use atomfamily::*;
     pub use atomref::*;
@@ -25,16 +41,20 @@ mod atoms {
 }
 
 pub mod hooks {
+    mod atom_family;
     mod atom_ref;
     mod atom_root;
     mod init_atom_root;
     mod read;
+    mod read_async;
     mod set;
     mod state;
+    pub use atom_family::*;
     pub use atom_ref::*;
     pub use atom_root::*;
     pub use init_atom_root::*;
     pub use read::*;
+    pub use read_async::*;
     pub use set::*;
     pub use state::*;
 }
@@ -44,9 +64,30 @@ pub mod hooks {
 /// This trait lets Dioxus abstract over Atoms, AtomFamilies, AtomRefs, and Selectors.
 /// It is not very useful for your own code, but could be used to build new Atom primitives.
 pub trait Readable<V> {
-    fn read(&self, root: AtomRoot) -> Option<V>;
+    /// Get the value stored in the root, if it was initialized.
+    fn read(&self, root: &AtomRoot) -> Option<Rc<V>>
+    where
+        V: 'static,
+    {
+        root.stored(self.unique_id(root))
+    }
+
     fn init(&self) -> V;
-    fn unique_id(&self) -> AtomId;
+
+    /// The id of the slot of this readable in the root.
+    ///
+    /// Atoms and selectors have the same id in every root, while a root gives the atom of a family's key an id the
+    /// first time it sees the key.
+    fn unique_id(&self, root: &AtomRoot) -> AtomId;
+
+    /// Compute the value this readable starts out with in the given root.
+    ///
+    /// Atoms start out with their `init` value, while selectors read the values they're derived from through the
+    /// root, which keeps track of them.
+    fn compute(&self, root: &AtomRoot) -> V {
+        let _ = root;
+        self.init()
+    }
 }
 
 /// All Atoms are `Writable` - they support writing their value.
@@ -54,5 +95,11 @@ pub trait Readable<V> {
 /// This trait lets Dioxus abstract over Atoms, AtomFamilies, AtomRefs, and Selectors.
 /// This trait lets Dioxus abstract over Atoms, AtomFamilies, AtomRefs, and Selectors
 pub trait Writable<V>: Readable<V> {
-    fn write(&self, root: AtomRoot, value: V);
+    /// Set the value in the root, updating its subscribers.
+    fn write(&self, root: &AtomRoot, value: V)
+    where
+        V: 'static,
+    {
+        root.set(self.unique_id(root), value)
+    }
 }
@@ -0,0 +1,266 @@
+use crate::{Atom, AtomBuilder, AtomId, AtomRoot, Readable, Writable};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use std::{any::Any, cell::RefCell, collections::HashMap};
+
+/// An atom whose value is saved to the [`Storage`] of the root, and restored from it when the app starts again.
+///
+/// The value is stored as JSON under the key of the atom, every time it's set. Until a root has a storage, persistent
+/// atoms work like any other atom.
+///
+/// ```rust, ignore
+/// static THEME: Persistent<Theme> = Persistent::new("theme", |_| Theme::Light);
+///
+/// fn app(cx: Scope) -> Element {
+///     use_init_atom_root_with(cx, |root| root.with_storage(FileStorage::new("settings")));
+///     let theme = use_atom_state(cx, THEME);
+///     ...
+/// }
+/// ```
+pub struct Persistent<V> {
+    key: &'static str,
+    atom: Atom<V>,
+}
+
+impl<V> Persistent<V> {
+    /// Create an atom stored under a key, starting out with the value of `atom` when nothing was stored yet.
+    pub const fn new(key: &'static str, atom: Atom<V>) -> Self {
+        Self { key, atom }
+    }
+}
+
+impl<V> Clone for Persistent<V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for Persistent<V> {}
+
+impl<V: Serialize + DeserializeOwned + 'static> Readable<V> for Persistent<V> {
+    fn init(&self) -> V {
+        (self.atom)(AtomBuilder)
+    }
+
+    /// Restore the value from the state the root was seeded with or from its storage.
+    fn compute(&self, root: &AtomRoot) -> V {
+        root.restore(self.unique_id(root), self.key)
+            .unwrap_or_else(|| self.init())
+    }
+
+    fn unique_id(&self, _root: &AtomRoot) -> AtomId {
+        AtomId {
+            ptr: self.atom as *const (),
+            type_id: std::any::TypeId::of::<V>(),
+            key: None,
+        }
+    }
+}
+
+impl<V: Serialize + DeserializeOwned + 'static> Writable<V> for Persistent<V> {
+    fn write(&self, root: &AtomRoot, value: V) {
+        // The root only saves the atoms it restored
+        root.initialize(*self);
+        root.set(self.unique_id(root), value)
+    }
+}
+
+/// Where the values of [`Persistent`] atoms are saved, as JSON strings.
+pub trait Storage {
+    /// Get the value stored under a key
+    fn get(&self, key: &str) -> Option<String>;
+
+    /// Store a value under a key, replacing the value stored before
+    fn set(&self, key: &str, value: &str);
+}
+
+/// Keeps values in memory, like for tests.
+///
+/// Clones share their values, so a test can keep a clone to look at what the app stored.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage {
+    values: std::rc::Rc<RefCell<HashMap<String, String>>>,
+}
+
+impl Storage for MemoryStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        self.values.borrow().get(key).cloned()
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        self.values
+            .borrow_mut()
+            .insert(key.to_string(), value.to_string());
+    }
+}
+
+/// Keeps every value in a JSON file of a directory, for desktop and TUI apps.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct FileStorage {
+    dir: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStorage {
+    /// Store the values in a directory, which is created when the first value is stored.
+    pub fn new(dir: impl Into<std::path::PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, key: &str) -> std::path::PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage for FileStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(self.path(key)).ok()
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        let saved =
+            std::fs::create_dir_all(&self.dir).and_then(|_| std::fs::write(self.path(key), value));
+
+        if let Err(err) = saved {
+            log::warn!("failed to save atom {:?}: {}", key, err);
+        }
+    }
+}
+
+/// Keeps values in the browser's `localStorage`.
+#[cfg(feature = "web")]
+#[derive(Debug, Clone, Default)]
+pub struct LocalStorage;
+
+#[cfg(feature = "web")]
+impl LocalStorage {
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+}
+
+#[cfg(feature = "web")]
+impl Storage for LocalStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        Self::storage()?.get_item(key).ok()?
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        let saved = Self::storage().map(|storage| storage.set_item(key, value));
+
+        if !matches!(saved, Some(Ok(()))) {
+            log::warn!("failed to save atom {:?} to the local storage", key);
+        }
+    }
+}
+
+/// The storage of a root and the persistent atoms it restored
+#[derive(Default)]
+pub(crate) struct Persistence {
+    storage: Option<Box<dyn Storage>>,
+    state: Map<String, Value>,
+    atoms: RefCell<HashMap<AtomId, PersistedAtom>>,
+}
+
+struct PersistedAtom {
+    key: &'static str,
+    serialize: fn(&dyn Any) -> Option<Value>,
+}
+
+fn serialize<V: Serialize + 'static>(value: &dyn Any) -> Option<Value> {
+    serde_json::to_value(value.downcast_ref::<V>()?).ok()
+}
+
+impl AtomRoot {
+    /// Save the values of [`Persistent`] atoms to a storage, and restore them from it.
+    pub fn with_storage(mut self, storage: impl Storage + 'static) -> Self {
+        self.persistence.storage = Some(Box::new(storage));
+        self
+    }
+
+    /// Seed the root with the state of [`Persistent`] atoms serialized with [`AtomRoot::serialize_state`], like to
+    /// hydrate an app with the state it was rendered with on the server.
+    ///
+    /// The atoms start out with the values of the state, before the values in the storage.
+    pub fn with_state(mut self, state: &str) -> Self {
+        match serde_json::from_str(state) {
+            Ok(state) => self.persistence.state = state,
+            Err(err) => log::warn!("failed to read the state of the atoms: {}", err),
+        }
+        self
+    }
+
+    /// Serialize the values of the [`Persistent`] atoms in the root, to seed another root with.
+    pub fn serialize_state(&self) -> String {
+        let atoms = self.atoms.borrow();
+        let state: Map<String, Value> = self
+            .persistence
+            .atoms
+            .borrow()
+            .iter()
+            .filter_map(|(id, atom)| {
+                let value = (atom.serialize)(atoms.get(id)?.value.as_ref())?;
+                Some((atom.key.to_string(), value))
+            })
+            .collect();
+
+        Value::Object(state).to_string()
+    }
+
+    /// Restore the value of a persistent atom, and save it whenever it changes from now on
+    fn restore<V: Serialize + DeserializeOwned + 'static>(
+        &self,
+        id: AtomId,
+        key: &'static str,
+    ) -> Option<V> {
+        self.persistence.atoms.borrow_mut().insert(
+            id,
+            PersistedAtom {
+                key,
+                serialize: serialize::<V>,
+            },
+        );
+
+        if let Some(value) = self.persistence.state.get(key) {
+            match serde_json::from_value(value.clone()) {
+                Ok(value) => return Some(value),
+                Err(err) => log::warn!("failed to restore atom {:?}: {}", key, err),
+            }
+        }
+
+        let stored = self.persistence.storage.as_ref()?.get(key)?;
+        match serde_json::from_str(&stored) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                log::warn!("failed to restore atom {:?}: {}", key, err);
+                None
+            }
+        }
+    }
+
+    /// Save the value of a slot to the storage, if it's a persistent atom
+    pub(crate) fn persist(&self, id: AtomId) {
+        let storage = match &self.persistence.storage {
+            Some(storage) => storage,
+            None => return,
+        };
+
+        let persisted = self.persistence.atoms.borrow();
+        let atom = match persisted.get(&id) {
+            Some(atom) => atom,
+            None => return,
+        };
+
+        let value = match self.atoms.borrow().get(&id) {
+            Some(slot) => (atom.serialize)(slot.value.as_ref()),
+            None => return,
+        };
+
+        match value {
+            Some(value) => storage.set(atom.key, &value.to_string()),
+            None => log::warn!("failed to serialize atom {:?}", atom.key),
+        }
+    }
+}
@@ -1,141 +1,621 @@
 use std::{
     any::{Any, TypeId},
-    cell::RefCell,
-    collections::HashMap,
+    cell::{Cell, RefCell},
+    collections::{hash_map::Entry, HashMap},
+    future::Future,
+    hash::Hash,
     rc::Rc,
     sync::Arc,
+    task::{Poll, Waker},
 };
 
 use dioxus_core::ScopeId;
+use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures_util::{future::LocalBoxFuture, stream::FuturesUnordered, StreamExt};
 use im_rc::HashSet;
 
-use crate::Readable;
+use crate::{
+    atoms::{AnyFamilyKeys, FamilyKeys},
+    effects::Effects,
+    history::{DebugValue, History},
+    AtomFamily, AtomHandle, Readable,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct AtomId {
     pub(crate) ptr: *const (),
     pub(crate) type_id: TypeId,
+
+    /// The id of the key within its family, for the atoms of an [`AtomFamily`]
+    pub(crate) key: Option<u64>,
 }
 
+// The pointer is only used to tell atoms apart, it's never dereferenced
+unsafe impl Send for AtomId {}
+unsafe impl Sync for AtomId {}
+
 pub struct AtomRoot {
     pub atoms: RefCell<HashMap<AtomId, Slot>>,
     pub update_any: Arc<dyn Fn(ScopeId)>,
+
+    /// The selectors that were computed in this root, with what they depend on
+    selectors: RefCell<HashMap<AtomId, SelectorState>>,
+
+    /// The values of async selectors that are being loaded, and the writes from other threads, run by
+    /// [`AtomRoot::run`]
+    loads: UnboundedSender<Load>,
+    writes: UnboundedSender<ExternalWrite>,
+    queues: RefCell<Option<(UnboundedReceiver<Load>, UnboundedReceiver<ExternalWrite>)>>,
+
+    /// The latest load of every async selector that's loading, so older loads that finish late are dropped
+    loading: RefCell<HashMap<AtomId, usize>>,
+    next_load: Cell<usize>,
+
+    /// The tasks waiting for an async selector to be loaded
+    waiters: RefCell<HashMap<AtomId, Vec<Waker>>>,
+
+    /// The keys of every family read from this root, by the family and the types of its keys and values. Ids aren't
+    /// reused, so a key that's forgotten gets a new atom the next time it's read.
+    family_keys: RefCell<HashMap<(*const (), TypeId), FamilyKeysOf>>,
+    next_family_key: Cell<u64>,
+
+    /// Where persistent atoms are saved
+    #[cfg(feature = "persist")]
+    pub(crate) persistence: crate::persist::Persistence,
+
+    /// The writes recorded since the history was enabled
+    pub(crate) history: RefCell<Option<History>>,
+
+    /// How the values of the atoms are formatted in the recorded writes, by their type
+    pub(crate) debug: HashMap<TypeId, DebugValue>,
+
+    /// The component the current write comes from
+    pub(crate) writing_scope: Cell<Option<ScopeId>>,
+
+    /// The effects run when atoms change
+    pub(crate) effects: Effects,
+
+    /// Whether slots are removed once nothing observes them, and the slots that are kept anyway
+    garbage_collection: bool,
+    keep_alive: RefCell<std::collections::HashSet<AtomId>>,
 }
 
 pub struct Slot {
     pub value: Rc<dyn Any>,
     pub subscribers: HashSet<ScopeId>,
+
+    /// The selectors that read this slot the last time they were computed
+    pub dependents: HashSet<AtomId>,
+}
+
+/// Recomputes a selector in the root, returning whether its value changed
+pub(crate) type Recompute = Rc<dyn Fn(&AtomRoot) -> bool>;
+
+/// Loads the value of an async selector, and stores it in the root once it's there
+type Load = LocalBoxFuture<'static, Box<dyn FnOnce(&AtomRoot)>>;
+
+/// The keys of a family, whatever the type of its keys
+type FamilyKeysOf = Box<dyn AnyFamilyKeys>;
+
+/// A write sent to the root from another thread
+pub(crate) type ExternalWrite = Box<dyn FnOnce(&AtomRoot) + Send>;
+
+struct SelectorState {
+    dependencies: std::collections::HashSet<AtomId>,
+    recompute: Recompute,
+}
+
+impl Slot {
+    fn new(value: Rc<dyn Any>) -> Self {
+        Self {
+            value,
+            subscribers: HashSet::new(),
+            dependents: HashSet::new(),
+        }
+    }
 }
 
 impl AtomRoot {
     pub fn new(update_any: Arc<dyn Fn(ScopeId)>) -> Self {
+        let (loads, pending_loads) = unbounded();
+        let (writes, pending_writes) = unbounded();
+
         Self {
             update_any,
             atoms: RefCell::new(HashMap::new()),
+            selectors: RefCell::new(HashMap::new()),
+            loads,
+            writes,
+            queues: RefCell::new(Some((pending_loads, pending_writes))),
+            loading: RefCell::new(HashMap::new()),
+            next_load: Cell::new(0),
+            waiters: RefCell::new(HashMap::new()),
+            family_keys: RefCell::new(HashMap::new()),
+            next_family_key: Cell::new(0),
+            #[cfg(feature = "persist")]
+            persistence: Default::default(),
+            history: RefCell::new(None),
+            debug: crate::history::default_debug(),
+            writing_scope: Cell::new(None),
+            effects: Effects::default(),
+            garbage_collection: false,
+            keep_alive: RefCell::new(std::collections::HashSet::new()),
         }
     }
 
+    /// Run the loads of the [`AsyncSelector`](crate::AsyncSelector)s read from this root, storing their values as
+    /// they finish, and apply the writes sent through its [`AtomHandle`](crate::AtomHandle)s.
+    ///
+    /// The root created by [`use_init_atom_root`](crate::use_init_atom_root) is already run by the app. A root used
+    /// outside of an app has to be run by whatever drives it. The returned future never finishes, and only the first
+    /// call runs the root.
+    pub fn run(self: Rc<Self>) -> impl Future<Output = ()> {
+        let mut queues = self.queues.borrow_mut().take();
+        let mut running = FuturesUnordered::new();
+
+        std::future::poll_fn(move |cx| {
+            let (loads, writes) = match queues.as_mut() {
+                Some(queues) => queues,
+                None => return Poll::Pending,
+            };
+
+            while let Poll::Ready(Some(write)) = writes.poll_next_unpin(cx) {
+                write(&self);
+            }
+
+            loop {
+                while let Poll::Ready(Some(load)) = loads.poll_next_unpin(cx) {
+                    running.push(load);
+                }
+
+                match running.poll_next_unpin(cx) {
+                    Poll::Ready(Some(finish)) => finish(&self),
+                    _ => return Poll::Pending,
+                }
+            }
+        })
+    }
+
     pub fn initialize<V: 'static>(&self, f: impl Readable<V>) {
-        let id = f.unique_id();
-        if self.atoms.borrow().get(&id).is_none() {
-            self.atoms.borrow_mut().insert(
-                id,
-                Slot {
-                    value: Rc::new(f.init()),
-                    subscribers: HashSet::new(),
-                },
-            );
-        }
+        self.value(&f);
     }
 
     pub fn register<V: 'static>(&self, f: impl Readable<V>, scope: ScopeId) -> Rc<V> {
-        let mut atoms = self.atoms.borrow_mut();
+        let value = self.value(&f);
 
-        // initialize the value if it's not already initialized
-        if let Some(slot) = atoms.get_mut(&f.unique_id()) {
+        if let Some(slot) = self.atoms.borrow_mut().get_mut(&f.unique_id(self)) {
             slot.subscribers.insert(scope);
-            match slot.value.clone().downcast() {
-                Ok(res) => res,
-                Err(e) => panic!(
-                    "Downcasting atom failed: {:?}. Has typeid of {:?} but needs typeid of {:?}",
-                    f.unique_id(),
-                    e.type_id(),
-                    TypeId::of::<V>()
-                ),
-            }
-        } else {
-            let value = Rc::new(f.init());
-            let mut subscribers = HashSet::new();
-            subscribers.insert(scope);
-
-            atoms.insert(
-                f.unique_id(),
-                Slot {
-                    value: value.clone(),
-                    subscribers,
-                },
-            );
-            value
         }
+
+        value
     }
 
     pub fn set<V: 'static>(&self, ptr: AtomId, value: V) {
-        let mut atoms = self.atoms.borrow_mut();
+        {
+            let mut atoms = self.atoms.borrow_mut();
 
-        if let Some(slot) = atoms.get_mut(&ptr) {
-            slot.value = Rc::new(value);
-            log::trace!("found item with subscribers {:?}", slot.subscribers);
-
-            for scope in &slot.subscribers {
-                log::trace!("updating subcsriber");
-                (self.update_any)(*scope);
+            if let Some(slot) = atoms.get_mut(&ptr) {
+                slot.value = Rc::new(value);
+                log::trace!("found item with subscribers {:?}", slot.subscribers);
+            } else {
+                log::trace!("no atoms found for {:?}", ptr);
+                atoms.insert(ptr, Slot::new(Rc::new(value)));
             }
-        } else {
-            log::trace!("no atoms found for {:?}", ptr);
-            atoms.insert(
-                ptr,
-                Slot {
-                    value: Rc::new(value),
-                    subscribers: HashSet::new(),
-                },
-            );
         }
+
+        self.notify(ptr);
+        self.record_write(ptr);
+
+        #[cfg(feature = "persist")]
+        self.persist(ptr);
+
+        self.update_dependents(ptr);
     }
 
     pub fn unsubscribe(&self, ptr: AtomId, scope: ScopeId) {
-        let mut atoms = self.atoms.borrow_mut();
-
-        if let Some(slot) = atoms.get_mut(&ptr) {
+        if let Some(slot) = self.atoms.borrow_mut().get_mut(&ptr) {
             slot.subscribers.remove(&scope);
         }
+
+        if self.garbage_collection {
+            self.collect(ptr);
+        }
+    }
+
+    /// Remove the slots of atoms and selectors once no component, selector or effect reads them anymore.
+    ///
+    /// Atoms that are removed go back to their initial value the next time they're read, unless they're marked with
+    /// [`AtomRoot::keep_alive`].
+    pub fn with_garbage_collection(mut self) -> Self {
+        self.garbage_collection = true;
+        self
+    }
+
+    /// Keep the value of an atom even when nothing reads it.
+    pub fn keep_alive<V>(&self, f: impl Readable<V>) {
+        self.keep_alive.borrow_mut().insert(f.unique_id(self));
+    }
+
+    /// Remove the slots no component, selector or effect reads, except the ones that are kept alive.
+    pub fn collect_garbage(&self) {
+        let ids: Vec<AtomId> = self.atoms.borrow().keys().copied().collect();
+
+        for id in ids {
+            self.collect(id);
+        }
+    }
+
+    /// Remove a slot if nothing reads it, and then the slots it was derived from
+    fn collect(&self, ptr: AtomId) {
+        let collectable = match self.atoms.borrow().get(&ptr) {
+            Some(slot) => slot.subscribers.is_empty() && slot.dependents.is_empty(),
+            None => false,
+        };
+
+        if !collectable || self.keep_alive.borrow().contains(&ptr) || self.has_effects(ptr) {
+            return;
+        }
+
+        let dependencies = self
+            .selectors
+            .borrow()
+            .get(&ptr)
+            .map(|state| state.dependencies.clone())
+            .unwrap_or_default();
+
+        log::trace!("collecting {:?}", ptr);
+        self.remove_slot(ptr);
+
+        for dependency in dependencies {
+            self.collect(dependency);
+        }
     }
 
     // force update of all subscribers
     pub fn force_update(&self, ptr: AtomId) {
-        if let Some(slot) = self.atoms.borrow_mut().get(&ptr) {
+        self.notify(ptr);
+
+        // The value may have been changed in place
+        self.record_write(ptr);
+
+        #[cfg(feature = "persist")]
+        self.persist(ptr);
+    }
+
+    /// Update the subscribers of a slot and run its effects
+    pub(crate) fn notify(&self, ptr: AtomId) {
+        if let Some(slot) = self.atoms.borrow().get(&ptr) {
             for scope in slot.subscribers.iter() {
                 log::trace!("updating subcsriber");
                 (self.update_any)(*scope);
             }
         }
+
+        self.run_effects(ptr);
     }
 
     pub fn read<V: 'static>(&self, f: impl Readable<V>) -> Rc<V> {
-        let mut atoms = self.atoms.borrow_mut();
+        self.value(&f)
+    }
+
+    /// Remove the value of an atom, like the atom of a key of an [`AtomFamily`] that isn't needed anymore.
+    ///
+    /// The subscribers of the atom and of the selectors derived from it are updated, and the atom is initialized
+    /// again the next time it's read.
+    pub fn remove<V: 'static>(&self, f: impl Readable<V>) {
+        self.remove_slot(f.unique_id(self));
+    }
+
+    /// Remove the atoms of every key of an [`AtomFamily`].
+    pub fn remove_family<K, V: 'static>(&self, family: AtomFamily<K, V>) {
+        let ptr = family as *const ();
+        let ids: Vec<AtomId> = self
+            .atoms
+            .borrow()
+            .keys()
+            .filter(|id| id.ptr == ptr && id.key.is_some())
+            .copied()
+            .collect();
+
+        for id in ids {
+            self.remove_slot(id);
+        }
+    }
 
-        // initialize the value if it's not already initialized
-        if let Some(slot) = atoms.get_mut(&f.unique_id()) {
-            slot.value.clone().downcast().unwrap()
-        } else {
-            let value = Rc::new(f.init());
-            atoms.insert(
-                f.unique_id(),
-                Slot {
-                    value: value.clone(),
-                    subscribers: HashSet::new(),
+    pub(crate) fn remove_slot(&self, ptr: AtomId) {
+        let slot = match self.atoms.borrow_mut().remove(&ptr) {
+            Some(slot) => slot,
+            None => return,
+        };
+        self.forget_family_key(ptr);
+        self.loading.borrow_mut().remove(&ptr);
+
+        // A selector doesn't depend on anything anymore
+        if let Some(state) = self.selectors.borrow_mut().remove(&ptr) {
+            let mut atoms = self.atoms.borrow_mut();
+            for dependency in &state.dependencies {
+                if let Some(slot) = atoms.get_mut(dependency) {
+                    slot.dependents.remove(&ptr);
+                }
+            }
+        }
+
+        for scope in &slot.subscribers {
+            (self.update_any)(*scope);
+        }
+        self.clean_up_effects(ptr);
+
+        // Selectors derived from the atom are computed again when they're read
+        for selector in slot.dependents {
+            self.remove_slot(selector);
+        }
+    }
+
+    /// Get the id of the atom of a key of a family, giving the key an id the first time the root sees it.
+    ///
+    /// Keys are compared with `Eq`, not by their hash, so keys whose hashes collide still get their own atom.
+    pub(crate) fn family_id<K: Hash + Eq + Clone + 'static, V: 'static>(
+        &self,
+        family: *const (),
+        key: &K,
+    ) -> AtomId {
+        let mut families = self.family_keys.borrow_mut();
+        let keys = families
+            .entry((family, TypeId::of::<(K, V)>()))
+            .or_insert_with(|| Box::new(FamilyKeys::<K>::default()));
+        let keys = keys
+            .as_any_mut()
+            .downcast_mut::<FamilyKeys<K>>()
+            .expect("the keys of a family to be of the type of its keys");
+
+        let id = keys.id(key, || {
+            let id = self.next_family_key.get();
+            self.next_family_key.set(id + 1);
+            id
+        });
+
+        AtomId {
+            ptr: family,
+            type_id: TypeId::of::<V>(),
+            key: Some(id),
+        }
+    }
+
+    /// Forget the key of the atom of a family once its slot is removed, unless something still refers to its id
+    fn forget_family_key(&self, ptr: AtomId) {
+        let id = match ptr.key {
+            Some(id) => id,
+            None => return,
+        };
+
+        if self.keep_alive.borrow().contains(&ptr)
+            || self.has_effects(ptr)
+            || self.history.borrow().is_some()
+        {
+            return;
+        }
+
+        for ((family, _), keys) in self.family_keys.borrow_mut().iter_mut() {
+            if *family == ptr.ptr {
+                keys.forget(id);
+            }
+        }
+    }
+
+    /// Get the value of a slot, if it was initialized
+    pub(crate) fn stored<V: 'static>(&self, ptr: AtomId) -> Option<Rc<V>> {
+        let atoms = self.atoms.borrow();
+        let slot = atoms.get(&ptr)?;
+        Some(downcast(ptr, slot.value.clone()))
+    }
+
+    /// Get a handle to write to the atoms of the root from any thread.
+    pub fn handle(&self) -> AtomHandle {
+        AtomHandle::new(self.writes.clone())
+    }
+
+    /// Replace the value of a slot without updating its subscribers
+    pub(crate) fn replace<V: 'static>(&self, ptr: AtomId, value: V) {
+        if let Some(slot) = self.atoms.borrow_mut().get_mut(&ptr) {
+            slot.value = Rc::new(value);
+        }
+    }
+
+    /// Put a value back into a slot without updating its subscribers, returning whether it's a different value
+    pub(crate) fn replace_value(&self, ptr: AtomId, value: Rc<dyn Any>) -> bool {
+        match self.atoms.borrow_mut().entry(ptr) {
+            Entry::Occupied(slot) if Rc::ptr_eq(&slot.get().value, &value) => false,
+            Entry::Occupied(mut slot) => {
+                slot.get_mut().value = value;
+                true
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(Slot::new(value));
+                false
+            }
+        }
+    }
+
+    /// Check whether a slot holds the value of a selector
+    pub(crate) fn is_selector(&self, ptr: AtomId) -> bool {
+        self.selectors.borrow().contains_key(&ptr)
+    }
+
+    /// Load the value of an async selector, dropping any load of it that's still running.
+    pub(crate) fn spawn_load<V: 'static>(
+        &self,
+        ptr: AtomId,
+        load: impl Future<Output = V> + 'static,
+    ) {
+        let generation = self.next_load.get();
+        self.next_load.set(generation + 1);
+        self.loading.borrow_mut().insert(ptr, generation);
+
+        let load: Load = Box::pin(async move {
+            let value = load.await;
+            Box::new(move |root: &AtomRoot| root.finish_load(ptr, generation, value))
+                as Box<dyn FnOnce(&AtomRoot)>
+        });
+
+        // The receiving end lives as long as the root
+        let _ = self.loads.unbounded_send(load);
+    }
+
+    fn finish_load<V: 'static>(&self, ptr: AtomId, generation: usize, value: V) {
+        {
+            let mut loading = self.loading.borrow_mut();
+            match loading.get(&ptr) {
+                Some(latest) if *latest == generation => loading.remove(&ptr),
+                _ => return,
+            };
+        }
+
+        self.set(ptr, value);
+
+        for waker in self.waiters.borrow_mut().remove(&ptr).unwrap_or_default() {
+            waker.wake();
+        }
+    }
+
+    /// Wake a task the next time an async selector finishes loading
+    pub(crate) fn wait_for_load(&self, ptr: AtomId, waker: Waker) {
+        self.waiters
+            .borrow_mut()
+            .entry(ptr)
+            .or_default()
+            .push(waker);
+    }
+
+    /// Get the value of a slot, initializing it if it's not already initialized
+    pub(crate) fn value<V: 'static>(&self, f: &impl Readable<V>) -> Rc<V> {
+        let id = f.unique_id(self);
+
+        if let Some(slot) = self.atoms.borrow().get(&id) {
+            return downcast(id, slot.value.clone());
+        }
+
+        // The atoms aren't borrowed while computing the value, since selectors read other atoms through the root
+        let value = Rc::new(f.compute(self));
+        self.atoms
+            .borrow_mut()
+            .entry(id)
+            .or_insert_with(|| Slot::new(value.clone()));
+
+        if !self.is_selector(id) {
+            self.record_initial(id, value.clone());
+        }
+        self.run_effects(id);
+
+        value
+    }
+
+    /// Store the atoms a selector read while it was computed, and how to recompute it when one of them changes.
+    pub(crate) fn set_dependencies(
+        &self,
+        selector: AtomId,
+        dependencies: std::collections::HashSet<AtomId>,
+        recompute: Recompute,
+    ) {
+        let previous = self
+            .selectors
+            .borrow_mut()
+            .insert(
+                selector,
+                SelectorState {
+                    dependencies: dependencies.clone(),
+                    recompute,
                 },
-            );
-            value
+            )
+            .map(|state| state.dependencies)
+            .unwrap_or_default();
+
+        let mut atoms = self.atoms.borrow_mut();
+        for dependency in previous.difference(&dependencies) {
+            if let Some(slot) = atoms.get_mut(dependency) {
+                slot.dependents.remove(&selector);
+            }
+        }
+        for dependency in &dependencies {
+            if let Some(slot) = atoms.get_mut(dependency) {
+                slot.dependents.insert(selector);
+            }
         }
     }
+
+    /// Replace the value of a selector, returning whether it's different from the value it had.
+    pub(crate) fn replace_if_changed<V: PartialEq + 'static>(
+        &self,
+        selector: AtomId,
+        value: V,
+    ) -> bool {
+        let mut atoms = self.atoms.borrow_mut();
+
+        match atoms.get_mut(&selector) {
+            Some(slot) if downcast::<V>(selector, slot.value.clone()).as_ref() == &value => false,
+            Some(slot) => {
+                slot.value = Rc::new(value);
+                true
+            }
+            None => {
+                atoms.insert(selector, Slot::new(Rc::new(value)));
+                true
+            }
+        }
+    }
+
+    /// Recompute the selectors that depend on a slot, updating the subscribers of the ones whose value changed.
+    ///
+    /// Selectors are recomputed after everything they depend on, so they never see a stale value and run at most once.
+    pub(crate) fn update_dependents(&self, ptr: AtomId) {
+        let mut order = Vec::new();
+        self.collect_dependents(ptr, &mut std::collections::HashSet::new(), &mut order);
+
+        let mut changed = std::collections::HashSet::new();
+        changed.insert(ptr);
+
+        for selector in order.into_iter().rev() {
+            let recompute = match self.selectors.borrow().get(&selector) {
+                Some(state) if !state.dependencies.is_disjoint(&changed) => state.recompute.clone(),
+                _ => continue,
+            };
+
+            if recompute(self) {
+                log::trace!("selector {:?} changed", selector);
+                changed.insert(selector);
+                self.notify(selector);
+            }
+        }
+    }
+
+    /// Collect every selector that depends on a slot, directly or not, in depth-first post-order
+    fn collect_dependents(
+        &self,
+        ptr: AtomId,
+        visited: &mut std::collections::HashSet<AtomId>,
+        order: &mut Vec<AtomId>,
+    ) {
+        let dependents: Vec<AtomId> = match self.atoms.borrow().get(&ptr) {
+            Some(slot) => slot.dependents.iter().copied().collect(),
+            None => return,
+        };
+
+        for selector in dependents {
+            if visited.insert(selector) {
+                self.collect_dependents(selector, visited, order);
+                order.push(selector);
+            }
+        }
+    }
+}
+
+fn downcast<V: 'static>(id: AtomId, value: Rc<dyn Any>) -> Rc<V> {
+    match value.downcast() {
+        Ok(res) => res,
+        Err(e) => panic!(
+            "Downcasting atom failed: {:?}. Has typeid of {:?} but needs typeid of {:?}",
+            id,
+            (*e).type_id(),
+            TypeId::of::<V>()
+        ),
+    }
 }
@@ -0,0 +1,113 @@
+mod common;
+
+use dioxus::prelude::*;
+use dioxus_core::{Mutation, ScopeId};
+use fermi::*;
+use std::{
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Create a root that records the scopes it updates, and run its loads
+fn root() -> (Rc<AtomRoot>, Arc<Mutex<Vec<ScopeId>>>) {
+    let (root, updated) = common::root();
+    let root = Rc::new(root);
+    tokio::task::spawn_local(root.clone().run());
+    (root, updated)
+}
+
+static USER_ID: Atom<u32> = |_| 1;
+static USER: AsyncSelector<String> = |cx| {
+    let id = *cx.get(USER_ID);
+    Box::pin(async move {
+        // The second user takes a while to load
+        if id == 2 {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        tokio::task::yield_now().await;
+        format!("user {}", id)
+    })
+};
+
+/// Let the loads that don't sleep finish
+async fn settle() {
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+}
+
+#[tokio::test]
+async fn loads_values_and_reloads_them_when_dependencies_change() {
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            let (root, updated) = root();
+            assert!(matches!(
+                *root.register(USER, ScopeId(1)),
+                Loadable::Loading
+            ));
+
+            settle().await;
+            assert!(matches!(&*root.read(USER), Loadable::Ready(user) if **user == "user 1"));
+            assert_eq!(*updated.lock().unwrap(), [ScopeId(1)]);
+
+            // The previous value is kept while the new one is loading
+            root.set(USER_ID.unique_id(&root), 3u32);
+            assert!(matches!(&*root.read(USER), Loadable::Reloading(user) if **user == "user 1"));
+
+            settle().await;
+            assert!(matches!(&*root.read(USER), Loadable::Ready(user) if **user == "user 3"));
+            assert_eq!(*updated.lock().unwrap(), [ScopeId(1); 3]);
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn drops_loads_that_finish_after_a_newer_one() {
+    tokio::task::LocalSet::new()
+        .run_until(async {
+            let (root, _) = root();
+            root.initialize(USER);
+            settle().await;
+
+            root.set(USER_ID.unique_id(&root), 2u32);
+            root.set(USER_ID.unique_id(&root), 3u32);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+
+            assert!(matches!(&*root.read(USER), Loadable::Ready(user) if **user == "user 3"));
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn suspends_components_until_loaded() {
+    fn app(cx: Scope) -> Element {
+        use_init_atom_root(cx);
+        cx.render(rsx!(user_profile {}))
+    }
+
+    async fn user_profile(cx: Scope<'_>) -> Element {
+        let user = use_read_async(cx, USER).await;
+        cx.render(rsx!(div { "{user}" }))
+    }
+
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+
+    let mut rendered = Vec::new();
+    while rendered.is_empty() {
+        tokio::time::timeout(Duration::from_secs(1), dom.wait_for_work())
+            .await
+            .expect("the user was never loaded");
+
+        for edit in dom.render_immediate().edits {
+            if let Mutation::CreateTextNode { value, .. } | Mutation::HydrateText { value, .. } =
+                edit
+            {
+                rendered.push(value.to_string());
+            }
+        }
+    }
+
+    assert_eq!(rendered, ["user 1"]);
+}
@@ -0,0 +1,15 @@
+//! Helpers shared by the integration tests
+
+use dioxus_core::ScopeId;
+use fermi::AtomRoot;
+use std::sync::{Arc, Mutex};
+
+/// Create a root that records the scopes it updates
+pub fn root() -> (AtomRoot, Arc<Mutex<Vec<ScopeId>>>) {
+    let updated = Arc::new(Mutex::new(Vec::new()));
+    let root = AtomRoot::new(Arc::new({
+        let updated = updated.clone();
+        move |scope| updated.lock().unwrap().push(scope)
+    }));
+    (root, updated)
+}
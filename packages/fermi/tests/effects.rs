@@ -0,0 +1,88 @@
+mod common;
+
+use dioxus_core::ScopeId;
+use fermi::*;
+use std::{cell::RefCell, rc::Rc};
+
+static VOLUME: Atom<u32> = |_| 50;
+static COUNT: Atom<u32> = |_| 0;
+static DOUBLED: Selector<u32> = |cx| *cx.get(COUNT) * 2;
+
+#[test]
+fn runs_effects_on_initialization_and_changes() {
+    let (root, _) = common::root();
+    let log = Rc::new(RefCell::new(Vec::new()));
+
+    let effect = root.add_effect(VOLUME, {
+        let log = log.clone();
+        move |_, volume| {
+            log.borrow_mut().push(format!("run {}", volume));
+            let log = log.clone();
+            let volume = *volume;
+            Some(Box::new(move || log.borrow_mut().push(format!("clean up {}", volume))) as Cleanup)
+        }
+    });
+    assert!(log.borrow().is_empty());
+
+    root.initialize(VOLUME);
+    VOLUME.write(&root, 60);
+    root.remove_effect(effect);
+    VOLUME.write(&root, 70);
+
+    assert_eq!(
+        *log.borrow(),
+        ["run 50", "clean up 50", "run 60", "clean up 60"]
+    );
+}
+
+#[test]
+fn effects_can_validate_writes() {
+    let (root, updated) = common::root();
+    root.register(VOLUME, ScopeId(1));
+
+    root.add_effect(VOLUME, |root, volume| {
+        if *volume > 100 {
+            VOLUME.write(root, 100);
+        }
+        None
+    });
+
+    VOLUME.write(&root, 150);
+    assert_eq!(*root.read(VOLUME), 100);
+    assert_eq!(updated.lock().unwrap().last(), Some(&ScopeId(1)));
+}
+
+#[test]
+fn collects_slots_nothing_reads() {
+    let (root, _) = common::root();
+    let root = root.with_garbage_collection();
+    root.keep_alive(VOLUME);
+
+    assert_eq!(*root.register(DOUBLED, ScopeId(1)), 0);
+    root.register(VOLUME, ScopeId(1));
+    COUNT.write(&root, 2);
+    VOLUME.write(&root, 10);
+
+    // The atom is still read by the selector
+    root.unsubscribe(COUNT.unique_id(&root), ScopeId(1));
+    assert_eq!(*root.read(COUNT), 2);
+
+    // Nothing reads the selector or the atom it's derived from anymore
+    root.unsubscribe(DOUBLED.unique_id(&root), ScopeId(1));
+    root.unsubscribe(VOLUME.unique_id(&root), ScopeId(1));
+    assert_eq!(*root.read(COUNT), 0);
+    assert_eq!(*root.read(VOLUME), 10);
+}
+
+#[test]
+fn keeps_slots_without_garbage_collection() {
+    let (root, _) = common::root();
+    root.register(COUNT, ScopeId(1));
+    COUNT.write(&root, 2);
+
+    root.unsubscribe(COUNT.unique_id(&root), ScopeId(1));
+    assert_eq!(*root.read(COUNT), 2);
+
+    root.collect_garbage();
+    assert_eq!(*root.read(COUNT), 0);
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use dioxus_core::ScopeId;
+use fermi::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn keys_have_their_own_subscribers() {
+    static ROWS: AtomFamily<usize, String> = |_, id| format!("row {}", id);
+
+    let (root, updated) = common::root();
+    for id in 0..5000 {
+        assert_eq!(
+            *root.register((ROWS, id), ScopeId(id)),
+            format!("row {}", id)
+        );
+    }
+
+    (ROWS, 42).write(&root, "edited".to_string());
+    assert_eq!(*root.read((ROWS, 42)), "edited");
+    assert_eq!(*root.read((ROWS, 43)), "row 43");
+    assert_eq!(*updated.lock().unwrap(), [ScopeId(42)]);
+}
+
+#[test]
+fn initializes_keys_lazily() {
+    static INITIALIZED: AtomicUsize = AtomicUsize::new(0);
+    static SQUARES: AtomFamily<u64, u64> = |_, n| {
+        INITIALIZED.fetch_add(1, Ordering::SeqCst);
+        n * n
+    };
+    static SUM: Selector<u64> = |cx| *cx.get((SQUARES, 3)) + *cx.get((SQUARES, 4));
+
+    let (root, _) = common::root();
+    assert_eq!((SQUARES, 3).read(&root), None);
+
+    assert_eq!(*root.read(SUM), 25);
+    assert_eq!(INITIALIZED.load(Ordering::SeqCst), 2);
+
+    (SQUARES, 4).write(&root, 0);
+    assert_eq!(*root.read(SUM), 9);
+    assert_eq!(INITIALIZED.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn removes_keys() {
+    static DRAFTS: AtomFamily<&'static str, String> = |_, _| String::new();
+
+    let (root, updated) = common::root();
+    root.register((DRAFTS, "a"), ScopeId(1));
+    root.register((DRAFTS, "b"), ScopeId(2));
+    (DRAFTS, "a").write(&root, "hello".to_string());
+    (DRAFTS, "b").write(&root, "world".to_string());
+    updated.lock().unwrap().clear();
+    let id = (DRAFTS, "a").unique_id(&root);
+
+    // Removed keys update their subscribers and start over when they're read again
+    root.remove((DRAFTS, "a"));
+    assert_eq!(*updated.lock().unwrap(), [ScopeId(1)]);
+    assert_eq!((DRAFTS, "a").read(&root), None);
+    assert_ne!((DRAFTS, "a").unique_id(&root), id);
+    assert_eq!(*root.read((DRAFTS, "a")), "");
+    assert_eq!(*root.read((DRAFTS, "b")), "world");
+
+    root.remove_family(DRAFTS);
+    assert_eq!((DRAFTS, "a").read(&root), None);
+    assert_eq!((DRAFTS, "b").read(&root), None);
+}
+
+#[test]
+fn keys_with_the_same_hash_are_different_atoms() {
+    #[derive(Clone, PartialEq, Eq)]
+    struct Key(u32);
+
+    impl std::hash::Hash for Key {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            0.hash(state);
+        }
+    }
+
+    static CELLS: AtomFamily<Key, u32> = |_, key| key.0;
+
+    let (root, updated) = common::root();
+    assert_eq!(*root.register((CELLS, Key(1)), ScopeId(1)), 1);
+    assert_eq!(*root.register((CELLS, Key(2)), ScopeId(2)), 2);
+
+    (CELLS, Key(2)).write(&root, 20);
+    assert_eq!(*root.read((CELLS, Key(1))), 1);
+    assert_eq!(*root.read((CELLS, Key(2))), 20);
+    assert_eq!(*updated.lock().unwrap(), [ScopeId(2)]);
+}
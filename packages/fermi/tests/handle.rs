@@ -0,0 +1,42 @@
+use dioxus::prelude::*;
+use dioxus_core::Mutation;
+use fermi::*;
+use std::time::Duration;
+
+static COUNT: Atom<u32> = |_| 0;
+
+#[tokio::test]
+async fn applies_writes_from_other_threads() {
+    fn app(cx: Scope) -> Element {
+        let root = use_init_atom_root(cx);
+        let count = use_read(cx, COUNT);
+
+        cx.use_hook(|| {
+            let handle = root.handle();
+            std::thread::spawn(move || {
+                handle.set(COUNT, 1);
+                handle.modify(COUNT, |count| count + 1);
+            });
+        });
+
+        cx.render(rsx!(div { "{count}" }))
+    }
+
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+
+    let mut rendered = Vec::new();
+    while rendered.last().map(String::as_str) != Some("2") {
+        tokio::time::timeout(Duration::from_secs(1), dom.wait_for_work())
+            .await
+            .expect("the writes never arrived");
+
+        for edit in dom.render_immediate().edits {
+            if let Mutation::SetText { value, .. } = edit {
+                rendered.push(value.to_string());
+            }
+        }
+    }
+
+    assert!(rendered.iter().all(|count| count == "1" || count == "2"));
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use dioxus_core::ScopeId;
+use fermi::*;
+use std::sync::{Arc, Mutex};
+
+static COUNT: Atom<u32> = |_| 0;
+static NAME: Atom<String> = |_| "fermi".to_string();
+static DOUBLED: Selector<u32> = |cx| *cx.get(COUNT) * 2;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Todo {
+    done: bool,
+}
+
+static TODO: Atom<Todo> = |_| Todo { done: false };
+
+/// Create a root with a history that records the scopes it updates
+fn root() -> (AtomRoot, Arc<Mutex<Vec<ScopeId>>>) {
+    let (root, updated) = common::root();
+    (root.with_history(), updated)
+}
+
+#[test]
+fn records_writes() {
+    let (root, _) = root();
+    let root = root.with_debug::<Todo>();
+    root.initialize(COUNT);
+
+    COUNT.write(&root, 1);
+    TODO.write(&root, Todo { done: true });
+
+    let writes = root.writes();
+    assert_eq!(writes.len(), 2);
+    assert_eq!(writes[0].atom, COUNT.unique_id(&root));
+    assert_eq!(writes[0].value.as_deref(), Some("1"));
+    assert_eq!(writes[0].scope, None);
+    assert_eq!(writes[1].value.as_deref(), Some("Todo { done: true }"));
+
+    // Values of types the root doesn't know are recorded without their value
+    let (root, _) = self::root();
+    TODO.write(&root, Todo { done: true });
+    assert_eq!(root.writes()[0].value, None);
+
+    // Types can be registered before the history is enabled as well
+    let (root, _) = common::root();
+    let root = root.with_debug::<Todo>().with_history();
+    TODO.write(&root, Todo { done: true });
+    assert_eq!(
+        root.writes()[0].value.as_deref(),
+        Some("Todo { done: true }")
+    );
+}
+
+#[test]
+fn steps_back_and_forward_through_writes() {
+    let (root, updated) = root();
+    assert_eq!(*root.register(DOUBLED, ScopeId(1)), 0);
+    root.register(NAME, ScopeId(2));
+
+    COUNT.write(&root, 1);
+    COUNT.write(&root, 2);
+    NAME.write(&root, "dioxus".to_string());
+    updated.lock().unwrap().clear();
+
+    assert!(root.step_back());
+    assert_eq!(*root.read(NAME), "fermi");
+    assert_eq!(*root.read(DOUBLED), 4);
+    assert_eq!(*updated.lock().unwrap(), [ScopeId(2)]);
+
+    assert!(root.step_back());
+    assert_eq!(*root.read(DOUBLED), 2);
+
+    assert!(root.step_forward());
+    assert!(root.step_forward());
+    assert!(!root.step_forward());
+    assert_eq!(*root.read(NAME), "dioxus");
+    assert_eq!(*root.read(DOUBLED), 4);
+
+    assert!(root.travel_to(0));
+    assert_eq!(*root.read(COUNT), 0);
+    assert_eq!(*root.read(DOUBLED), 0);
+
+    // Writing after stepping back drops the writes that were stepped back over
+    COUNT.write(&root, 5);
+    assert_eq!(root.writes().len(), 1);
+    assert!(!root.step_forward());
+}
+
+#[test]
+fn restores_snapshots() {
+    let (root, _) = root();
+    COUNT.write(&root, 1);
+    let snapshot = root.snapshot();
+
+    COUNT.write(&root, 2);
+    NAME.write(&root, "dioxus".to_string());
+    assert_eq!(*root.read(DOUBLED), 4);
+
+    root.restore_snapshot(&snapshot);
+    assert_eq!(*root.read(COUNT), 1);
+    assert_eq!(*root.read(NAME), "fermi");
+    assert_eq!(*root.read(DOUBLED), 2);
+    assert_eq!(
+        snapshot.get::<u32>(COUNT.unique_id(&root)).as_deref(),
+        Some(&1)
+    );
+}
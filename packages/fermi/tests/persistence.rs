@@ -0,0 +1,64 @@
+use dioxus_core::ScopeId;
+use fermi::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Theme {
+    Light,
+    Dark,
+}
+
+static THEME: Persistent<Theme> = Persistent::new("theme", |_| Theme::Light);
+static DRAFT: Persistent<String> = Persistent::new("draft", |_| String::new());
+
+fn root() -> AtomRoot {
+    AtomRoot::new(Arc::new(|_| {}))
+}
+
+#[test]
+fn restores_values_from_the_storage() {
+    let storage = MemoryStorage::default();
+
+    let first = root().with_storage(storage.clone());
+    assert_eq!(*first.register(THEME, ScopeId(0)), Theme::Light);
+    THEME.write(&first, Theme::Dark);
+    assert_eq!(storage.get("theme").as_deref(), Some("\"Dark\""));
+
+    // A new root, like after restarting the app, starts out with the stored value
+    let second = root().with_storage(storage);
+    assert_eq!(*second.read(THEME), Theme::Dark);
+    assert_eq!(*second.read(DRAFT), "");
+}
+
+#[test]
+fn seeds_the_root_from_a_serialized_state() {
+    let server = root();
+    server.initialize(THEME);
+    DRAFT.write(&server, "hello".to_string());
+
+    let storage = MemoryStorage::default();
+    storage.set("draft", "\"stored\"");
+    storage.set("theme", "\"Dark\"");
+
+    // The state the app was rendered with takes precedence over the storage
+    let client = root()
+        .with_storage(storage)
+        .with_state(&server.serialize_state());
+    assert_eq!(*client.read(DRAFT), "hello");
+    assert_eq!(*client.read(THEME), Theme::Light);
+}
+
+#[test]
+fn saves_values_to_files() {
+    let dir = std::env::temp_dir().join(format!("fermi-persistence-{}", std::process::id()));
+
+    let first = root().with_storage(FileStorage::new(&dir));
+    first.initialize(DRAFT);
+    DRAFT.write(&first, "unsaved changes".to_string());
+
+    let second = root().with_storage(FileStorage::new(&dir));
+    assert_eq!(*second.read(DRAFT), "unsaved changes");
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use dioxus_core::ScopeId;
+use fermi::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn derives_values_from_atoms() {
+    static COUNT: Atom<u32> = |_| 1;
+    static DOUBLED: Selector<u32> = |cx| *cx.get(COUNT) * 2;
+    static LABEL: Selector<String> =
+        |cx| format!("{} doubled is {}", cx.get(COUNT), cx.get(DOUBLED));
+
+    let (root, updated) = common::root();
+    assert_eq!(*root.register(LABEL, ScopeId(1)), "1 doubled is 2");
+
+    root.set(COUNT.unique_id(&root), 5u32);
+    assert_eq!(*root.read(DOUBLED), 10);
+    assert_eq!(*root.read(LABEL), "5 doubled is 10");
+    assert_eq!(*updated.lock().unwrap(), [ScopeId(1)]);
+}
+
+#[test]
+fn only_updates_subscribers_when_the_value_changes() {
+    static COUNT: Atom<u32> = |_| 0;
+    static IS_EVEN: Selector<bool> = |cx| *cx.get(COUNT) % 2 == 0;
+
+    let (root, updated) = common::root();
+    assert!(*root.register(IS_EVEN, ScopeId(1)));
+
+    root.set(COUNT.unique_id(&root), 2u32);
+    assert!(updated.lock().unwrap().is_empty());
+
+    root.set(COUNT.unique_id(&root), 3u32);
+    assert!(!*root.read(IS_EVEN));
+    assert_eq!(*updated.lock().unwrap(), [ScopeId(1)]);
+}
+
+#[test]
+fn tracks_the_atoms_read_on_the_last_run() {
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    static USE_CELSIUS: Atom<bool> = |_| true;
+    static CELSIUS: Atom<i32> = |_| 20;
+    static FAHRENHEIT: Atom<i32> = |_| 68;
+    static TEMPERATURE: Selector<String> = |cx| {
+        RUNS.fetch_add(1, Ordering::SeqCst);
+        match *cx.get(USE_CELSIUS) {
+            true => format!("{}°C", cx.get(CELSIUS)),
+            false => format!("{}°F", cx.get(FAHRENHEIT)),
+        }
+    };
+
+    let (root, _) = common::root();
+    assert_eq!(*root.read(TEMPERATURE), "20°C");
+
+    // The selector isn't recomputed for atoms it didn't read
+    root.set(FAHRENHEIT.unique_id(&root), 70);
+    assert_eq!(RUNS.load(Ordering::SeqCst), 1);
+
+    root.set(USE_CELSIUS.unique_id(&root), false);
+    assert_eq!(*root.read(TEMPERATURE), "70°F");
+    assert_eq!(RUNS.load(Ordering::SeqCst), 2);
+
+    root.set(CELSIUS.unique_id(&root), 25);
+    assert_eq!(RUNS.load(Ordering::SeqCst), 2);
+
+    // Reading the selector again uses the stored value
+    assert_eq!(*root.read(TEMPERATURE), "70°F");
+    assert_eq!(RUNS.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn families_keep_a_selector_for_every_key() {
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    static ROWS: AtomFamily<usize, String> = |_, id| format!("row {}", id);
+    static LENGTHS: SelectorFamily<usize, usize> = |cx, id| {
+        RUNS.fetch_add(1, Ordering::SeqCst);
+        cx.get((ROWS, *id)).len()
+    };
+
+    let (root, updated) = common::root();
+    assert_eq!(*root.register((LENGTHS, 1), ScopeId(1)), 5);
+    assert_eq!(*root.register((LENGTHS, 2), ScopeId(2)), 5);
+    assert_eq!(RUNS.load(Ordering::SeqCst), 2);
+
+    // Only the selector of the key that read the row is recomputed and updated
+    (ROWS, 2).write(&root, "second row".to_string());
+    assert_eq!(RUNS.load(Ordering::SeqCst), 3);
+    assert_eq!(*root.read((LENGTHS, 2)), 10);
+    assert_eq!(*root.read((LENGTHS, 1)), 5);
+    assert_eq!(*updated.lock().unwrap(), [ScopeId(2)]);
+}
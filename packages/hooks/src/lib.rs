@@ -39,8 +39,14 @@ pub use usecoroutine::*;
 mod usefuture;
 pub use usefuture::*;
 
+mod usequery;
+pub use usequery::*;
+
 mod useeffect;
 pub use useeffect::*;
 
 mod usecallback;
 pub use usecallback::*;
+
+mod usesignal;
+pub use usesignal::*;
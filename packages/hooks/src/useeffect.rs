@@ -1,5 +1,10 @@
 use dioxus_core::{ScopeState, TaskId};
-use std::{any::Any, cell::Cell, future::Future};
+use std::{
+    any::Any,
+    cell::Cell,
+    future::Future,
+    rc::{Rc, Weak},
+};
 
 use crate::UseFutureDep;
 
@@ -7,7 +12,7 @@ use crate::UseFutureDep;
 ///
 /// Whenever the hooks dependencies change, the future will be re-evaluated.
 /// If a future is pending when the dependencies change, the previous future
-/// will be allowed to continue
+/// is cancelled, and so is a pending future when the component is dropped.
 ///
 /// - dependencies: a tuple of references to values that are PartialEq + Clone
 ///
@@ -30,7 +35,7 @@ where
 {
     struct UseEffect {
         needs_regen: bool,
-        task: Cell<Option<TaskId>>,
+        task: Cell<Option<(TaskId, Weak<()>)>>,
         dependencies: Vec<Box<dyn Any>>,
     }
 
@@ -44,12 +49,173 @@ where
         // We don't need regen anymore
         state.needs_regen = false;
 
+        // Cancel the previous future if it's still running
+        if let Some((task, running)) = state.task.take() {
+            if running.upgrade().is_some() {
+                cx.remove_future(task);
+            }
+        }
+
         // Create the new future
         let fut = future(dependencies.out());
 
-        state.task.set(Some(cx.push_future(async move {
+        // The future holds the token until it completes, when the scheduler forgets the task
+        let running = Rc::new(());
+        let token = Rc::downgrade(&running);
+
+        let task = cx.push_future(async move {
+            let _running = running;
             fut.await;
-        })));
+        });
+        state.task.set(Some((task, token)));
+    }
+}
+
+/// A hook that runs a closure after the component rendered, and again whenever the dependencies change.
+///
+/// The closure can return a cleanup, which runs before the closure runs again and when the component is dropped. This
+/// is where resources attached by the effect are detached again.
+///
+/// - dependencies: a tuple of references to values that are PartialEq + Clone
+///
+/// ## Examples
+///
+/// ```rust, ignore
+/// #[inline_props]
+/// fn app(cx: Scope, room: String) -> Element {
+///     use_effect_with_cleanup(cx, (room,), |(room,)| {
+///         let connection = connect(&room);
+///         move || connection.close()
+///     });
+///
+///     cx.render(rsx!( "connected to {room}" ))
+/// }
+/// ```
+pub fn use_effect_with_cleanup<C, D>(
+    cx: &ScopeState,
+    dependencies: D,
+    effect: impl FnOnce(D::Out) -> C + 'static,
+) where
+    C: EffectCleanup,
+    D: UseFutureDep,
+    D::Out: 'static,
+{
+    use_cleanup_effect(cx, dependencies, effect, |cx, run| {
+        cx.push_future(async move { run() });
+    })
+}
+
+/// A hook that runs a closure synchronously once the renderer applied the mutations of the render, and again whenever
+/// the dependencies change.
+///
+/// Unlike [`use_effect_with_cleanup`], the closure runs before anything else happens, so it sees the real dom in the
+/// state the render produced. This is the place to measure elements or to attach handles to them.
+///
+/// The closure can return a cleanup, which runs before the closure runs again and when the component is dropped.
+///
+/// ## Examples
+///
+/// ```rust, ignore
+/// use_layout_effect(cx, (), |_| {
+///     let observer = observe_resizes("canvas");
+///     move || observer.disconnect()
+/// });
+/// ```
+pub fn use_layout_effect<C, D>(
+    cx: &ScopeState,
+    dependencies: D,
+    effect: impl FnOnce(D::Out) -> C + 'static,
+) where
+    C: EffectCleanup,
+    D: UseFutureDep,
+    D::Out: 'static,
+{
+    use_cleanup_effect(cx, dependencies, effect, |cx, run| {
+        cx.queue_layout_effect(run)
+    })
+}
+
+/// What an effect returns to clean up after itself: a closure, or `()` when there is nothing to clean up.
+pub trait EffectCleanup: 'static {
+    /// Detach what the effect attached.
+    fn clean_up(self);
+}
+
+impl EffectCleanup for () {
+    fn clean_up(self) {}
+}
+
+impl<F: FnOnce() + 'static> EffectCleanup for F {
+    fn clean_up(self) {
+        self()
+    }
+}
+
+/// The cleanup of the last run of an effect, shared with the runs that were scheduled
+#[derive(Default)]
+struct EffectSlot {
+    // Bumped when the dependencies change and when the component is dropped, to skip the runs that are outdated
+    generation: Cell<usize>,
+    cleanup: Cell<Option<Box<dyn FnOnce()>>>,
+}
+
+impl EffectSlot {
+    fn clean_up(&self) {
+        if let Some(cleanup) = self.cleanup.take() {
+            cleanup();
+        }
+    }
+}
+
+fn use_cleanup_effect<C, D>(
+    cx: &ScopeState,
+    dependencies: D,
+    effect: impl FnOnce(D::Out) -> C + 'static,
+    schedule: impl FnOnce(&ScopeState, Box<dyn FnOnce()>),
+) where
+    C: EffectCleanup,
+    D: UseFutureDep,
+    D::Out: 'static,
+{
+    struct UseCleanupEffect {
+        needs_regen: bool,
+        slot: Rc<EffectSlot>,
+        dependencies: Vec<Box<dyn Any>>,
+    }
+
+    impl Drop for UseCleanupEffect {
+        fn drop(&mut self) {
+            self.slot.generation.set(self.slot.generation.get() + 1);
+            self.slot.clean_up();
+        }
+    }
+
+    let state = cx.use_hook(|| UseCleanupEffect {
+        needs_regen: true,
+        slot: Rc::default(),
+        dependencies: Vec::new(),
+    });
+
+    if dependencies.clone().apply(&mut state.dependencies) || state.needs_regen {
+        state.needs_regen = false;
+
+        let slot = state.slot.clone();
+        let generation = slot.generation.get() + 1;
+        slot.generation.set(generation);
+
+        let dependencies = dependencies.out();
+        schedule(
+            cx,
+            Box::new(move || {
+                if slot.generation.get() != generation {
+                    return;
+                }
+
+                slot.clean_up();
+                let cleanup = effect(dependencies);
+                slot.cleanup.set(Some(Box::new(move || cleanup.clean_up())));
+            }),
+        );
     }
 }
 
@@ -89,4 +255,112 @@ mod tests {
             todo!()
         }
     }
+
+    #[test]
+    fn effects_clean_up_before_running_again_and_on_drop() {
+        use dioxus_core::prelude::*;
+        use std::cell::RefCell;
+
+        thread_local! {
+            static DEPENDENCY: Cell<u32> = const { Cell::new(0) };
+            static LOG: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+        }
+
+        fn log(entry: String) {
+            LOG.with(|log| log.borrow_mut().push(entry));
+        }
+
+        fn take_log() -> Vec<String> {
+            LOG.with(|log| log.take())
+        }
+
+        fn app(cx: Scope) -> Element {
+            let dependency = DEPENDENCY.with(Cell::get);
+
+            use_effect_with_cleanup(cx, (&dependency,), |(dependency,)| {
+                log(format!("effect {}", dependency));
+                move || log(format!("clean up effect {}", dependency))
+            });
+
+            use_layout_effect(cx, (&dependency,), |(dependency,)| {
+                log(format!("layout {}", dependency));
+                move || log(format!("clean up layout {}", dependency))
+            });
+
+            use_layout_effect(cx, (), |_| log("once".to_string()));
+
+            VNode::empty()
+        }
+
+        let mut dom = VirtualDom::new(app).with_layout_effects();
+        _ = dom.rebuild();
+        assert!(take_log().is_empty());
+
+        dom.run_layout_effects();
+        dom.process_events();
+        assert_eq!(take_log(), ["layout 0", "once", "effect 0"]);
+
+        // Nothing runs again while the dependencies stay the same
+        dom.mark_dirty(ScopeId(0));
+        _ = dom.render_immediate();
+        dom.run_layout_effects();
+        dom.process_events();
+        assert!(take_log().is_empty());
+
+        DEPENDENCY.with(|dependency| dependency.set(1));
+        dom.mark_dirty(ScopeId(0));
+        _ = dom.render_immediate();
+        dom.run_layout_effects();
+        dom.process_events();
+        assert_eq!(
+            take_log(),
+            [
+                "clean up layout 0",
+                "layout 1",
+                "clean up effect 0",
+                "effect 1"
+            ]
+        );
+
+        drop(dom);
+        assert_eq!(take_log(), ["clean up effect 1", "clean up layout 1"]);
+    }
+
+    #[test]
+    fn use_effect_cancels_outdated_futures() {
+        use dioxus_core::prelude::*;
+
+        thread_local! {
+            static DEPENDENCY: Cell<u32> = const { Cell::new(0) };
+            static DROPPED: Cell<usize> = const { Cell::new(0) };
+        }
+
+        struct Guard;
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                DROPPED.with(|dropped| dropped.set(dropped.get() + 1));
+            }
+        }
+
+        fn app(cx: Scope) -> Element {
+            let dependency = DEPENDENCY.with(Cell::get);
+
+            use_effect(cx, (&dependency,), |_| async move {
+                let _guard = Guard;
+                std::future::pending::<()>().await;
+            });
+
+            VNode::empty()
+        }
+
+        let mut dom = VirtualDom::new(app);
+        _ = dom.rebuild();
+        dom.process_events();
+
+        DEPENDENCY.with(|dependency| dependency.set(1));
+        dom.mark_dirty(ScopeId(0));
+        _ = dom.render_immediate();
+        assert_eq!(DROPPED.with(Cell::get), 1);
+    }
 }
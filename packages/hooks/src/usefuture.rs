@@ -1,5 +1,6 @@
 #![allow(missing_docs)]
 use dioxus_core::{ScopeState, TaskId};
+use futures_util::future::{abortable, AbortHandle};
 use std::{
     any::Any,
     cell::{Cell, RefCell},
@@ -18,7 +19,7 @@ use std::{
 ///
 /// Whenever the hooks dependencies change, the future will be re-evaluated.
 /// If a future is pending when the dependencies change, the previous future
-/// will be allowed to continue
+/// is cancelled and its value will never show up.
 ///
 /// - dependencies: a tuple of references to values that are PartialEq + Clone
 pub fn use_future<T, F, D>(
@@ -35,12 +36,16 @@ where
         update: cx.schedule_update(),
         needs_regen: Cell::new(true),
         values: Default::default(),
+        current: Default::default(),
+        readable: Cell::new(0),
         task: Cell::new(None),
+        running: Default::default(),
         dependencies: Vec::new(),
         waker: Default::default(),
     });
 
     *state.waker.borrow_mut() = None;
+    state.release_values();
 
     if dependencies.clone().apply(&mut state.dependencies) || state.needs_regen.get() {
         // We don't need regen anymore
@@ -51,17 +56,29 @@ where
 
         // Clone in our cells
         let values = state.values.clone();
+        let current = state.current.clone();
         let schedule_update = state.update.clone();
         let waker = state.waker.clone();
+        let running = state.running.clone();
 
         // Cancel the current future
-        if let Some(current) = state.task.take() {
-            cx.remove_future(current);
-        }
+        state.abort();
+
+        let (fut, handle) = abortable(fut);
+        *state.running.borrow_mut() = Some(handle);
 
         state.task.set(Some(cx.push_future(async move {
-            let res = fut.await;
-            values.borrow_mut().push(Box::leak(Box::new(res)));
+            // A cancelled future never produces a value
+            let res = match fut.await {
+                Ok(res) => res,
+                Err(_) => return,
+            };
+
+            store_value(&values, &current, res);
+
+            // A future that completed was never aborted, so the running handle is still ours. Aborting it instead would
+            // wake this task again after the scheduler already dropped it.
+            running.borrow_mut().take();
 
             // if there's a waker, we dont re-render the component. Instead we just progress that future
             match waker.borrow().as_ref() {
@@ -74,6 +91,14 @@ where
     state
 }
 
+// The values are kept until the render after the next one, since the references handed out during a render can outlive a
+// newer value and end up in the nodes of the render
+fn store_value<T>(values: &RefCell<Vec<Box<T>>>, current: &Cell<Option<*const T>>, value: T) {
+    let value = Box::new(value);
+    current.set(Some(&*value as *const T));
+    values.borrow_mut().push(value);
+}
+
 pub enum FutureState<'a, T> {
     Pending,
     Complete(&'a T),
@@ -84,9 +109,14 @@ pub struct UseFuture<T> {
     update: Arc<dyn Fn()>,
     needs_regen: Cell<bool>,
     task: Cell<Option<TaskId>>,
+    running: Rc<RefCell<Option<AbortHandle>>>,
     dependencies: Vec<Box<dyn Any>>,
     waker: Rc<RefCell<Option<std::task::Waker>>>,
-    values: Rc<RefCell<Vec<*mut T>>>,
+    values: Rc<RefCell<Vec<Box<T>>>>,
+    current: Rc<Cell<Option<*const T>>>,
+
+    // The index of the oldest value the last render could read
+    readable: Cell<usize>,
 }
 
 pub enum UseFutureState<'a, T> {
@@ -98,44 +128,73 @@ pub enum UseFutureState<'a, T> {
 impl<T> UseFuture<T> {
     /// Restart the future with new dependencies.
     ///
-    /// Will not cancel the previous future, but will ignore any values that it
-    /// generates.
+    /// The previous future is cancelled right away, so it can't overwrite the
+    /// value of the new one.
     pub fn restart(&self) {
+        self.abort();
         self.needs_regen.set(true);
         (self.update)();
     }
 
     /// Forcefully cancel a future
-    pub fn cancel(&self, cx: &ScopeState) {
-        if let Some(task) = self.task.take() {
-            cx.remove_future(task);
+    pub fn cancel(&self, _cx: &ScopeState) {
+        self.abort();
+    }
+
+    // Stop the running future, if there is one. Aborting wakes the task, which the scheduler then drops
+    fn abort(&self) {
+        if let Some(running) = self.running.borrow_mut().take() {
+            running.abort();
         }
     }
 
-    // clears the value in the future slot without starting the future over
-    pub fn clear(&self) -> Option<T> {
-        todo!()
-        // (self.update)();
-        // self.slot.replace(None)
+    // Free the values that were replaced before the last render. Only the nodes of the last render are still around,
+    // so the values read before it can't be referenced anymore.
+    fn release_values(&self) {
+        let mut values = self.values.borrow_mut();
+        values.drain(..self.readable.get());
+
+        // The current value is always the last one
+        let readable = match self.current.get() {
+            Some(_) => values.len() - 1,
+            None => values.len(),
+        };
+        self.readable.set(readable);
+    }
+
+    /// Check whether the future is still running
+    pub fn is_running(&self) -> bool {
+        self.running.borrow().is_some()
+    }
+
+    /// Clear the value in the future slot without starting the future over, returning the value that was cleared
+    ///
+    /// The value is cloned, since references to it handed out during this render may still be around. A future that
+    /// is still running sets the value again once it completes.
+    pub fn clear(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let value = self.value().cloned();
+        self.current.set(None);
+        (self.update)();
+        value
     }
 
-    // Manually set the value in the future slot without starting the future over
-    pub fn set(&self, _new_value: T) {
-        // self.slot.set(Some(new_value));
-        // self.needs_regen.set(true);
-        // (self.update)();
-        todo!()
+    /// Manually set the value in the future slot without starting the future over
+    ///
+    /// A future that is still running replaces the value once it completes. Call
+    /// [`UseFuture::cancel`] first to keep the value that was set.
+    pub fn set(&self, new_value: T) {
+        store_value(&self.values, &self.current, new_value);
+        (self.update)();
     }
 
     /// Return any value, even old values if the future has not yet resolved.
     ///
     /// If the future has never completed, the returned value will be `None`.
     pub fn value(&self) -> Option<&T> {
-        self.values
-            .borrow_mut()
-            .last()
-            .cloned()
-            .map(|x| unsafe { &*x })
+        self.current.get().map(|x| unsafe { &*x })
     }
 
     /// Get the ID of the future in Dioxus' internal scheduler
@@ -145,20 +204,16 @@ impl<T> UseFuture<T> {
 
     /// Get the current stateof the future.
     pub fn state(&self) -> UseFutureState<T> {
-        todo!()
-        // match (&self.task.get(), &self.value) {
-        //     // If we have a task and an existing value, we're reloading
-        //     (Some(_), Some(val)) => UseFutureState::Reloading(val),
-
-        //     // no task, but value - we're done
-        //     (None, Some(val)) => UseFutureState::Complete(val),
+        match (self.is_running(), self.value()) {
+            // If we have a task and an existing value, we're reloading
+            (true, Some(val)) => UseFutureState::Reloading(val),
 
-        //     // no task, no value - something's wrong? return pending
-        //     (None, None) => UseFutureState::Pending,
+            // no task, but value - we're done
+            (false, Some(val)) => UseFutureState::Complete(val),
 
-        //     // Task, no value - we're still pending
-        //     (Some(_), None) => UseFutureState::Pending,
-        // }
+            // no value - we're still pending, or the future was cancelled before it completed
+            (_, None) => UseFutureState::Pending,
+        }
     }
 }
 
@@ -181,8 +236,8 @@ impl<'a, T> Future for UseFutureAwait<'a, T> {
         self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Self::Output> {
-        match self.hook.values.borrow_mut().last().cloned() {
-            Some(value) => std::task::Poll::Ready(unsafe { &*value }),
+        match self.hook.value() {
+            Some(value) => std::task::Poll::Ready(value),
             None => {
                 self.hook.waker.replace(Some(cx.waker().clone()));
                 std::task::Poll::Pending
@@ -343,4 +398,103 @@ mod tests {
             todo!()
         }
     }
+
+    #[test]
+    fn restart_cancels_the_running_future() {
+        use dioxus_core::prelude::*;
+
+        #[derive(Clone, Copy, PartialEq)]
+        enum Step {
+            Render,
+            Restart,
+            Set,
+            Clear,
+        }
+
+        thread_local! {
+            static STEP: Cell<Step> = const { Cell::new(Step::Render) };
+            static STARTED: Cell<usize> = const { Cell::new(0) };
+            static DROPPED: Cell<usize> = const { Cell::new(0) };
+            static VALUE: Cell<Option<u32>> = const { Cell::new(None) };
+        }
+
+        struct Guard;
+
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                DROPPED.with(|dropped| dropped.set(dropped.get() + 1));
+            }
+        }
+
+        fn app(cx: Scope) -> Element {
+            let fut = use_future(cx, (), |_| async move {
+                let _guard = Guard;
+                STARTED.with(|started| started.set(started.get() + 1));
+                std::future::pending::<u32>().await
+            });
+
+            VALUE.with(|value| value.set(fut.value().copied()));
+            match STEP.with(|step| step.replace(Step::Render)) {
+                Step::Render => {}
+                Step::Restart => fut.restart(),
+                Step::Set => fut.set(5),
+                Step::Clear => {
+                    assert!(matches!(fut.state(), UseFutureState::Reloading(5)));
+                    assert_eq!(fut.clear(), Some(5));
+                }
+            }
+
+            VNode::empty()
+        }
+
+        fn render(dom: &mut VirtualDom, step: Step) {
+            STEP.with(|current| current.set(step));
+            dom.mark_dirty(ScopeId(0));
+            _ = dom.render_immediate();
+            dom.process_events();
+        }
+
+        let mut dom = VirtualDom::new(app);
+        _ = dom.rebuild();
+        dom.process_events();
+        assert_eq!(STARTED.with(Cell::get), 1);
+
+        // The first future is dropped as soon as the second one starts
+        render(&mut dom, Step::Restart);
+        render(&mut dom, Step::Render);
+        assert_eq!(STARTED.with(Cell::get), 2);
+        assert_eq!(DROPPED.with(Cell::get), 1);
+
+        render(&mut dom, Step::Set);
+        render(&mut dom, Step::Clear);
+        assert_eq!(VALUE.with(Cell::get), Some(5));
+
+        render(&mut dom, Step::Render);
+        assert_eq!(VALUE.with(Cell::get), None);
+    }
+
+    #[test]
+    fn replaced_values_are_freed() {
+        use dioxus_core::prelude::*;
+
+        thread_local! {
+            static VALUE: Rc<()> = Rc::new(());
+        }
+
+        fn app(cx: Scope) -> Element {
+            let fut = use_future(cx, (), |_| std::future::pending::<Rc<()>>());
+            fut.set(VALUE.with(Rc::clone));
+            VNode::empty()
+        }
+
+        let mut dom = VirtualDom::new(app);
+        _ = dom.rebuild();
+        for _ in 0..10 {
+            dom.mark_dirty(ScopeId(0));
+            _ = dom.render_immediate();
+        }
+
+        // Besides the original, only the values the last two renders could read are kept
+        assert_eq!(VALUE.with(Rc::strong_count), 4);
+    }
 }
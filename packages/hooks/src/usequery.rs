@@ -0,0 +1,524 @@
+use dioxus_core::{ScopeId, ScopeState};
+use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures_util::{stream::FuturesUnordered, StreamExt};
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    rc::{Rc, Weak},
+    sync::Arc,
+    task::Poll,
+    time::Duration,
+};
+
+type LocalFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+type Fetch = Rc<dyn Fn() -> LocalFuture<Result<Rc<dyn Any>, Rc<dyn Any>>>>;
+type Sleep = Rc<dyn Fn(Duration) -> LocalFuture<()>>;
+
+/// Provide a [`QueryClient`] to the components below, which share the queries cached in it.
+///
+/// The client is configured with its `with_*` methods:
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     use_query_client_provider(cx, |client| {
+///         client
+///             .with_retries(3)
+///             .with_sleep(|duration| async_std::task::sleep(duration))
+///     });
+///
+///     cx.render(rsx!( UserCard {} UserName {} ))
+/// }
+/// ```
+pub fn use_query_client_provider(
+    cx: &ScopeState,
+    init: impl FnOnce(QueryClient) -> QueryClient,
+) -> &QueryClient {
+    cx.use_hook(|| {
+        let client = init(QueryClient::new(cx.schedule_update_any()));
+        cx.push_future(client.run());
+        cx.provide_context(client)
+    })
+}
+
+/// Get the [`QueryClient`] provided by a parent component, to invalidate queries or to set their data.
+pub fn use_query_client(cx: &ScopeState) -> Option<&QueryClient> {
+    cx.use_hook(|| cx.consume_context::<QueryClient>()).as_ref()
+}
+
+/// Fetch data identified by a key, sharing it with every component that queries the same key.
+///
+/// Components that query a key while it's being fetched wait for the same fetch instead of starting their own. Once
+/// fetched, the data is cached and considered stale: a component that queries the key later gets the cached data
+/// right away while it's fetched again in the background. Queries are fetched again when they're invalidated and
+/// retried with a backoff when they fail. Queries no component uses anymore are removed from the cache after the
+/// [cache time](QueryClient::with_cache_time).
+///
+/// The key has to identify everything the fetch depends on, and the queries of a key have to fetch the same type.
+///
+/// ```rust, ignore
+/// #[inline_props]
+/// fn UserCard(cx: Scope, id: usize) -> Element {
+///     let user = use_query(cx, &format!("user/{id}"), move || fetch_user(*id));
+///
+///     cx.render(match user.data() {
+///         Some(user) => rsx!( "{user.name}" ),
+///         None => rsx!( "Loading..." ),
+///     })
+/// }
+/// ```
+pub fn use_query<'a, T, E, F>(
+    cx: &'a ScopeState,
+    key: &str,
+    fetch: impl Fn() -> F + 'static,
+) -> &'a UseQuery<T, E>
+where
+    T: 'static,
+    E: 'static,
+    F: Future<Output = Result<T, E>> + 'static,
+{
+    let query = cx.use_hook(|| UseQuery {
+        client: cx
+            .consume_context::<QueryClient>()
+            .expect("use_query needs a QueryClient provided by use_query_client_provider"),
+        key: None,
+        scope: cx.scope_id(),
+        _marker: PhantomData,
+    });
+
+    if query.key.as_deref() != Some(key) {
+        // The component queries another key than it did before
+        if let Some(previous) = query.key.replace(key.to_string()) {
+            query.client.unsubscribe(&previous, query.scope);
+        }
+
+        let fetch: Fetch = Rc::new(move || {
+            let fut = fetch();
+            Box::pin(async move {
+                match fut.await {
+                    Ok(data) => Ok(Rc::new(data) as Rc<dyn Any>),
+                    Err(error) => Err(Rc::new(error) as Rc<dyn Any>),
+                }
+            })
+        });
+        query.client.subscribe(key, query.scope, fetch);
+    }
+
+    query
+}
+
+/// A query created with [`use_query`].
+pub struct UseQuery<T, E> {
+    client: QueryClient,
+    key: Option<String>,
+    scope: ScopeId,
+    _marker: PhantomData<fn() -> (T, E)>,
+}
+
+impl<T: 'static, E: 'static> UseQuery<T, E> {
+    fn key(&self) -> &str {
+        self.key.as_deref().unwrap_or_default()
+    }
+
+    /// Get the data of the last successful fetch, even if it's stale.
+    pub fn data(&self) -> Option<Rc<T>> {
+        self.client.data(self.key())
+    }
+
+    /// Get the error of the last fetch, if it failed every time it was tried.
+    pub fn error(&self) -> Option<Rc<E>> {
+        self.client.error(self.key())
+    }
+
+    /// Check whether the query is being fetched.
+    pub fn is_fetching(&self) -> bool {
+        self.client.is_fetching(self.key())
+    }
+
+    /// Fetch the query again, unless it's already being fetched.
+    pub fn refetch(&self) {
+        self.client.refetch(self.key());
+    }
+
+    /// Mark the data of the query as stale and fetch it again.
+    pub fn invalidate(&self) {
+        self.client.invalidate(self.key());
+    }
+}
+
+impl<T, E> Drop for UseQuery<T, E> {
+    fn drop(&mut self) {
+        if let Some(key) = &self.key {
+            self.client.unsubscribe(key, self.scope);
+        }
+    }
+}
+
+/// The cache the queries of [`use_query`] share, provided by [`use_query_client_provider`].
+#[derive(Clone)]
+pub struct QueryClient {
+    inner: Rc<QueryClientInner>,
+}
+
+struct QueryClientInner {
+    queries: RefCell<HashMap<String, Query>>,
+    update_any: Arc<dyn Fn(ScopeId)>,
+    next_fetch: Cell<usize>,
+
+    fetches: UnboundedSender<LocalFuture<()>>,
+    pending_fetches: RefCell<Option<UnboundedReceiver<LocalFuture<()>>>>,
+
+    retries: Cell<u32>,
+    retry_delay: Cell<Duration>,
+    stale_time: Cell<Option<Duration>>,
+    cache_time: Cell<Duration>,
+    sleep: RefCell<Option<Sleep>>,
+    next_removal: Cell<usize>,
+}
+
+#[derive(Default)]
+struct Query {
+    data: Option<Rc<dyn Any>>,
+    error: Option<Rc<dyn Any>>,
+    stale: bool,
+
+    // The fetch whose result the query is waiting for
+    fetching: Option<usize>,
+    fetch: Option<Fetch>,
+    subscribers: HashSet<ScopeId>,
+
+    // The removal that's waiting for the cache time since the query was last used
+    removal: Option<usize>,
+}
+
+impl QueryClient {
+    fn new(update_any: Arc<dyn Fn(ScopeId)>) -> Self {
+        let (fetches, pending_fetches) = unbounded();
+
+        Self {
+            inner: Rc::new(QueryClientInner {
+                queries: RefCell::default(),
+                update_any,
+                next_fetch: Cell::new(0),
+                fetches,
+                pending_fetches: RefCell::new(Some(pending_fetches)),
+                retries: Cell::new(3),
+                retry_delay: Cell::new(Duration::from_secs(1)),
+                stale_time: Cell::new(None),
+                cache_time: Cell::new(Duration::from_secs(5 * 60)),
+                sleep: RefCell::new(None),
+                next_removal: Cell::new(0),
+            }),
+        }
+    }
+
+    /// Set how many times a failed fetch is tried again before the query fails. Defaults to 3.
+    pub fn with_retries(self, retries: u32) -> Self {
+        self.inner.retries.set(retries);
+        self
+    }
+
+    /// Set how long to wait before the first retry, which doubles with every retry after it. Defaults to a second.
+    pub fn with_retry_delay(self, delay: Duration) -> Self {
+        self.inner.retry_delay.set(delay);
+        self
+    }
+
+    /// Set how long fetched data stays fresh, during which components that query it don't fetch it again.
+    ///
+    /// Data is stale as soon as it's fetched by default. The client waits for the stale time with the sleep set by
+    /// [`QueryClient::with_sleep`], and without one the data stays fresh until it's invalidated.
+    pub fn with_stale_time(self, stale_time: Duration) -> Self {
+        self.inner.stale_time.set(Some(stale_time));
+        self
+    }
+
+    /// Set how long the data of a query no component uses anymore stays cached. Defaults to five minutes.
+    ///
+    /// Components that query the key again within the cache time get the cached data right away. The client waits
+    /// for the cache time with the sleep set by [`QueryClient::with_sleep`], and without one the data is removed as
+    /// soon as the last component stops using it.
+    pub fn with_cache_time(self, cache_time: Duration) -> Self {
+        self.inner.cache_time.set(cache_time);
+        self
+    }
+
+    /// Set how to wait for a duration, with the timer of the platform the app runs on.
+    ///
+    /// The client uses it to back off between retries and to wait for the stale and cache times. Without it, failed
+    /// fetches are retried right away.
+    pub fn with_sleep<F: Future<Output = ()> + 'static>(
+        self,
+        sleep: impl Fn(Duration) -> F + 'static,
+    ) -> Self {
+        *self.inner.sleep.borrow_mut() = Some(Rc::new(move |duration| Box::pin(sleep(duration))));
+        self
+    }
+
+    /// Get the data of a query, even if it's stale.
+    pub fn data<T: 'static>(&self, key: &str) -> Option<Rc<T>> {
+        let queries = self.inner.queries.borrow();
+        queries.get(key)?.data.clone()?.downcast().ok()
+    }
+
+    /// Get the error of a query, if its last fetch failed every time it was tried.
+    pub fn error<E: 'static>(&self, key: &str) -> Option<Rc<E>> {
+        let queries = self.inner.queries.borrow();
+        queries.get(key)?.error.clone()?.downcast().ok()
+    }
+
+    /// Check whether a query is being fetched.
+    pub fn is_fetching(&self, key: &str) -> bool {
+        matches!(self.inner.queries.borrow().get(key), Some(query) if query.fetching.is_some())
+    }
+
+    /// Set the data of a query, like after a mutation returned the new data, updating the components that query it.
+    ///
+    /// Data set for a key no component queries is removed after the cache time, unless a component queries it by then.
+    pub fn set_data<T: 'static>(&self, key: &str, data: T) {
+        let unused = {
+            let mut queries = self.inner.queries.borrow_mut();
+            let query = queries.entry(key.to_string()).or_default();
+            query.data = Some(Rc::new(data));
+            query.error = None;
+            query.stale = false;
+            query.subscribers.is_empty() && query.removal.is_none()
+        };
+
+        if unused {
+            self.schedule_removal(key);
+        }
+        self.notify(key);
+    }
+
+    /// Fetch a query again, unless it's already being fetched.
+    pub fn refetch(&self, key: &str) {
+        if self.start_fetch(key) {
+            self.notify(key);
+        }
+    }
+
+    /// Mark the data of a query as stale, fetching it again if components query it.
+    ///
+    /// A fetch that's running when the query is invalidated may return outdated data, so its result is ignored.
+    pub fn invalidate(&self, key: &str) {
+        let subscribed = match self.inner.queries.borrow_mut().get_mut(key) {
+            Some(query) => {
+                query.stale = true;
+                query.fetching = None;
+                !query.subscribers.is_empty()
+            }
+            None => false,
+        };
+
+        if subscribed && self.start_fetch(key) {
+            self.notify(key);
+        }
+    }
+
+    /// Run the fetches of the client
+    fn run(&self) -> impl Future<Output = ()> {
+        let mut pending = self.inner.pending_fetches.borrow_mut().take();
+        let mut running = FuturesUnordered::new();
+
+        std::future::poll_fn(move |cx| {
+            let pending = match pending.as_mut() {
+                Some(pending) => pending,
+                None => return Poll::Pending,
+            };
+
+            loop {
+                while let Poll::Ready(Some(fetch)) = pending.poll_next_unpin(cx) {
+                    running.push(fetch);
+                }
+
+                match running.poll_next_unpin(cx) {
+                    Poll::Ready(Some(())) => {}
+                    _ => return Poll::Pending,
+                }
+            }
+        })
+    }
+
+    fn subscribe(&self, key: &str, scope: ScopeId, fetch: Fetch) {
+        let needs_fetch = {
+            let mut queries = self.inner.queries.borrow_mut();
+            let query = queries.entry(key.to_string()).or_default();
+            query.subscribers.insert(scope);
+            query.fetch = Some(fetch);
+            query.removal = None;
+            query.data.is_none() || query.stale
+        };
+
+        if needs_fetch {
+            self.start_fetch(key);
+        }
+    }
+
+    fn unsubscribe(&self, key: &str, scope: ScopeId) {
+        let unused = match self.inner.queries.borrow_mut().get_mut(key) {
+            Some(query) => query.subscribers.remove(&scope) && query.subscribers.is_empty(),
+            None => false,
+        };
+
+        if unused {
+            self.schedule_removal(key);
+        }
+    }
+
+    /// Remove a query that isn't used anymore once the cache time passed, unless it's used again by then
+    fn schedule_removal(&self, key: &str) {
+        let id = self.inner.next_removal.get();
+        self.inner.next_removal.set(id + 1);
+
+        if let Some(query) = self.inner.queries.borrow_mut().get_mut(key) {
+            query.removal = Some(id);
+        }
+
+        let sleep = match self.inner.sleep.borrow().clone() {
+            Some(sleep) => sleep,
+            None => return self.remove_unused(key, id),
+        };
+
+        let cache_time = self.inner.cache_time.get();
+        let client = Rc::downgrade(&self.inner);
+        let key = key.to_string();
+
+        self.spawn(async move {
+            sleep(cache_time).await;
+
+            if let Some(client) = QueryClient::upgrade(&client) {
+                client.remove_unused(&key, id);
+            }
+        });
+    }
+
+    fn remove_unused(&self, key: &str, removal: usize) {
+        let mut queries = self.inner.queries.borrow_mut();
+        if matches!(queries.get(key), Some(query) if query.removal == Some(removal)) {
+            queries.remove(key);
+        }
+    }
+
+    /// Start fetching a query if it isn't being fetched, returning whether it started
+    fn start_fetch(&self, key: &str) -> bool {
+        let id = self.inner.next_fetch.get();
+
+        let fetch = {
+            let mut queries = self.inner.queries.borrow_mut();
+            let query = match queries.get_mut(key) {
+                Some(query) if query.fetching.is_none() => query,
+                _ => return false,
+            };
+            let fetch = match &query.fetch {
+                Some(fetch) => fetch.clone(),
+                None => return false,
+            };
+
+            query.fetching = Some(id);
+            fetch
+        };
+        self.inner.next_fetch.set(id + 1);
+
+        let retries = self.inner.retries.get();
+        let delay = self.inner.retry_delay.get();
+        let sleep = self.inner.sleep.borrow().clone();
+        let client = Rc::downgrade(&self.inner);
+        let key = key.to_string();
+
+        self.spawn(async move {
+            let mut attempt = 0;
+            let result = loop {
+                match fetch().await {
+                    Ok(data) => break Ok(data),
+                    Err(_) if attempt < retries => {
+                        if let Some(sleep) = &sleep {
+                            sleep(delay * 2u32.saturating_pow(attempt)).await;
+                        }
+                        attempt += 1;
+                    }
+                    Err(error) => break Err(error),
+                }
+            };
+
+            if let Some(client) = QueryClient::upgrade(&client) {
+                client.finish_fetch(&key, id, result);
+            }
+        });
+
+        true
+    }
+
+    fn finish_fetch(&self, key: &str, id: usize, result: Result<Rc<dyn Any>, Rc<dyn Any>>) {
+        let fresh_data = {
+            let mut queries = self.inner.queries.borrow_mut();
+            let query = match queries.get_mut(key) {
+                Some(query) if query.fetching == Some(id) => query,
+                _ => return,
+            };
+
+            query.fetching = None;
+            match result {
+                Ok(data) => {
+                    query.data = Some(data.clone());
+                    query.error = None;
+                    query.stale = self.inner.stale_time.get().is_none();
+                    Some(data).filter(|_| !query.stale)
+                }
+                Err(error) => {
+                    query.error = Some(error);
+                    None
+                }
+            }
+        };
+
+        if let (Some(data), Some(stale_time), Some(sleep)) = (
+            fresh_data,
+            self.inner.stale_time.get(),
+            self.inner.sleep.borrow().clone(),
+        ) {
+            let client = Rc::downgrade(&self.inner);
+            let key = key.to_string();
+
+            self.spawn(async move {
+                sleep(stale_time).await;
+
+                if let Some(client) = QueryClient::upgrade(&client) {
+                    let mut queries = client.inner.queries.borrow_mut();
+                    match queries.get_mut(&key) {
+                        // The data may have been replaced in the meantime
+                        Some(query) if matches!(&query.data, Some(current) if Rc::ptr_eq(current, &data)) => {
+                            query.stale = true
+                        }
+                        _ => {}
+                    }
+                }
+            });
+        }
+
+        self.notify(key);
+    }
+
+    fn spawn(&self, fut: impl Future<Output = ()> + 'static) {
+        _ = self.inner.fetches.unbounded_send(Box::pin(fut));
+    }
+
+    fn upgrade(inner: &Weak<QueryClientInner>) -> Option<Self> {
+        inner.upgrade().map(|inner| Self { inner })
+    }
+
+    /// Update the components that query a key
+    fn notify(&self, key: &str) {
+        let subscribers: Vec<ScopeId> = match self.inner.queries.borrow().get(key) {
+            Some(query) => query.subscribers.iter().copied().collect(),
+            None => return,
+        };
+
+        for scope in subscribers {
+            (self.inner.update_any)(scope);
+        }
+    }
+}
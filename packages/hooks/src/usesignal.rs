@@ -0,0 +1,499 @@
+use dioxus_core::{current_scope, ScopeHandle, ScopeState};
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    collections::HashSet,
+    fmt::{Debug, Display},
+    marker::PhantomData,
+    rc::Rc,
+};
+
+/// Store a value in a [`Signal`] that lives as long as the component.
+///
+/// A signal is `Copy`, so it can be moved into any number of closures and passed to child components without
+/// cloning it first. The components that read a signal while they render are the only ones rendered again when it's
+/// written to, even if they're not the component that created it.
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     let count = use_signal(cx, || 0);
+///
+///     cx.render(rsx! {
+///         button { onclick: move |_| count.with_mut(|count| *count += 1), "Increment" }
+///         Counter { count: count }
+///     })
+/// }
+///
+/// #[inline_props]
+/// fn Counter(cx: Scope, count: Signal<i32>) -> Element {
+///     // Only this component renders again when the count changes
+///     cx.render(rsx! { "{count}" })
+/// }
+/// ```
+pub fn use_signal<T: 'static>(cx: &ScopeState, init: impl FnOnce() -> T) -> Signal<T> {
+    cx.use_hook(|| {
+        let signal = Signal::new(init());
+        Owner {
+            ids: vec![signal.id],
+            value: signal,
+        }
+    })
+    .value
+}
+
+/// Derive a [`Signal`] from other signals.
+///
+/// The value is computed right away, and computed again every time one of the signals read by `compute` changes. The
+/// readers of the derived signal are only updated when the result is different.
+///
+/// ```rust, ignore
+/// let count = use_signal(cx, || 0);
+/// let doubled = use_computed(cx, move || count.get() * 2);
+/// ```
+pub fn use_computed<T: PartialEq + 'static>(
+    cx: &ScopeState,
+    mut compute: impl FnMut() -> T + 'static,
+) -> Signal<T> {
+    cx.use_hook(|| {
+        let output: Rc<Cell<Option<Signal<T>>>> = Rc::new(Cell::new(None));
+
+        let effect = Runtime::create_effect({
+            let output = output.clone();
+            move || {
+                let value = compute();
+                match output.get() {
+                    // Comparing with the current value doesn't subscribe to it
+                    Some(signal) if *signal.value().borrow() == value => {}
+                    Some(signal) => signal.set(value),
+                    None => output.set(Some(Signal::new(value))),
+                }
+            }
+        });
+
+        let signal = output.get().unwrap();
+        Owner {
+            ids: vec![effect, signal.id],
+            value: signal,
+        }
+    })
+    .value
+}
+
+/// Run a closure right away, and again every time one of the signals it read changes.
+///
+/// ```rust, ignore
+/// let count = use_signal(cx, || 0);
+///
+/// use_signal_effect(cx, move || log::info!("the count is {}", count));
+/// ```
+pub fn use_signal_effect(cx: &ScopeState, effect: impl FnMut() + 'static) {
+    cx.use_hook(|| Owner {
+        ids: vec![Runtime::create_effect(effect)],
+        value: (),
+    });
+}
+
+/// A value that tracks who reads it, created with [`use_signal`] or [`use_computed`].
+///
+/// Reading the signal while a component renders subscribes the component to it, and reading it in an effect
+/// subscribes the effect. Writing to the signal updates all of them.
+///
+/// The value lives as long as the component that created the signal. Using the signal after the component was dropped
+/// panics.
+pub struct Signal<T: 'static> {
+    id: SlotId,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: 'static> Signal<T> {
+    fn new(value: T) -> Self {
+        Self {
+            id: Runtime::insert(Rc::new(RefCell::new(value))),
+            _marker: PhantomData,
+        }
+    }
+
+    fn value(&self) -> Rc<RefCell<T>> {
+        Runtime::get(self.id)
+            .and_then(|value| value.downcast().ok())
+            .expect("a signal was used after the component that created it was dropped")
+    }
+
+    /// Read the value, subscribing the component or effect that's running to it.
+    pub fn with<O>(&self, f: impl FnOnce(&T) -> O) -> O {
+        Runtime::track(self.id);
+        f(&self.value().borrow())
+    }
+
+    /// Get a clone of the value, subscribing the component or effect that's running to it.
+    pub fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+
+    /// Set the value, updating everything that read it.
+    pub fn set(&self, value: T) {
+        *self.value().borrow_mut() = value;
+        Runtime::notify(self.id);
+    }
+
+    /// Change the value in place, updating everything that read it.
+    pub fn with_mut<O>(&self, f: impl FnOnce(&mut T) -> O) -> O {
+        let output = f(&mut self.value().borrow_mut());
+        Runtime::notify(self.id);
+        output
+    }
+}
+
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Signal<T> {}
+
+impl<T> PartialEq for Signal<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T: Display> Display for Signal<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.with(|value| Display::fmt(value, f))
+    }
+}
+
+impl<T: Debug> Debug for Signal<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.with(|value| Debug::fmt(value, f))
+    }
+}
+
+/// Frees the signals and effects of a hook when the component is dropped
+struct Owner<V> {
+    ids: Vec<SlotId>,
+    value: V,
+}
+
+impl<V> Drop for Owner<V> {
+    fn drop(&mut self) {
+        for id in &self.ids {
+            Runtime::remove(*id);
+        }
+    }
+}
+
+/// Where the values of the signals and effects of a thread live
+#[derive(Default)]
+struct Runtime {
+    slots: RefCell<Vec<Slot>>,
+    free: RefCell<Vec<usize>>,
+
+    /// The effects that are running, innermost last
+    observers: RefCell<Vec<SlotId>>,
+}
+
+thread_local! {
+    static RUNTIME: Runtime = Runtime::default();
+}
+
+/// A slot of the runtime, told apart from the slots that used its index before by its generation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct SlotId {
+    index: usize,
+    generation: usize,
+}
+
+#[derive(Default)]
+struct Slot {
+    generation: usize,
+    value: Option<Rc<dyn Any>>,
+    subscribers: HashSet<Subscriber>,
+}
+
+/// A component is tracked by its handle, so it isn't updated once it's unmounted, even if another component of the
+/// same or another VirtualDom gets its id
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Subscriber {
+    Scope(ScopeHandle),
+    Effect(SlotId),
+}
+
+type Effect = RefCell<Box<dyn FnMut()>>;
+
+impl Runtime {
+    fn insert(value: Rc<dyn Any>) -> SlotId {
+        RUNTIME.with(|runtime| {
+            let mut slots = runtime.slots.borrow_mut();
+            let index = match runtime.free.borrow_mut().pop() {
+                Some(index) => index,
+                None => {
+                    slots.push(Slot::default());
+                    slots.len() - 1
+                }
+            };
+
+            let slot = &mut slots[index];
+            slot.value = Some(value);
+            SlotId {
+                index,
+                generation: slot.generation,
+            }
+        })
+    }
+
+    fn remove(id: SlotId) {
+        let value = RUNTIME.with(|runtime| {
+            let mut slots = runtime.slots.borrow_mut();
+            let slot = &mut slots[id.index];
+            if slot.generation != id.generation {
+                return None;
+            }
+
+            slot.generation += 1;
+            slot.subscribers.clear();
+            runtime.free.borrow_mut().push(id.index);
+            slot.value.take()
+        });
+
+        // The value is dropped once the runtime isn't borrowed anymore
+        drop(value);
+    }
+
+    fn get(id: SlotId) -> Option<Rc<dyn Any>> {
+        RUNTIME.with(|runtime| {
+            let slots = runtime.slots.borrow();
+            let slot = slots.get(id.index)?;
+            match slot.generation == id.generation {
+                true => slot.value.clone(),
+                false => None,
+            }
+        })
+    }
+
+    /// Subscribe the effect or the component that's running to a slot
+    fn track(id: SlotId) {
+        RUNTIME.with(|runtime| {
+            let subscriber = match runtime.observers.borrow().last() {
+                Some(effect) => Subscriber::Effect(*effect),
+                None => match current_scope() {
+                    Some(scope) => Subscriber::Scope(scope),
+                    None => return,
+                },
+            };
+
+            if let Some(slot) = runtime.slots.borrow_mut().get_mut(id.index) {
+                if slot.generation == id.generation {
+                    // Forget the components that were unmounted since the slot was last written to, every time
+                    // the subscribers double so a signal that's never written to doesn't keep them all
+                    if slot.subscribers.insert(subscriber)
+                        && slot.subscribers.len().is_power_of_two()
+                    {
+                        slot.subscribers.retain(|subscriber| match subscriber {
+                            Subscriber::Scope(scope) => scope.is_mounted(),
+                            Subscriber::Effect(_) => true,
+                        });
+                    }
+                }
+            }
+        })
+    }
+
+    /// Update the subscribers of a slot, which subscribe again when they read it
+    fn notify(id: SlotId) {
+        let subscribers = RUNTIME.with(|runtime| {
+            let mut slots = runtime.slots.borrow_mut();
+            match slots.get_mut(id.index) {
+                Some(slot) if slot.generation == id.generation => {
+                    std::mem::take(&mut slot.subscribers)
+                }
+                _ => HashSet::new(),
+            }
+        });
+
+        for subscriber in subscribers {
+            match subscriber {
+                Subscriber::Scope(scope) => scope.needs_update(),
+                Subscriber::Effect(effect) => Runtime::run_effect(effect),
+            }
+        }
+    }
+
+    fn create_effect(effect: impl FnMut() + 'static) -> SlotId {
+        let effect: Effect = RefCell::new(Box::new(effect));
+        let id = Runtime::insert(Rc::new(effect));
+        Runtime::run_effect(id);
+        id
+    }
+
+    fn run_effect(id: SlotId) {
+        let effect = match Runtime::get(id).and_then(|effect| effect.downcast::<Effect>().ok()) {
+            Some(effect) => effect,
+            None => return,
+        };
+
+        // An effect that writes to a signal it read doesn't run itself again
+        let mut run = match effect.try_borrow_mut() {
+            Ok(run) => run,
+            Err(_) => return,
+        };
+
+        RUNTIME.with(|runtime| runtime.observers.borrow_mut().push(id));
+        run();
+        RUNTIME.with(|runtime| runtime.observers.borrow_mut().pop());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dioxus_core::prelude::*;
+
+    #[test]
+    fn only_readers_render_again() {
+        thread_local! {
+            static SIGNALS: Cell<Option<(Signal<u32>, Signal<u32>)>> = const { Cell::new(None) };
+            static RENDERS: Cell<usize> = const { Cell::new(0) };
+        }
+
+        fn app(cx: Scope) -> Element {
+            let read = use_signal(cx, || 0);
+            let unread = use_signal(cx, || 0);
+            read.get();
+
+            SIGNALS.with(|signals| signals.set(Some((read, unread))));
+            RENDERS.with(|renders| renders.set(renders.get() + 1));
+            VNode::empty()
+        }
+
+        let mut dom = VirtualDom::new(app);
+        _ = dom.rebuild();
+        let (read, unread) = SIGNALS.with(|signals| signals.get().unwrap());
+
+        unread.set(1);
+        dom.process_events();
+        _ = dom.render_immediate();
+        assert_eq!(RENDERS.with(Cell::get), 1);
+
+        read.with_mut(|read| *read += 1);
+        dom.process_events();
+        _ = dom.render_immediate();
+        assert_eq!(RENDERS.with(Cell::get), 2);
+        assert_eq!(read.to_string(), "1");
+    }
+
+    #[test]
+    fn effects_and_computed_signals_follow_their_sources() {
+        thread_local! {
+            static SIGNALS: Cell<Option<(Signal<u32>, Signal<bool>)>> = const { Cell::new(None) };
+            static LOG: RefCell<Vec<u32>> = const { RefCell::new(Vec::new()) };
+        }
+
+        fn app(cx: Scope) -> Element {
+            let count = use_signal(cx, || 1);
+            let even = use_computed(cx, move || count.get() % 2 == 0);
+            use_signal_effect(cx, move || {
+                LOG.with(|log| log.borrow_mut().push(count.get()))
+            });
+
+            SIGNALS.with(|signals| signals.set(Some((count, even))));
+            VNode::empty()
+        }
+
+        let mut dom = VirtualDom::new(app);
+        _ = dom.rebuild();
+        let (count, even) = SIGNALS.with(|signals| signals.get().unwrap());
+        assert!(!even.get());
+
+        count.set(2);
+        assert!(even.get());
+        count.set(4);
+        assert!(even.get());
+        assert_eq!(LOG.with(|log| log.borrow().clone()), [1, 2, 4]);
+
+        // The signals and effects are freed with the component
+        drop(dom);
+        LOG.with(|log| log.borrow_mut().clear());
+        assert!(Runtime::get(count.id).is_none());
+        assert!(Runtime::get(even.id).is_none());
+    }
+
+    #[test]
+    fn readers_of_other_virtualdoms_are_updated() {
+        thread_local! {
+            static SIGNAL: Cell<Option<Signal<u32>>> = const { Cell::new(None) };
+            static OWNER_RENDERS: Cell<usize> = const { Cell::new(0) };
+            static READER_RENDERS: Cell<usize> = const { Cell::new(0) };
+        }
+
+        fn owner(cx: Scope) -> Element {
+            SIGNAL.with(|signal| signal.set(Some(use_signal(cx, || 0))));
+            OWNER_RENDERS.with(|renders| renders.set(renders.get() + 1));
+            VNode::empty()
+        }
+
+        fn reader(_: Scope) -> Element {
+            SIGNAL.with(|signal| signal.get().unwrap().get());
+            READER_RENDERS.with(|renders| renders.set(renders.get() + 1));
+            VNode::empty()
+        }
+
+        let mut owner_dom = VirtualDom::new(owner);
+        _ = owner_dom.rebuild();
+        let mut reader_dom = VirtualDom::new(reader);
+        _ = reader_dom.rebuild();
+
+        // Both roots are ScopeId(0), but only the root of the dom that read the signal renders again
+        SIGNAL.with(|signal| signal.get().unwrap().set(1));
+        for dom in [&mut owner_dom, &mut reader_dom] {
+            dom.process_events();
+            _ = dom.render_immediate();
+        }
+
+        assert_eq!(OWNER_RENDERS.with(Cell::get), 1);
+        assert_eq!(READER_RENDERS.with(Cell::get), 2);
+    }
+
+    #[test]
+    fn unmounted_readers_are_not_updated() {
+        use dioxus::prelude::*;
+
+        thread_local! {
+            static SIGNALS: Cell<Option<(Signal<bool>, Signal<u32>)>> = const { Cell::new(None) };
+        }
+
+        fn app(cx: Scope) -> Element {
+            let show = use_signal(cx, || true);
+            let count = use_signal(cx, || 0);
+            SIGNALS.with(|signals| signals.set(Some((show, count))));
+
+            cx.render(match show.get() {
+                true => rsx!(Reader { count: count }),
+                false => rsx!(()),
+            })
+        }
+
+        #[inline_props]
+        fn Reader(cx: Scope, count: Signal<u32>) -> Element {
+            count.get();
+            cx.render(rsx!(()))
+        }
+
+        let mut dom = VirtualDom::new(app);
+        _ = dom.rebuild();
+        let (show, count) = SIGNALS.with(|signals| signals.get().unwrap());
+
+        show.set(false);
+        dom.process_events();
+        _ = dom.render_immediate();
+
+        // The reader is gone along with its subscription
+        count.set(1);
+        dom.process_events();
+        assert!(dom.render_immediate().dirty_scopes.is_empty());
+    }
+}
@@ -0,0 +1,145 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use std::{cell::Cell, time::Duration};
+
+thread_local! {
+    static CLIENT: Cell<Option<QueryClient>> = const { Cell::new(None) };
+    static FETCHES: Cell<usize> = const { Cell::new(0) };
+}
+
+fn client() -> QueryClient {
+    CLIENT.with(|client| {
+        let current = client.take();
+        client.set(current.clone());
+        current.unwrap()
+    })
+}
+
+fn fetches() -> usize {
+    FETCHES.with(Cell::get)
+}
+
+/// Fetch the user, failing the attempts before the one given
+async fn fetch_user(succeeds_on: usize) -> Result<String, String> {
+    let attempt = FETCHES.with(|fetches| fetches.replace(fetches.get() + 1));
+    tokio::task::yield_now().await;
+
+    match attempt + 1 >= succeeds_on {
+        true => Ok("ada".to_string()),
+        false => Err(format!("attempt {} failed", attempt + 1)),
+    }
+}
+
+#[inline_props]
+fn UserName(cx: Scope, succeeds_on: usize) -> Element {
+    let succeeds_on = *succeeds_on;
+    let user = use_query(cx, "user", move || fetch_user(succeeds_on));
+
+    let name = user.data().map(|name| name.to_string());
+    let error = user
+        .error()
+        .map(|error: std::rc::Rc<String>| error.to_string());
+    cx.render(rsx!("{name:?} {error:?}"))
+}
+
+fn app(cx: Scope<(usize, u32)>) -> Element {
+    let (succeeds_on, retries) = *cx.props;
+
+    let client = use_query_client_provider(cx, |client| {
+        client
+            .with_retries(retries)
+            .with_retry_delay(Duration::from_millis(1))
+            .with_cache_time(Duration::from_millis(1))
+            .with_sleep(tokio::time::sleep)
+    });
+    CLIENT.with(|current| current.set(Some(client.clone())));
+
+    cx.render(rsx! {
+        UserName { succeeds_on: succeeds_on }
+        UserName { succeeds_on: succeeds_on }
+    })
+}
+
+/// Render until the app has nothing left to do
+async fn settle(dom: &mut VirtualDom) {
+    while tokio::time::timeout(Duration::from_millis(50), dom.wait_for_work())
+        .await
+        .is_ok()
+    {
+        _ = dom.render_immediate();
+    }
+}
+
+#[tokio::test]
+async fn shares_fetches_of_a_key() {
+    let mut dom = VirtualDom::new_with_props(app, (1, 0));
+    _ = dom.rebuild();
+    settle(&mut dom).await;
+
+    assert_eq!(fetches(), 1);
+    assert_eq!(
+        client()
+            .data::<String>("user")
+            .as_deref()
+            .map(String::as_str),
+        Some("ada")
+    );
+
+    // The cached data is kept while it's fetched again
+    client().invalidate("user");
+    assert!(client().is_fetching("user"));
+    assert!(client().data::<String>("user").is_some());
+
+    settle(&mut dom).await;
+    assert_eq!(fetches(), 2);
+    assert!(!client().is_fetching("user"));
+}
+
+#[tokio::test]
+async fn retries_failed_fetches() {
+    let mut dom = VirtualDom::new_with_props(app, (3, 3));
+    _ = dom.rebuild();
+    settle(&mut dom).await;
+
+    assert_eq!(fetches(), 3);
+    assert!(client().data::<String>("user").is_some());
+    assert!(client().error::<String>("user").is_none());
+}
+
+#[tokio::test]
+async fn fails_once_out_of_retries() {
+    let mut dom = VirtualDom::new_with_props(app, (10, 1));
+    _ = dom.rebuild();
+    settle(&mut dom).await;
+
+    assert_eq!(fetches(), 2);
+    assert!(client().data::<String>("user").is_none());
+    assert_eq!(
+        client()
+            .error::<String>("user")
+            .as_deref()
+            .map(String::as_str),
+        Some("attempt 2 failed")
+    );
+
+    // Setting the data replaces the error
+    client().set_data("user", "grace".to_string());
+    assert!(client().error::<String>("user").is_none());
+}
+
+#[tokio::test]
+async fn removes_unused_queries_after_the_cache_time() {
+    let mut dom = VirtualDom::new_with_props(app, (1, 0));
+    _ = dom.rebuild();
+    settle(&mut dom).await;
+
+    client().set_data("unused", 1);
+    assert_eq!(client().data::<i32>("unused").as_deref(), Some(&1));
+
+    settle(&mut dom).await;
+    assert!(client().data::<i32>("unused").is_none());
+
+    // The query the components use is kept
+    assert!(client().data::<String>("user").is_some());
+}
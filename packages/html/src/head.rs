@@ -0,0 +1,322 @@
+//! Management of the document `<head>`.
+//!
+//! Components describe the `<title>`, `<meta>`, `<link>` and `<style>` entries they need with hooks like [`use_title`]
+//! and [`use_meta`]. The entries are collected into a [`HeadContext`] that the renderer provides at the root of the
+//! VirtualDom. SSR renders the collected entries into the document it produces, while live renderers (web, desktop)
+//! check [`HeadContext::take_changed`] after applying edits and patch the real document.
+//!
+//! When no [`HeadContext`] is present in the tree, the hooks are no-ops.
+//!
+//! ```rust, ignore
+//! fn BlogPost(cx: Scope<PostProps>) -> Element {
+//!     use_title(cx, format!("{} | My Blog", cx.props.title));
+//!     use_meta(cx, "description", &cx.props.summary);
+//!
+//!     render! { article { "{cx.props.body}" } }
+//! }
+//! ```
+
+use dioxus_core::ScopeState;
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fmt::{self, Write},
+    rc::Rc,
+};
+
+/// The attribute written onto every element managed by the [`HeadContext`].
+///
+/// Renderers use it to find (and replace) the elements they previously inserted, including the ones written by SSR.
+pub const HEAD_MARKER: &str = "data-dioxus-head";
+
+/// A single entry in the document `<head>`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HeadEntry {
+    /// The document title. The most recently mounted title wins.
+    Title(String),
+
+    /// A `<meta>` tag with the given attributes.
+    ///
+    /// Meta tags that share a `name`, `property`, `http-equiv` or `charset` replace each other, so the most recently
+    /// mounted one wins.
+    Meta(Vec<(String, String)>),
+
+    /// A `<link>` tag with the given attributes.
+    Link(Vec<(String, String)>),
+
+    /// A `<style>` tag with the given css.
+    Style(String),
+}
+
+impl HeadEntry {
+    /// Create a new title entry
+    pub fn title(title: impl Into<String>) -> Self {
+        HeadEntry::Title(title.into())
+    }
+
+    /// Create a new `<meta name=".." content="..">` entry
+    pub fn meta(name: impl Into<String>, content: impl Into<String>) -> Self {
+        HeadEntry::Meta(vec![
+            ("name".to_string(), name.into()),
+            ("content".to_string(), content.into()),
+        ])
+    }
+
+    /// Create a new `<meta property=".." content="..">` entry, as used by OpenGraph
+    pub fn meta_property(property: impl Into<String>, content: impl Into<String>) -> Self {
+        HeadEntry::Meta(vec![
+            ("property".to_string(), property.into()),
+            ("content".to_string(), content.into()),
+        ])
+    }
+
+    /// Create a new `<link rel=".." href="..">` entry
+    pub fn link(rel: impl Into<String>, href: impl Into<String>) -> Self {
+        HeadEntry::Link(vec![
+            ("rel".to_string(), rel.into()),
+            ("href".to_string(), href.into()),
+        ])
+    }
+
+    /// Create a new `<link rel="stylesheet" href="..">` entry
+    pub fn stylesheet(href: impl Into<String>) -> Self {
+        Self::link("stylesheet", href)
+    }
+
+    /// The key that entries replace each other by, if any
+    fn dedup_key(&self) -> Option<String> {
+        match self {
+            HeadEntry::Title(_) => Some("title".to_string()),
+            HeadEntry::Meta(attrs) => attrs
+                .iter()
+                .find(|(name, _)| matches!(name.as_str(), "name" | "property" | "http-equiv"))
+                .map(|(name, value)| format!("meta:{}={}", name, value))
+                .or_else(|| {
+                    attrs
+                        .iter()
+                        .any(|(name, _)| name == "charset")
+                        .then(|| "meta:charset".to_string())
+                }),
+            HeadEntry::Link(_) | HeadEntry::Style(_) => None,
+        }
+    }
+
+    /// Write this entry as HTML
+    pub fn render_to(&self, out: &mut impl Write) -> fmt::Result {
+        match self {
+            HeadEntry::Title(title) => {
+                write!(out, "<title>")?;
+                escape_into(out, title)?;
+                write!(out, "</title>")
+            }
+            HeadEntry::Meta(attrs) => {
+                write!(out, "<meta")?;
+                write_attrs(out, attrs)?;
+                write!(out, " {}>", HEAD_MARKER)
+            }
+            HeadEntry::Link(attrs) => {
+                write!(out, "<link")?;
+                write_attrs(out, attrs)?;
+                write!(out, " {}>", HEAD_MARKER)
+            }
+            HeadEntry::Style(css) => {
+                write!(out, "<style {}>", HEAD_MARKER)?;
+                escape_style_into(out, css)?;
+                write!(out, "</style>")
+            }
+        }
+    }
+}
+
+/// The collection of [`HeadEntry`]s registered by the components currently mounted in a VirtualDom.
+///
+/// Renderers provide this as a root context with [`dioxus_core::VirtualDom::with_root_context`].
+#[derive(Clone, Default)]
+pub struct HeadContext {
+    inner: Rc<RefCell<HeadState>>,
+}
+
+#[derive(Default)]
+struct HeadState {
+    entries: BTreeMap<usize, HeadEntry>,
+    next_id: usize,
+    changed: bool,
+}
+
+impl HeadContext {
+    /// Create a new, empty head
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserve a slot for an entry. Slots reserved later take precedence over earlier ones.
+    fn reserve(&self) -> usize {
+        let mut inner = self.inner.borrow_mut();
+        inner.next_id += 1;
+        inner.next_id
+    }
+
+    /// Set the entry in a slot, marking the head as changed if the entry is different
+    fn set(&self, slot: usize, entry: HeadEntry) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.entries.get(&slot) != Some(&entry) {
+            inner.entries.insert(slot, entry);
+            inner.changed = true;
+        }
+    }
+
+    /// Remove the entry in a slot
+    fn remove(&self, slot: usize) {
+        let mut inner = self.inner.borrow_mut();
+        if inner.entries.remove(&slot).is_some() {
+            inner.changed = true;
+        }
+    }
+
+    /// Get the current document title, if any component set one
+    pub fn title(&self) -> Option<String> {
+        self.entries().into_iter().find_map(|entry| match entry {
+            HeadEntry::Title(title) => Some(title),
+            _ => None,
+        })
+    }
+
+    /// Get the resolved list of entries, with replaced entries removed.
+    ///
+    /// The title always comes first.
+    pub fn entries(&self) -> Vec<HeadEntry> {
+        let inner = self.inner.borrow();
+        let mut resolved: Vec<(Option<String>, &HeadEntry)> = Vec::new();
+
+        for entry in inner.entries.values() {
+            let key = entry.dedup_key();
+            if key.is_some() {
+                resolved.retain(|(other, _)| other != &key);
+            }
+            resolved.push((key, entry));
+        }
+
+        resolved.sort_by_key(|(_, entry)| !matches!(entry, HeadEntry::Title(_)));
+        resolved.into_iter().map(|(_, e)| e.clone()).collect()
+    }
+
+    /// Check if the entries have changed since the last call, resetting the flag.
+    ///
+    /// Live renderers call this after applying edits to decide whether the real document needs to be patched.
+    pub fn take_changed(&self) -> bool {
+        std::mem::take(&mut self.inner.borrow_mut().changed)
+    }
+
+    /// Render every entry, including the `<title>`, as HTML
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        self.render_to(&mut out).unwrap();
+        out
+    }
+
+    /// Render every entry, including the `<title>`, into a writer
+    pub fn render_to(&self, out: &mut impl Write) -> fmt::Result {
+        for entry in self.entries() {
+            entry.render_to(out)?;
+        }
+        Ok(())
+    }
+
+    /// Render every entry except the `<title>` as HTML.
+    ///
+    /// Every element is marked with [`HEAD_MARKER`], so renderers can remove them before inserting the new set.
+    pub fn render_elements(&self) -> String {
+        let mut out = String::new();
+        for entry in self.entries() {
+            if !matches!(entry, HeadEntry::Title(_)) {
+                entry.render_to(&mut out).unwrap();
+            }
+        }
+        out
+    }
+}
+
+struct HeadSlot {
+    context: HeadContext,
+    id: usize,
+}
+
+impl Drop for HeadSlot {
+    fn drop(&mut self) {
+        self.context.remove(self.id);
+    }
+}
+
+/// Add an entry to the document head for as long as this component is mounted.
+///
+/// The entry is updated whenever it changes between renders.
+pub fn use_head(cx: &ScopeState, entry: HeadEntry) {
+    let slot = cx.use_hook(|| {
+        cx.consume_context::<HeadContext>().map(|context| HeadSlot {
+            id: context.reserve(),
+            context,
+        })
+    });
+
+    if let Some(slot) = slot {
+        slot.context.set(slot.id, entry);
+    }
+}
+
+/// Set the document title for as long as this component is mounted.
+///
+/// If several mounted components set a title, the most recently mounted one wins.
+pub fn use_title(cx: &ScopeState, title: impl Into<String>) {
+    use_head(cx, HeadEntry::title(title))
+}
+
+/// Add a `<meta name=".." content="..">` tag to the document head for as long as this component is mounted.
+pub fn use_meta(cx: &ScopeState, name: impl Into<String>, content: impl Into<String>) {
+    use_head(cx, HeadEntry::meta(name, content))
+}
+
+/// Add a stylesheet `<link>` to the document head for as long as this component is mounted.
+pub fn use_stylesheet(cx: &ScopeState, href: impl Into<String>) {
+    use_head(cx, HeadEntry::stylesheet(href))
+}
+
+fn write_attrs(out: &mut impl Write, attrs: &[(String, String)]) -> fmt::Result {
+    for (name, value) in attrs {
+        write!(out, " {}=\"", name)?;
+        escape_into(out, value)?;
+        write!(out, "\"")?;
+    }
+    Ok(())
+}
+
+/// Write css so it can't close the `<style>` element it's in.
+///
+/// `</style` ends the element wherever it appears, even inside a css string or comment, so its `/` is written as the
+/// css escape `\/`, which means the same thing to the css parser.
+fn escape_style_into(out: &mut impl Write, css: &str) -> fmt::Result {
+    let mut rest = css;
+    while let Some(idx) = rest.find("</") {
+        let after = &rest[idx + 2..];
+        let closes_style = after
+            .get(..5)
+            .map_or(false, |tag| tag.eq_ignore_ascii_case("style"));
+
+        out.write_str(&rest[..idx])?;
+        out.write_str(if closes_style { "<\\/" } else { "</" })?;
+        rest = after;
+    }
+    out.write_str(rest)
+}
+
+fn escape_into(out: &mut impl Write, text: &str) -> fmt::Result {
+    for c in text.chars() {
+        match c {
+            '&' => out.write_str("&amp;")?,
+            '<' => out.write_str("&lt;")?,
+            '>' => out.write_str("&gt;")?,
+            '"' => out.write_str("&quot;")?,
+            c => out.write_char(c)?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+//! The markers written into pre-rendered HTML so a renderer can hydrate it.
+//!
+//! `dioxus-ssr` writes these when pre-rendering, and renderers that hydrate (like `dioxus-web`) read them back. The
+//! [`ElementId`]s in the markers are the ones the VirtualDom assigned while rebuilding, so a renderer that rebuilds the
+//! same app gets the same ids and can check them against the markers.
+//!
+//! | Node                                             | Marker                                                 |
+//! | ------------------------------------------------ | ------------------------------------------------------ |
+//! | A template root, or an element with dynamic attributes | `<div data-node-hydration="3">`                  |
+//! | Dynamic text                                     | `<!--node-id4-->hello<!--#-->`                         |
+//! | A placeholder                                    | `<pre hidden data-node-hydration="5"></pre>`           |
+//!
+//! Static text roots don't carry a marker since text can't have attributes. Markers around dynamic text also keep it
+//! from merging with the text around it. Empty dynamic text is written as just the markers, since there is no text
+//! node in the HTML to find.
+
+use dioxus_core::ElementId;
+use std::fmt::{self, Write};
+
+/// The attribute holding the [`ElementId`] of a template root, an element with dynamic attributes, or a placeholder
+pub const NODE_ID_ATTRIBUTE: &str = "data-node-hydration";
+
+/// The prefix of the comment written in front of dynamic text, followed by the [`ElementId`] of the text
+pub const TEXT_START_PREFIX: &str = "node-id";
+
+/// The contents of the comment written after dynamic text
+pub const TEXT_END: &str = "#";
+
+/// The tag of placeholder elements
+pub const PLACEHOLDER_TAG: &str = "pre";
+
+/// Write the attribute holding the id of an element, including the leading space
+pub fn write_node_id(out: &mut impl Write, id: ElementId) -> fmt::Result {
+    write!(out, " {}=\"{}\"", NODE_ID_ATTRIBUTE, id.0)
+}
+
+/// Write the comment in front of a dynamic text node
+pub fn write_text_start(out: &mut impl Write, id: ElementId) -> fmt::Result {
+    write!(out, "<!--{}{}-->", TEXT_START_PREFIX, id.0)
+}
+
+/// Write the comment after a dynamic text node
+pub fn write_text_end(out: &mut impl Write) -> fmt::Result {
+    write!(out, "<!--{}-->", TEXT_END)
+}
+
+/// Write a placeholder element
+pub fn write_placeholder(out: &mut impl Write, id: ElementId) -> fmt::Result {
+    write!(out, "<{} hidden", PLACEHOLDER_TAG)?;
+    write_node_id(out, id)?;
+    write!(out, "></{}>", PLACEHOLDER_TAG)
+}
+
+/// Read the id out of the value of a [`NODE_ID_ATTRIBUTE`]
+pub fn parse_node_id(value: &str) -> Option<ElementId> {
+    value.parse().ok().map(ElementId)
+}
+
+/// Read the id out of the contents of the comment in front of dynamic text
+pub fn parse_text_start(comment: &str) -> Option<ElementId> {
+    comment
+        .strip_prefix(TEXT_START_PREFIX)
+        .and_then(parse_node_id)
+}
@@ -17,6 +17,8 @@ mod elements;
 pub mod events;
 pub mod geometry;
 mod global_attributes;
+pub mod head;
+pub mod hydration;
 pub mod input_data;
 mod render_template;
 #[cfg(feature = "wasm-bind")]
@@ -29,4 +31,5 @@ pub use render_template::*;
 
 pub mod prelude {
     pub use crate::events::*;
+    pub use crate::head::{use_head, use_meta, use_stylesheet, use_title};
 }
@@ -1,9 +1,13 @@
+use crate::hydration::PLACEHOLDER_TAG;
 use dioxus_core::{Template, TemplateAttribute, TemplateNode};
 use std::fmt::Write;
 
 /// Render a template to an HTML string
 ///
 /// Useful for sending over the wire. Can be used to with innerHtml to create templates with little work
+///
+/// Dynamic nodes, including dynamic text, are written as hidden placeholder elements (see [`crate::hydration`]) so
+/// every node in the template keeps its index. Renderers replace them when the template is used.
 pub fn render_template_to_html(template: &Template) -> String {
     let mut out = String::new();
 
@@ -25,17 +29,19 @@ fn render_template_node(node: &TemplateNode, out: &mut String) -> std::fmt::Resu
             write!(out, "<{tag}")?;
             for attr in *attrs {
                 if let TemplateAttribute::Static { name, value, .. } = attr {
-                    write!(out, "{}=\"{}\"", name, value)?;
+                    write!(out, " {}=\"{}\"", name, value)?;
                 }
             }
+            write!(out, ">")?;
             for child in *children {
                 render_template_node(child, out)?;
             }
             write!(out, "</{tag}>")?;
         }
         TemplateNode::Text { text: t } => write!(out, "{t}")?,
-        TemplateNode::Dynamic { id: _ } => write!(out, "<pre hidden />")?,
-        TemplateNode::DynamicText { id: t } => write!(out, "<!-- --> {t} <!-- -->")?,
+        TemplateNode::Dynamic { .. } | TemplateNode::DynamicText { .. } => {
+            write!(out, "<{0} hidden></{0}>", PLACEHOLDER_TAG)?
+        }
     };
     Ok(())
 }
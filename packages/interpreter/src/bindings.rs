@@ -2,7 +2,7 @@
 
 use js_sys::Function;
 use wasm_bindgen::prelude::*;
-use web_sys::Element;
+use web_sys::{Element, Node};
 
 #[wasm_bindgen(module = "/src/interpreter.js")]
 extern "C" {
@@ -17,6 +17,9 @@ extern "C" {
     #[wasm_bindgen(method)]
     pub fn MountToRoot(this: &Interpreter);
 
+    #[wasm_bindgen(method)]
+    pub fn SetNode(this: &Interpreter, id: u32, node: Node);
+
     #[wasm_bindgen(method)]
     pub fn AppendChildren(this: &Interpreter, m: u32, id: u32);
 
@@ -0,0 +1,43 @@
+extern crate proc_macro;
+
+mod route;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+/// Derive `dioxus_router::Routable` for an enum of routes.
+///
+/// Every variant needs a `#[route("...")]` attribute with the path it matches. Segments starting with a colon are
+/// parameters, which are parsed into the field of the variant with the same name. When the route matches, the
+/// component with the same name as the variant is rendered, with the fields of the variant as its props. A different
+/// component can be given after the path.
+///
+/// A segment starting with an asterisk is a catch-all that matches the rest of the path (segments joined by slashes),
+/// which makes a good "not found" route. Parameters can be constrained like in a `Route` component, as in `:id<int>`.
+///
+/// Routes are ranked by the router exactly like `Route` components, so `/blog/new` wins over `/blog/:id<int>`, which
+/// wins over `/blog/:slug`, no matter which is declared first, and a catch-all only matches when nothing else does. A
+/// parameter that doesn't parse into the type of its field doesn't match either.
+///
+/// ```rust, ignore
+/// #[derive(Routable, Clone, PartialEq)]
+/// enum Route {
+///     #[route("/")]
+///     Home,
+///
+///     #[route("/blog/:id", BlogPostPage)]
+///     BlogPost { id: usize },
+///
+///     #[route("/*path")]
+///     NotFound { path: String },
+/// }
+/// ```
+#[proc_macro_derive(Routable, attributes(route))]
+pub fn derive_routable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match route::RoutableEnum::parse(&input) {
+        Ok(routes) => routes.to_token_stream().into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
@@ -0,0 +1,362 @@
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    Data, DeriveInput, Error, Fields, Ident, LitStr, Path, Result, Token, Type,
+};
+
+/// An enum with a route on every variant
+pub struct RoutableEnum {
+    name: Ident,
+    routes: Vec<Route>,
+}
+
+/// A variant of the enum and the path it matches
+struct Route {
+    variant: Ident,
+    component: Path,
+    path: String,
+    segments: Vec<Segment>,
+    fields: Option<Vec<(Ident, Type)>>,
+}
+
+enum Segment {
+    Static(String),
+    Parameter(Ident, Box<Type>),
+    /// Matches everything left in the path
+    CatchAll(Ident, Box<Type>),
+}
+
+/// The contents of a `#[route("/path", Component)]` attribute
+struct RouteAttribute {
+    path: LitStr,
+    component: Option<Path>,
+}
+
+impl Parse for RouteAttribute {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = input.parse()?;
+
+        let component = match input.parse::<Option<Token![,]>>()? {
+            Some(_) if !input.is_empty() => Some(input.parse()?),
+            _ => None,
+        };
+
+        Ok(Self { path, component })
+    }
+}
+
+impl RoutableEnum {
+    pub fn parse(input: &DeriveInput) -> Result<Self> {
+        let data = match &input.data {
+            Data::Enum(data) => data,
+            _ => {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "Routable can only be derived for enums",
+                ))
+            }
+        };
+
+        let routes = data
+            .variants
+            .iter()
+            .map(|variant| {
+                let attr = variant
+                    .attrs
+                    .iter()
+                    .find(|attr| attr.path.is_ident("route"))
+                    .ok_or_else(|| {
+                        Error::new(
+                            variant.ident.span(),
+                            "every variant needs a #[route(\"/path\")] attribute",
+                        )
+                    })?;
+                let attr: RouteAttribute = attr.parse_args()?;
+
+                let fields = match &variant.fields {
+                    Fields::Unit => None,
+                    Fields::Named(fields) => Some(
+                        fields
+                            .named
+                            .iter()
+                            .map(|field| (field.ident.clone().unwrap(), field.ty.clone()))
+                            .collect::<Vec<_>>(),
+                    ),
+                    Fields::Unnamed(fields) => {
+                        return Err(Error::new(
+                            fields.span(),
+                            "route parameters need to be named fields",
+                        ))
+                    }
+                };
+
+                let no_fields = Vec::new();
+                let mut unused: Vec<&(Ident, Type)> =
+                    fields.as_ref().unwrap_or(&no_fields).iter().collect();
+
+                let path = attr.path.value();
+                let pieces: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+                let mut segments = Vec::new();
+
+                for (idx, piece) in pieces.iter().enumerate() {
+                    if piece.ends_with('?') {
+                        return Err(Error::new(
+                            attr.path.span(),
+                            "optional segments aren't supported by Routable, add a variant for each path instead",
+                        ));
+                    }
+
+                    let (name, catch_all) = match (piece.strip_prefix(':'), piece.strip_prefix('*'))
+                    {
+                        (Some(name), _) => (name, false),
+                        (_, Some(name)) => (name, true),
+                        _ => {
+                            segments.push(Segment::Static(piece.to_string()));
+                            continue;
+                        }
+                    };
+
+                    // Constraints are checked by the router while matching, but a typo should be caught here
+                    let name = match name.split_once('<') {
+                        Some((name, constraint)) => {
+                            let constraint = constraint.trim_end_matches('>');
+                            if !matches!(constraint, "int" | "alpha" | "alnum") {
+                                return Err(Error::new(
+                                    attr.path.span(),
+                                    format!(
+                                        "unknown constraint `{}`, expected `int`, `alpha` or `alnum`",
+                                        constraint
+                                    ),
+                                ));
+                            }
+                            name
+                        }
+                        None => name,
+                    };
+
+                    if catch_all && idx != pieces.len() - 1 {
+                        return Err(Error::new(
+                            attr.path.span(),
+                            "a catch-all segment has to be the last segment of the route",
+                        ));
+                    }
+
+                    let field = unused
+                        .iter()
+                        .position(|(field, _)| field == name)
+                        .ok_or_else(|| {
+                            Error::new(
+                                attr.path.span(),
+                                format!(
+                                    "the parameter `{}` is not a field of `{}`",
+                                    name, variant.ident
+                                ),
+                            )
+                        })?;
+                    let (field, ty) = unused.remove(field);
+
+                    segments.push(match catch_all {
+                        true => Segment::CatchAll(field.clone(), Box::new(ty.clone())),
+                        false => Segment::Parameter(field.clone(), Box::new(ty.clone())),
+                    });
+                }
+
+                if let Some((field, _)) = unused.first() {
+                    return Err(Error::new(
+                        field.span(),
+                        format!("the field `{}` is not a parameter of the route", field),
+                    ));
+                }
+
+                Ok(Route {
+                    component: attr
+                        .component
+                        .unwrap_or_else(|| variant.ident.clone().into()),
+                    variant: variant.ident.clone(),
+                    path,
+                    segments,
+                    fields,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            name: input.ident.clone(),
+            routes,
+        })
+    }
+
+    pub fn to_token_stream(&self) -> TokenStream2 {
+        let name = &self.name;
+        let parsers = self.routes.iter().map(Route::parser);
+        let displays = self.routes.iter().map(Route::display);
+        let renders = self.routes.iter().map(Route::render);
+        let (input, best, cx, f) = (local("input"), local("best"), local("cx"), local("f"));
+
+        quote! {
+            impl ::dioxus_router::Routable for #name {
+                fn from_path(#input: &str) -> ::std::option::Option<Self> {
+                    // Every route is ranked by the router, so the most specific one wins, and the first one declared
+                    // when several are equally specific
+                    let mut #best: ::std::option::Option<(::dioxus_router::__private::Rank, Self)> =
+                        ::std::option::Option::None;
+
+                    #(#parsers)*
+
+                    #best.map(|(_, route)| route)
+                }
+
+                fn render<'a>(
+                    &self,
+                    #cx: &'a ::dioxus::prelude::ScopeState,
+                ) -> ::dioxus::prelude::Element<'a> {
+                    use ::dioxus::prelude::*;
+
+                    match self {
+                        #(#renders)*
+                    }
+                }
+            }
+
+            impl ::std::fmt::Display for #name {
+                fn fmt(&self, #f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    match self {
+                        #(#displays)*
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Route {
+    fn destructure(&self) -> TokenStream2 {
+        let variant = &self.variant;
+        match &self.fields {
+            Some(fields) => {
+                let names = fields.iter().map(|(name, _)| name);
+                quote! { Self::#variant { #(#names),* } }
+            }
+            None => quote! { Self::#variant },
+        }
+    }
+
+    fn parser(&self) -> TokenStream2 {
+        let (input, best, rank, params, param, key) = (
+            local("input"),
+            local("best"),
+            local("rank"),
+            local("params"),
+            local("param"),
+            local("key"),
+        );
+        let path = &self.path;
+        let fields = self.segments.iter().filter_map(|segment| match segment {
+            Segment::Static(_) => None,
+            Segment::Parameter(name, ty) => {
+                let field = name.to_string();
+                Some(quote! {
+                    let #name = ::dioxus_router::__private::decode_segment(#param(#field))
+                        .parse::<#ty>()
+                        .ok()?;
+                })
+            }
+            Segment::CatchAll(name, ty) => {
+                let field = name.to_string();
+                Some(quote! {
+                    let #name = #param(#field)
+                        .split('/')
+                        .filter(|segment| !segment.is_empty())
+                        .map(|segment| ::dioxus_router::__private::decode_segment(segment))
+                        .collect::<::std::vec::Vec<_>>()
+                        .join("/")
+                        .parse::<#ty>()
+                        .ok()?;
+                })
+            }
+        });
+        let construct = self.destructure();
+
+        quote! {
+            if let ::std::option::Option::Some((#rank, #params)) =
+                ::dioxus_router::__private::match_pattern(#path, #input)
+            {
+                let #param = |#key: &str| {
+                    #params
+                        .iter()
+                        .find(|(name, _)| name == #key)
+                        .map_or("", |(_, value)| value.as_str())
+                };
+
+                // Parameters that don't parse into their field don't match
+                let matched = (|| {
+                    #(#fields)*
+                    ::std::option::Option::Some(#construct)
+                })();
+
+                if let ::std::option::Option::Some(route) = matched {
+                    if !::std::matches!(&#best, ::std::option::Option::Some((best, _)) if *best >= #rank) {
+                        #best = ::std::option::Option::Some((#rank, route));
+                    }
+                }
+            }
+        }
+    }
+
+    fn display(&self) -> TokenStream2 {
+        let (path, f) = (local("path"), local("f"));
+        let pattern = self.destructure();
+        let writes = self.segments.iter().map(|segment| match segment {
+            Segment::Static(value) => quote! {
+                #path.push('/');
+                #path.push_str(#value);
+            },
+            Segment::Parameter(name, _) => quote! {
+                #path.push('/');
+                #path.push_str(&::dioxus_router::__private::encode_segment(&#name.to_string()));
+            },
+            Segment::CatchAll(name, _) => quote! {
+                for segment in #name.to_string().split('/').filter(|s| !s.is_empty()) {
+                    #path.push('/');
+                    #path.push_str(&::dioxus_router::__private::encode_segment(segment));
+                }
+            },
+        });
+
+        quote! {
+            #[allow(unused_variables)]
+            #pattern => {
+                let mut #path = ::std::string::String::new();
+                #(#writes)*
+                if #path.is_empty() {
+                    #path.push('/');
+                }
+                #f.write_str(&#path)
+            }
+        }
+    }
+
+    fn render(&self) -> TokenStream2 {
+        let cx = local("cx");
+        let pattern = self.destructure();
+        let component = &self.component;
+        let props = self
+            .fields
+            .iter()
+            .flatten()
+            .map(|(name, _)| quote! { #name: #name.clone(), });
+
+        quote! {
+            #pattern => #cx.render(rsx! {
+                #component { #(#props)* }
+            }),
+        }
+    }
+}
+
+/// An identifier for a local variable of the generated code that can't clash with the fields of the variants
+fn local(name: &str) -> Ident {
+    Ident::new(name, Span::mixed_site())
+}
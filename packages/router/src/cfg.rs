@@ -1,6 +1,41 @@
-#[derive(Default)]
+use crate::RouterProvider;
+use std::rc::Rc;
+
+/// Global configuration for the router.
+///
+/// The [`Router`](crate::Router) builds its configuration from its props. A `RouterCfg` can also be provided as a
+/// context above the router (for instance as a root context of the VirtualDom), in which case any field set on the
+/// context overrides the props. This lets renderers and tools like static site generators point an existing app at a
+/// different URL.
+#[derive(Default, Clone)]
 pub struct RouterCfg {
+    /// The path the app is deployed under, like `/apps/billing/v2`, or a full URL with that path.
+    ///
+    /// The path is stripped from locations before they're matched against routes, and added to the links the
+    /// [`Link`](crate::Link)s render and the locations the router navigates to.
     pub base_url: Option<String>,
+
+    /// The class added to [`Link`](crate::Link)s that point at the current route.
     pub active_class: Option<String>,
+
+    /// The location the router starts at instead of the platform's current location.
+    ///
+    /// This can be a full URL, a path in the app like `/users/3`, or a deep link with a custom scheme like
+    /// `myapp://users/3`, which starts the router at `/users/3`. Paths and deep links are joined with the `base_url`.
     pub initial_url: Option<String>,
+
+    /// The history to use instead of the platform's, like a [`MemoryHistory`](crate::MemoryHistory) to control
+    /// from tests.
+    pub history: Option<Rc<dyn RouterProvider>>,
+}
+
+impl std::fmt::Debug for RouterCfg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RouterCfg")
+            .field("base_url", &self.base_url)
+            .field("active_class", &self.active_class)
+            .field("initial_url", &self.initial_url)
+            .field("history", &self.history.is_some())
+            .finish()
+    }
 }
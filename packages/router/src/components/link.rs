@@ -6,6 +6,10 @@ use dioxus::prelude::*;
 pub struct LinkProps<'a> {
     /// The route to link to. This can be a relative path, or a full URL.
     ///
+    /// Relative paths are resolved against the part of the current path matched by the [`Route`](crate::Route) the
+    /// link is rendered in. Paths that start with a slash are paths of the app, and are prefixed with the base path
+    /// the app is deployed under.
+    ///
     /// ```rust, ignore
     /// // Absolute path
     /// Link { to: "/home", "Go Home" }
@@ -106,14 +110,20 @@ pub fn Link<'a>(cx: Scope<'a, LinkProps<'a>>) -> Element {
     };
 
     let route = use_route(cx);
-    let url = route.url();
-    let path = url.path();
-    let active = path == cx.props.to;
+    let to = match svc {
+        Some(service) if !outerlink => service.resolve_route(route.route_context.as_ref(), to),
+        _ => to.to_string(),
+    };
+    let href = match svc {
+        Some(service) if !outerlink => service.with_base(&to),
+        _ => to.clone(),
+    };
+    let active = route.url().path() == href;
     let active_class = if active { active_class_name } else { "".into() };
 
     cx.render(rsx! {
         a {
-            href: "{to}",
+            href: "{href}",
             class: format_args!("{} {}", class.unwrap_or(""), active_class),
             id: format_args!("{}", id.unwrap_or("")),
             title: format_args!("{}", title.unwrap_or("")),
@@ -122,12 +132,9 @@ pub fn Link<'a>(cx: Scope<'a, LinkProps<'a>>) -> Element {
             onclick: move |_| {
                 if !outerlink {
                     if let Some(service) = svc {
-                        service.push_route(to, cx.props.title.map(|f| f.to_string()), None);
+                        service.push_route(&to, cx.props.title.map(|f| f.to_string()), None);
                     } else {
-                        log::error!(
-                            "Attempted to create a Link to {} outside of a Router context", cx.props
-                            .to,
-                        );
+                        log::error!("Attempted to create a Link to {} outside of a Router context", to);
                     }
                 }
             },
@@ -0,0 +1,80 @@
+use crate::{use_route, use_router, LoadRoute, RouteContext};
+use dioxus::core::{Component, DynamicNode, IntoDynNode};
+use dioxus::prelude::*;
+
+/// The place where a layout route renders the route nested in it that matches the current location.
+///
+/// # Example
+///
+/// ```rust, ignore
+/// fn AdminLayout(cx: Scope) -> Element {
+///     cx.render(rsx! {
+///         nav {
+///             Link { to: "users", "Users" }
+///         }
+///         Outlet {}
+///     })
+/// }
+/// ```
+///
+/// Outside of a layout route, the outlet renders nothing.
+pub fn Outlet(cx: Scope) -> Element {
+    let router = use_router(cx);
+    let route = use_route(cx);
+
+    let parent = match &route.route_context {
+        Some(ctx) => ctx.scope,
+        None => {
+            log::error!("An Outlet was rendered outside of a Route");
+            return cx.render(rsx!(()));
+        }
+    };
+
+    let matched = match router.matched_route(Some(parent)) {
+        Some(matched) => matched,
+        None => return cx.render(rsx!(())),
+    };
+
+    cx.render(rsx! {
+        // A different route gets a fresh component, even if it renders the same one
+        std::iter::once(rsx!(RouteContent { key: "{matched.0}", route: matched }))
+    })
+}
+
+#[derive(Props, PartialEq)]
+pub(crate) struct RouteContentProps {
+    route: ScopeId,
+}
+
+/// Renders the component of a layout route when it matches.
+pub(crate) fn RouteContent(cx: Scope<RouteContentProps>) -> Element {
+    let router = use_router(cx);
+
+    // Re-render on navigation when the route is rendered in place
+    use_route(cx);
+
+    let route = cx.use_hook(|| {
+        let route = router.slots.borrow().get(&cx.props.route).cloned();
+        route.map(|route| cx.provide_context::<RouteContext>(route))
+    });
+
+    match route.as_ref().and_then(|route| route.component) {
+        Some(component) if router.should_render(cx.props.route) => {
+            let route = cx.props.route;
+            match router.needs_loading(route, &router.current_location().url) {
+                true => cx.render(rsx!(LoadRoute { route: route })),
+                false => cx.render(rsx!(MatchedComponent(component))),
+            }
+        }
+        _ => cx.render(rsx!(())),
+    }
+}
+
+/// Render a component stored in a route
+struct MatchedComponent(Component);
+
+impl<'a> IntoDynNode<'a> for MatchedComponent {
+    fn into_vnode(self, cx: &'a ScopeState) -> DynamicNode<'a> {
+        cx.component(self.0, (), "RouteComponent")
+    }
+}
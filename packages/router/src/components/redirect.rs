@@ -1,6 +1,6 @@
 use dioxus::prelude::*;
 
-use crate::use_router;
+use crate::{join_route, use_router, RouteContext, RouteRegistration};
 
 /// The props for the [`Router`](fn.Router.html) component.
 #[derive(Props)]
@@ -33,17 +33,31 @@ pub struct RedirectProps<'a> {
 /// It will replace the current route rather than pushing the current one to the stack.
 pub fn Redirect<'a>(cx: Scope<'a, RedirectProps<'a>>) -> Element {
     let router = use_router(cx);
+    let root_context = use_context::<RouteContext>(cx);
 
-    let immediate_redirect = cx.use_hook(|| {
-        if let Some(from) = cx.props.from {
-            router.register_total_route(from.to_string(), cx.scope_id());
-            false
-        } else {
-            true
-        }
+    let registration = cx.use_hook(|| {
+        let from = cx.props.from?;
+        let total_route = match root_context {
+            Some(ctx) => join_route(&ctx.total_route, from),
+            None => from.to_string(),
+        };
+
+        Some(RouteRegistration::new(
+            router.clone(),
+            RouteContext {
+                declared_route: from.to_string(),
+                total_route,
+                parent: root_context.map(|ctx| ctx.scope),
+                scope: cx.scope_id(),
+                component: None,
+                nested: false,
+                guard: None,
+                loader: None,
+            },
+        ))
     });
 
-    if *immediate_redirect || router.should_render(cx.scope_id()) {
+    if registration.is_none() || router.should_render(cx.scope_id()) {
         router.replace_route(cx.props.to, None, None);
     }
 
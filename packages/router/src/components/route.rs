@@ -1,18 +1,63 @@
-use crate::{RouteContext, RouterContext};
+use crate::{
+    join_route, use_router, Redirect, RouteContent, RouteContext, RouteGuard, RouteLoader,
+    RouterContext,
+};
+use dioxus::core::{Component, DynamicNode, VNode};
 use dioxus::prelude::*;
 
 /// Props for the [`Route`](struct.Route.html) component.
 #[derive(Props)]
 pub struct RouteProps<'a> {
-    /// The path to match.
+    /// The path to match, relative to the route this one is nested in.
     pub to: &'a str,
 
-    /// The component to render when the path matches.
+    /// Make this a layout route that renders this component when the path matches.
+    ///
+    /// The routes nested in a layout route are rendered into the [`Outlet`](crate::Outlet) of this component instead
+    /// of in place.
+    #[props(default, strip_option)]
+    pub component: Option<Component>,
+
+    /// Decide whether navigating to a location this route matches goes through.
+    #[props(default, strip_option)]
+    pub guard: Option<RouteGuard>,
+
+    /// Load the data of this route before it renders, for [`use_loader_data`](crate::use_loader_data).
+    #[props(default, strip_option)]
+    pub loader: Option<RouteLoader>,
+
+    /// The elements to render when the path matches, or the nested routes of a layout route.
+    #[props(default)]
     pub children: Element<'a>,
 }
 
 /// A component that conditionally renders children based on the current location.
 ///
+/// Only one route among the routes next to each other is rendered: the one that matches the current location most
+/// specifically, so `/users/new` wins over `/users/:id` no matter which is declared first. Routes that match equally
+/// well are picked in the order they are declared. Routes with other routes nested in them match every path that
+/// starts with their own.
+///
+/// Besides plain segments and `:parameters`, patterns can contain constrained parameters (`:id<int>`, `<alpha>`
+/// or `<alnum>`), optional segments (`:tab?` or `edit?`) and a catch-all at the end (`*rest`) that matches
+/// everything that's left. A route with only a catch-all makes a good "not found" page, since every other route
+/// wins over it:
+///
+///```rust, ignore
+/// rsx!(
+///     Router {
+///         Route { to: "/users/:id<int>", User {} }
+///         Route { to: "/users/new", NewUser {} }
+///         Route { to: "/files/*path", File {} }
+///         Route { to: "*", NotFound {} }
+///     }
+/// )
+/// ```
+///
+/// When a more specific route is declared after the route that matched first, the router only finds out when the
+/// later route is mounted. The first route is then re-rendered with the next update of the VirtualDom, so during
+/// server-side rendering call `process_events` and `render_immediate` after `rebuild` to settle on the right one.
+///
 /// # Example
 ///
 ///```rust, ignore
@@ -24,14 +69,30 @@ pub struct RouteProps<'a> {
 ///     }
 /// )
 /// ```
+///
+/// Routes are nested by giving the outer route a `component` that renders an [`Outlet`](crate::Outlet). The layout
+/// stays mounted while navigating between the routes nested in it:
+///
+///```rust, ignore
+/// rsx!(
+///     Router {
+///         Route { to: "/", Home {} }
+///         Route { to: "/admin", component: AdminLayout,
+///             Route { to: "/", component: Dashboard }
+///             Route { to: "users", component: UserList }
+///             Route { to: "users/:id", component: UserDetail }
+///         }
+///     }
+/// )
+/// ```
 pub fn Route<'a>(cx: Scope<'a, RouteProps<'a>>) -> Element {
     let router_root = use_context::<RouterContext>(cx).unwrap();
     let root_context = use_context::<RouteContext>(cx);
 
-    cx.use_hook(|| {
+    let registration = cx.use_hook(|| {
         // create a bigger, better, longer route if one above us exists
         let total_route = match root_context {
-            Some(ctx) => ctx.total_route.clone(),
+            Some(ctx) => join_route(&ctx.total_route, cx.props.to),
             None => cx.props.to.to_string(),
         };
 
@@ -39,19 +100,92 @@ pub fn Route<'a>(cx: Scope<'a, RouteProps<'a>>) -> Element {
         let route_context = cx.provide_context(RouteContext {
             declared_route: cx.props.to.to_string(),
             total_route,
+            parent: root_context.map(|ctx| ctx.scope),
+            scope: cx.scope_id(),
+            component: cx.props.component,
+            nested: false,
+            guard: cx.props.guard,
+            loader: cx.props.loader.clone(),
         });
 
         // submit our rout
-        router_root.register_total_route(route_context.total_route, cx.scope_id());
+        RouteRegistration::new(router_root.clone(), route_context)
     });
 
     log::trace!("Checking Route: {:?}", cx.props.to);
 
+    let nested = matches!(&cx.props.children, Ok(children) if has_nested_routes(children));
+    router_root.set_nested(cx.scope_id(), nested);
+
+    if registration.route.component.is_some() {
+        // The nested routes are always mounted so the router knows about them before deciding what matches
+        let in_outlet = matches!(root_context, Some(ctx) if ctx.component.is_some());
+        let scope = cx.scope_id();
+
+        return cx.render(rsx! {
+            &cx.props.children,
+            (!in_outlet).then(|| rsx!(RouteContent { route: scope }))
+        });
+    }
+
     if router_root.should_render(cx.scope_id()) {
         log::trace!("Route should render: {:?}", cx.scope_id());
+
+        let scope = cx.scope_id();
+        if router_root.needs_loading(scope, &router_root.current_location().url) {
+            return cx.render(rsx!(LoadRoute { route: scope }));
+        }
+
         cx.render(rsx!(&cx.props.children))
     } else {
         log::trace!("Route should *not* render: {:?}", cx.scope_id());
         cx.render(rsx!(()))
     }
 }
+
+/// Check if any routes are declared directly in these nodes
+fn has_nested_routes(node: &VNode) -> bool {
+    node.dynamic_nodes.iter().any(|node| match node {
+        DynamicNode::Component(component) => {
+            component.render_fn == Route as *const ()
+                || component.render_fn == Redirect as *const ()
+        }
+        DynamicNode::Fragment(nodes) => nodes.iter().any(has_nested_routes),
+        _ => false,
+    })
+}
+
+#[derive(Props, PartialEq)]
+pub(crate) struct LoadRouteProps {
+    route: ScopeId,
+}
+
+/// Suspends until the loader of a route that rendered before its data was loaded is done, and then renders the route
+/// again.
+pub(crate) async fn LoadRoute(cx: Scope<'_, LoadRouteProps>) -> Element {
+    let router = use_router(cx);
+    let route = cx.props.route;
+
+    use_future(cx, (), |_| router.load_current(route)).await;
+
+    cx.render(rsx!(()))
+}
+
+/// Registers a route with the router for as long as the route is mounted.
+pub(crate) struct RouteRegistration {
+    pub(crate) route: RouteContext,
+    router: RouterContext,
+}
+
+impl RouteRegistration {
+    pub(crate) fn new(router: RouterContext, route: RouteContext) -> Self {
+        router.register_total_route(route.clone());
+        Self { route, router }
+    }
+}
+
+impl Drop for RouteRegistration {
+    fn drop(&mut self) {
+        self.router.unregister_route(self.route.scope)
+    }
+}
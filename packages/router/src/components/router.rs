@@ -10,10 +10,10 @@ pub struct RouterProps<'a> {
     /// regardless of the path.
     pub children: Element<'a>,
 
-    /// The URL to point at
+    /// The path the app is deployed under, like `/apps/billing/v2`, when it's not deployed at the root of the domain.
     ///
-    /// This will be used to trim any latent segments from the URL when your app is
-    /// not deployed to the root of the domain.
+    /// The path is stripped from locations before they're matched against routes, and added to links and the
+    /// locations the router navigates to.
     pub base_url: Option<&'a str>,
 
     /// Hook into the router when the route is changed.
@@ -28,30 +28,37 @@ pub struct RouterProps<'a> {
     /// By default set to `"active"`.
     pub active_class: Option<&'a str>,
 
-    /// Set the initial url.
+    /// Set the initial url: a full URL, a path like `/users/3` or a deep link like `myapp://users/3`.
     pub initial_url: Option<String>,
 }
 
 /// A component that conditionally renders children based on the current location of the app.
 ///
-/// Uses BrowserRouter in the browser and HashRouter everywhere else.
-///
-/// Will fallback to HashRouter is BrowserRouter is not available, or through configuration.
+/// Uses the browser's history on the web and a [`MemoryHistory`](crate::MemoryHistory) everywhere else, unless a
+/// different history is passed in through a [`RouterCfg`] context.
 #[allow(non_snake_case)]
 pub fn Router<'a>(cx: Scope<'a, RouterProps<'a>>) -> Element {
     let svc = cx.use_hook(|| {
-        cx.provide_context(RouterService::new(
-            cx,
-            RouterCfg {
-                base_url: cx.props.base_url.map(|s| s.to_string()),
-                active_class: cx.props.active_class.map(|s| s.to_string()),
-                initial_url: cx.props.initial_url.clone(),
-            },
-        ))
+        let mut cfg = RouterCfg {
+            base_url: cx.props.base_url.map(|s| s.to_string()),
+            active_class: cx.props.active_class.map(|s| s.to_string()),
+            initial_url: cx.props.initial_url.clone(),
+            history: None,
+        };
+
+        // A configuration provided from above (by a renderer or a static site generator) takes precedence
+        if let Some(overrides) = cx.consume_context::<RouterCfg>() {
+            cfg.base_url = overrides.base_url.or(cfg.base_url);
+            cfg.active_class = overrides.active_class.or(cfg.active_class);
+            cfg.initial_url = overrides.initial_url.or(cfg.initial_url);
+            cfg.history = overrides.history;
+        }
+
+        cx.provide_context(RouterService::new(cx, cfg))
     });
 
     // next time we run the rout_found will be filled
-    if svc.route_found.get().is_none() {
+    if svc.route_found.borrow().is_empty() {
         cx.props.onchange.call(svc.clone());
     }
 
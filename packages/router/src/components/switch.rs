@@ -0,0 +1,29 @@
+use crate::{use_route, use_router, Routable};
+use dioxus::prelude::*;
+
+/// Render the route of a [`Routable`] enum that matches the current location.
+///
+/// The `Switch` has to be rendered inside a [`Router`](crate::Router). If no route matches, it renders nothing.
+///
+/// # Example
+///
+/// ```rust, ignore
+/// fn app(cx: Scope) -> Element {
+///     cx.render(rsx! {
+///         Router {
+///             Switch::<Route> {}
+///         }
+///     })
+/// }
+/// ```
+pub fn Switch<R: Routable>(cx: Scope) -> Element {
+    let router = use_router(cx);
+
+    // Re-render on navigation
+    use_route(cx);
+
+    match router.current_route::<R>() {
+        Some(route) => route.render(cx),
+        None => cx.render(rsx!(())),
+    }
+}
@@ -0,0 +1,279 @@
+use crate::{ParsedRoute, RouterService};
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    rc::Weak,
+    str::FromStr,
+};
+use url::Url;
+
+/// The history the [`RouterService`] reads its location from and writes navigation to.
+///
+/// On the web the router uses the browser's history, everywhere else it uses a [`MemoryHistory`]. A different
+/// provider can be passed with [`RouterCfg::history`](crate::RouterCfg::history).
+pub trait RouterProvider {
+    /// Add a new entry to the history, discarding any entries ahead of the current one.
+    fn push(&self, route: &ParsedRoute);
+
+    /// Replace the current entry of the history.
+    fn replace(&self, route: &ParsedRoute);
+
+    /// Move `delta` entries through the history, backwards if it's negative, because the router did.
+    ///
+    /// The router already knows where it's going, so this shouldn't navigate it.
+    fn go(&self, delta: isize);
+
+    /// Get the platform's native location, for [`RouterService::native_location`].
+    fn native_location(&self) -> Box<dyn Any>;
+
+    /// Get the location the router starts at.
+    fn init_location(&self) -> ParsedRoute;
+
+    /// Called once the router is created, so the provider can tell it about navigation that happens outside of the
+    /// router (like the browser's back button).
+    fn attach_listeners(&self, svc: Weak<RouterService>);
+}
+
+/// A history that lives in memory, for platforms without a history of their own and for tests.
+///
+/// The history keeps every entry, so it can move back and forward through them with [`MemoryHistory::go`]. Moving
+/// through the history navigates the router it's attached to.
+///
+/// # Example
+///
+/// ```rust, ignore
+/// let history = Rc::new(MemoryHistory::default().with_initial_path("/users"));
+///
+/// let mut dom = VirtualDom::new(app).with_root_context(RouterCfg {
+///     history: Some(history.clone()),
+///     ..Default::default()
+/// });
+///
+/// // later, for instance from a back button
+/// history.go(-1);
+/// ```
+pub struct MemoryHistory {
+    entries: RefCell<Vec<ParsedRoute>>,
+    current: Cell<usize>,
+    router: RefCell<Weak<RouterService>>,
+}
+
+impl Default for MemoryHistory {
+    fn default() -> Self {
+        Self {
+            entries: RefCell::new(vec![ParsedRoute {
+                url: Url::from_str("app:///").unwrap(),
+                title: None,
+                serialized_state: None,
+            }]),
+            current: Cell::new(0),
+            router: RefCell::new(Weak::new()),
+        }
+    }
+}
+
+impl MemoryHistory {
+    /// Create a history with a single entry at the root of the app.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start the history at the given path instead of the root.
+    pub fn with_initial_path(self, path: &str) -> Self {
+        {
+            let mut entries = self.entries.borrow_mut();
+            let url = entries[0].url.join(path).unwrap_or_else(|_| {
+                panic!("MemoryHistory expects a valid path, but got '{}'", path)
+            });
+            entries[0].url = url;
+        }
+        self
+    }
+
+    /// Get the current entry of the history.
+    pub fn location(&self) -> ParsedRoute {
+        self.entries.borrow()[self.current.get()].clone()
+    }
+
+    /// Check whether there's an entry before the current one.
+    pub fn can_go_back(&self) -> bool {
+        self.current.get() > 0
+    }
+
+    /// Check whether there's an entry after the current one.
+    pub fn can_go_forward(&self) -> bool {
+        self.current.get() + 1 < self.entries.borrow().len()
+    }
+
+    /// Move back one entry. Returns `false` if there's no entry to move to.
+    pub fn back(&self) -> bool {
+        self.go(-1)
+    }
+
+    /// Move forward one entry. Returns `false` if there's no entry to move to.
+    pub fn forward(&self) -> bool {
+        self.go(1)
+    }
+
+    /// Move `delta` entries through the history, backwards if it's negative, and navigate the router to the entry.
+    ///
+    /// Returns `false` and does nothing if there's no entry that far away.
+    pub fn go(&self, delta: isize) -> bool {
+        let router = self.router.borrow().upgrade();
+
+        match router {
+            // The router moves the history along with it
+            Some(router) => router.go(delta),
+            None => self.move_by(delta),
+        }
+    }
+
+    fn move_by(&self, delta: isize) -> bool {
+        match self.current.get().checked_add_signed(delta) {
+            Some(target) if target < self.entries.borrow().len() => {
+                self.current.set(target);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl RouterProvider for MemoryHistory {
+    fn push(&self, route: &ParsedRoute) {
+        let mut entries = self.entries.borrow_mut();
+        let current = self.current.get();
+
+        entries.truncate(current + 1);
+        entries.push(route.clone());
+        self.current.set(current + 1);
+    }
+
+    fn replace(&self, route: &ParsedRoute) {
+        self.entries.borrow_mut()[self.current.get()] = route.clone();
+    }
+
+    fn go(&self, delta: isize) {
+        self.move_by(delta);
+    }
+
+    fn native_location(&self) -> Box<dyn Any> {
+        Box::new(self.location())
+    }
+
+    fn init_location(&self) -> ParsedRoute {
+        self.location()
+    }
+
+    fn attach_listeners(&self, svc: Weak<RouterService>) {
+        *self.router.borrow_mut() = svc;
+    }
+}
+
+/// A history for rendering a route on the server, like for an HTTP request.
+///
+/// The history starts at the path that was requested, and keeps track of where the router goes from there. After
+/// rendering, it tells whether a route matched the request and whether the router was redirected, so the server can
+/// respond with a 404 or a real 3xx instead of the page:
+///
+/// ```rust, ignore
+/// let history = Rc::new(ServerHistory::new(request.uri().path()));
+///
+/// let mut dom = VirtualDom::new(app).with_root_context(RouterCfg {
+///     history: Some(history.clone()),
+///     ..Default::default()
+/// });
+/// let _ = dom.rebuild();
+///
+/// if let Some(location) = history.redirect() {
+///     return redirect_to(location);
+/// }
+/// ```
+pub struct ServerHistory {
+    history: MemoryHistory,
+    request: String,
+}
+
+impl ServerHistory {
+    /// Create a history for the requested path, including its query, like `/users/3?tab=posts`.
+    pub fn new(path: &str) -> Self {
+        let history = MemoryHistory::default().with_initial_path(path);
+        let request = href(&history.location().url);
+
+        Self { history, request }
+    }
+
+    /// Get the current location of the router.
+    pub fn location(&self) -> ParsedRoute {
+        self.history.location()
+    }
+
+    /// Get the path (with its query) the router went to instead of the requested path, like after rendering a
+    /// [`Redirect`](crate::Redirect).
+    pub fn redirect(&self) -> Option<String> {
+        let location = href(&self.history.location().url);
+        (location != self.request).then_some(location)
+    }
+
+    /// Get the full pattern of the innermost route that matches the current location, like `/users/:id`.
+    pub fn matched_route(&self) -> Option<String> {
+        let router = self.history.router.borrow().upgrade()?;
+        let matched = *router
+            .matching_routes(&self.history.location().url)
+            .last()?;
+        let route = router.slots.borrow().get(&matched)?.total_route.clone();
+        Some(route)
+    }
+
+    /// Check whether no route matches the current location, or only a "not found" route: a route at the root whose
+    /// pattern is empty or only a catch-all, like `*`.
+    pub fn is_not_found(&self) -> bool {
+        match self.matched_route() {
+            Some(route) => {
+                let segments = crate::pattern::path_segments(&route);
+                segments.is_empty() || (segments.len() == 1 && segments[0].starts_with('*'))
+            }
+            None => true,
+        }
+    }
+}
+
+impl RouterProvider for ServerHistory {
+    fn push(&self, route: &ParsedRoute) {
+        self.history.push(route);
+    }
+
+    fn replace(&self, route: &ParsedRoute) {
+        self.history.replace(route);
+    }
+
+    fn go(&self, delta: isize) {
+        RouterProvider::go(&self.history, delta);
+    }
+
+    fn native_location(&self) -> Box<dyn Any> {
+        Box::new(self.location())
+    }
+
+    fn init_location(&self) -> ParsedRoute {
+        self.location()
+    }
+
+    fn attach_listeners(&self, svc: Weak<RouterService>) {
+        self.history.attach_listeners(svc);
+    }
+}
+
+/// The path of a url, with its query and fragment
+fn href(url: &Url) -> String {
+    let mut href = url.path().to_string();
+    if let Some(query) = url.query() {
+        href.push('?');
+        href.push_str(query);
+    }
+    if let Some(fragment) = url.fragment() {
+        href.push('#');
+        href.push_str(fragment);
+    }
+    href
+}
@@ -0,0 +1,33 @@
+use crate::{use_router, RouteContext};
+use dioxus::{core::ScopeState, prelude::use_context};
+use std::rc::Rc;
+
+/// Get the data the [`RouteLoader`](crate::RouteLoader) of the route this component is rendered in loaded for the
+/// current location.
+///
+/// The routes the component is nested in are searched from the innermost outwards, for the first one whose loader
+/// loaded a `T`. Returns `None` outside of a route with a loader that loads a `T`.
+///
+/// ```rust, ignore
+/// fn UserPage(cx: Scope) -> Element {
+///     let user = use_loader_data::<User>(cx).unwrap();
+///
+///     cx.render(rsx!(h1 { "{user.name}" }))
+/// }
+/// ```
+pub fn use_loader_data<T: 'static>(cx: &ScopeState) -> Option<Rc<T>> {
+    let router = use_router(cx);
+    let mut route = use_context::<RouteContext>(cx).map(|ctx| ctx.scope);
+
+    while let Some(scope) = route {
+        if let Some(data) = router.loaded_data(scope) {
+            if let Ok(data) = data.downcast::<T>() {
+                return Some(data);
+            }
+        }
+
+        route = router.slots.borrow().get(&scope).and_then(|ctx| ctx.parent);
+    }
+
+    None
+}
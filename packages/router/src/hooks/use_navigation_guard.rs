@@ -0,0 +1,60 @@
+use crate::{use_router, GuardDecision, Navigation, RouterContext};
+use dioxus::core::{ScopeId, ScopeState};
+use std::rc::Rc;
+
+/// Guard every navigation for as long as the component is mounted.
+///
+/// The guard is asked about every navigation before the guards of the routes, so it can block leaving the current
+/// location, like for an unsaved-changes prompt:
+///
+/// ```rust, ignore
+/// fn Editor(cx: Scope) -> Element {
+///     let unsaved = use_state(cx, || false);
+///
+///     let current = unsaved.current();
+///     use_navigation_guard(cx, move |_| match *current {
+///         true => GuardDecision::Block,
+///         false => GuardDecision::Allow,
+///     });
+///
+///     // ...
+/// }
+/// ```
+///
+/// The guard is replaced every time the component renders, so it can capture the component's current state.
+pub fn use_navigation_guard(
+    cx: &ScopeState,
+    guard: impl Fn(&Navigation) -> GuardDecision + 'static,
+) {
+    let router = use_router(cx);
+
+    let registration = cx.use_hook(|| GuardRegistration {
+        router: router.clone(),
+        scope: cx.scope_id(),
+    });
+
+    let guard = Rc::new(guard);
+    let mut guards = registration.router.guards.borrow_mut();
+    match guards
+        .iter_mut()
+        .find(|(scope, _)| *scope == registration.scope)
+    {
+        Some((_, current)) => *current = guard,
+        None => guards.push((registration.scope, guard)),
+    }
+}
+
+/// Removes the guard of a component when it's unmounted
+struct GuardRegistration {
+    router: RouterContext,
+    scope: ScopeId,
+}
+
+impl Drop for GuardRegistration {
+    fn drop(&mut self) {
+        self.router
+            .guards
+            .borrow_mut()
+            .retain(|(scope, _)| *scope != self.scope);
+    }
+}
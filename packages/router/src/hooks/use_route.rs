@@ -20,6 +20,7 @@ pub fn use_route(cx: &ScopeState) -> &UseRoute {
             state: UseRoute {
                 route_context,
                 route: router.current_location(),
+                params: Vec::new(),
             },
             router,
             scope: cx.scope_id(),
@@ -27,6 +28,14 @@ pub fn use_route(cx: &ScopeState) -> &UseRoute {
     });
 
     handle.state.route = handle.router.current_location();
+    handle.state.params = match &handle.state.route_context {
+        Some(ctx) => handle
+            .router
+            .route_match(ctx.scope)
+            .map(|matched| matched.params)
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
 
     &handle.state
 }
@@ -37,6 +46,9 @@ pub struct UseRoute {
 
     /// If `use_route` is used inside a `Route` component this has some context otherwise `None`.
     pub(crate) route_context: Option<RouteContext>,
+
+    /// The parameters of the route `use_route` is used in, with their values in the current location.
+    pub(crate) params: Vec<(String, String)>,
 }
 
 impl UseRoute {
@@ -54,6 +66,16 @@ impl UseRoute {
         serde_urlencoded::from_str(query).ok()
     }
 
+    /// Get the state stored with the current entry of the history, deserialized from JSON.
+    ///
+    /// Returns `None` if the entry has no state, or if it isn't a `T`. State is stored with
+    /// [`NavigationBuilder::state`](crate::NavigationBuilder::state).
+    #[cfg(feature = "query")]
+    pub fn state<T: serde::de::DeserializeOwned>(&self) -> Option<T> {
+        let state = self.route.serialized_state.as_deref()?;
+        serde_json::from_str(state).ok()
+    }
+
     /// Get the first query parameter given the parameter name.
     ///
     /// If you need to get more than one parameter, use [`query_pairs`] on the [`Url`] instead.
@@ -83,18 +105,14 @@ impl UseRoute {
     /// value will be parsed into the type specified by `T` by calling
     /// `value.parse::<T>()`. This method returns `None` if the named
     /// parameter does not exist in the current path.
+    ///
+    /// The value of a catch-all parameter (`*rest`) is every segment it matched, joined by slashes. Optional
+    /// parameters that aren't in the path return `None`.
     pub fn segment(&self, name: &str) -> Option<&str> {
-        let total_route = match self.route_context {
-            None => self.route.url.path(),
-            Some(ref ctx) => &ctx.total_route,
-        };
-
-        let index = total_route
-            .trim_start_matches('/')
-            .split('/')
-            .position(|segment| segment.starts_with(':') && &segment[1..] == name)?;
-
-        self.route.url.path_segments()?.nth(index)
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
     }
 
     /// Get the named parameter from the path, as defined in your router. The
@@ -2,8 +2,12 @@
 #![warn(missing_docs)]
 
 mod hooks {
+    mod use_loader_data;
+    mod use_navigation_guard;
     mod use_route;
     mod use_router;
+    pub use use_loader_data::*;
+    pub use use_navigation_guard::*;
     pub use use_route::*;
     pub use use_router::*;
 }
@@ -13,20 +17,33 @@ mod components {
     #![allow(non_snake_case)]
 
     mod link;
+    mod outlet;
     mod redirect;
     mod route;
     mod router;
+    mod switch;
 
     pub use link::*;
+    pub use outlet::*;
     pub use redirect::*;
     pub use route::*;
     pub use router::*;
+    pub use switch::*;
 }
 pub use components::*;
 
 mod cfg;
+mod history;
+mod navigation;
+mod pattern;
+mod routable;
 mod routecontext;
 mod service;
 
+pub use cfg::RouterCfg;
+pub use dioxus_router_macro::Routable;
+pub use history::*;
+pub use navigation::*;
+pub use routable::*;
 pub use routecontext::*;
 pub use service::*;
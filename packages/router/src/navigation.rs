@@ -0,0 +1,209 @@
+use crate::RouterService;
+use futures_util::future::LocalBoxFuture;
+use std::{any::Any, future::Future, rc::Rc};
+use url::Url;
+
+/// A navigation that is about to happen, as seen by a guard.
+#[derive(Debug, Clone)]
+pub struct Navigation {
+    /// The location the router is at
+    pub from: Url,
+
+    /// The location the router is going to
+    pub to: Url,
+}
+
+/// What a navigation guard decides to do with a [`Navigation`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuardDecision {
+    /// Let the navigation happen
+    Allow,
+
+    /// Stay at the current location
+    Block,
+
+    /// Go somewhere else instead, resolved against the location the navigation was going to
+    Redirect(String),
+}
+
+/// A guard on a [`Route`](crate::Route), run before navigating to a location the route matches.
+///
+/// ```rust, ignore
+/// fn logged_in(nav: &Navigation) -> GuardDecision {
+///     match is_logged_in() {
+///         true => GuardDecision::Allow,
+///         false => GuardDecision::Redirect(format!("/login?next={}", nav.to.path())),
+///     }
+/// }
+///
+/// rsx!(Route { to: "/admin", guard: logged_in, Admin {} })
+/// ```
+pub type RouteGuard = fn(&Navigation) -> GuardDecision;
+
+/// What a [`RouteLoader`] gets to load the data of a route with.
+#[derive(Debug, Clone)]
+pub struct LoaderArgs {
+    /// The location the route is loaded for
+    pub url: Url,
+
+    pub(crate) params: Vec<(String, String)>,
+}
+
+impl LoaderArgs {
+    /// Get the named parameter of the route, like [`UseRoute::segment`](crate::UseRoute::segment).
+    pub fn segment(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Loads the data of a [`Route`](crate::Route) before it renders.
+///
+/// When navigating to a location, the loaders of the routes that match it run first, and the current route stays
+/// rendered until all of them are done. The route's components read the data with
+/// [`use_loader_data`](crate::use_loader_data). If a route with a loader is rendered before its data was loaded (like
+/// when the app starts), it suspends until the data is there.
+///
+/// ```rust, ignore
+/// async fn load_user(args: LoaderArgs) -> User {
+///     fetch_user(args.segment("id").unwrap()).await
+/// }
+///
+/// rsx!(Route { to: "/users/:id", loader: RouteLoader::new(load_user), UserPage {} })
+/// ```
+#[derive(Clone)]
+pub struct RouteLoader {
+    load: Rc<dyn Fn(LoaderArgs) -> LocalBoxFuture<'static, Rc<dyn Any>>>,
+}
+
+impl RouteLoader {
+    /// Create a loader from an async function.
+    pub fn new<F, Fut, T>(load: F) -> Self
+    where
+        F: Fn(LoaderArgs) -> Fut + 'static,
+        Fut: Future<Output = T> + 'static,
+        T: 'static,
+    {
+        Self {
+            load: Rc::new(move |args| {
+                let fut = load(args);
+                Box::pin(async move { Rc::new(fut.await) as Rc<dyn Any> })
+            }),
+        }
+    }
+
+    pub(crate) fn load(&self, args: LoaderArgs) -> LocalBoxFuture<'static, Rc<dyn Any>> {
+        (self.load)(args)
+    }
+}
+
+impl std::fmt::Debug for RouteLoader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RouteLoader").finish_non_exhaustive()
+    }
+}
+
+/// Builds a navigation from a path, a typed query and a typed state, started with
+/// [`RouterService::navigate`](crate::RouterService::navigate).
+///
+/// ```rust, ignore
+/// #[derive(Serialize, Deserialize)]
+/// struct Filters {
+///     search: String,
+///     page: usize,
+/// }
+///
+/// router
+///     .navigate("/users")
+///     .query(&Filters { search: "ada".into(), page: 2 })?
+///     .state(&scroll_position)?
+///     .push();
+/// ```
+///
+/// The current route's query and state are read back with [`UseRoute::query`](crate::UseRoute::query) and
+/// [`UseRoute::state`](crate::UseRoute::state).
+#[must_use = "a navigation does nothing until it's pushed or replaced"]
+pub struct NavigationBuilder<'a> {
+    router: &'a RouterService,
+    path: String,
+    query: Option<String>,
+    title: Option<String>,
+    state: Option<String>,
+}
+
+impl<'a> NavigationBuilder<'a> {
+    pub(crate) fn new(router: &'a RouterService, path: &str) -> Self {
+        Self {
+            router,
+            path: path.to_string(),
+            query: None,
+            title: None,
+            state: None,
+        }
+    }
+
+    /// Set the query string of the location, replacing any query the path has.
+    #[cfg(feature = "query")]
+    pub fn query<T: serde::Serialize>(mut self, query: &T) -> Result<Self, NavigationError> {
+        self.query = Some(serde_urlencoded::to_string(query)?);
+        Ok(self)
+    }
+
+    /// Set the state stored with the history entry, serialized as JSON.
+    #[cfg(feature = "query")]
+    pub fn state<T: serde::Serialize>(mut self, state: &T) -> Result<Self, NavigationError> {
+        self.state = Some(serde_json::to_string(state)?);
+        Ok(self)
+    }
+
+    /// Set the title of the history entry.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Push the location as a new entry of the history.
+    pub fn push(self) {
+        let (router, route, title, state) = self.into_parts();
+        router.push_route(&route, title, state);
+    }
+
+    /// Replace the current entry of the history with the location.
+    pub fn replace(self) {
+        let (router, route, title, state) = self.into_parts();
+        router.replace_route(&route, title, state);
+    }
+
+    fn into_parts(self) -> (&'a RouterService, String, Option<String>, Option<String>) {
+        let route = match self.query {
+            Some(query) => {
+                let path = match self.path.split_once('?') {
+                    Some((path, _)) => path,
+                    None => &self.path,
+                };
+                match query.is_empty() {
+                    true => path.to_string(),
+                    false => format!("{}?{}", path, query),
+                }
+            }
+            None => self.path,
+        };
+
+        (self.router, route, self.title, self.state)
+    }
+}
+
+/// An error building a navigation with a [`NavigationBuilder`].
+#[cfg(feature = "query")]
+#[derive(Debug, thiserror::Error)]
+pub enum NavigationError {
+    /// The query couldn't be serialized into a query string
+    #[error("failed to serialize the query: {0}")]
+    Query(#[from] serde_urlencoded::ser::Error),
+
+    /// The state couldn't be serialized
+    #[error("failed to serialize the state: {0}")]
+    State(#[from] serde_json::Error),
+}
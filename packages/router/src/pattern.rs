@@ -0,0 +1,210 @@
+//! Matching paths against the patterns of routes.
+//!
+//! A pattern is a list of segments separated by slashes:
+//!
+//! | Segment        | Matches                                                     |
+//! | -------------- | ----------------------------------------------------------- |
+//! | `users`        | exactly `users`                                             |
+//! | `:id`          | any segment, available as the `id` parameter                |
+//! | `:id<int>`     | a segment that passes the `int` constraint                  |
+//! | `:id?`, `new?` | the segment if it's there, or nothing                       |
+//! | `*rest`        | everything that's left, including nothing (only at the end) |
+//!
+//! The constraints are `int` (an integer), `alpha` (letters) and `alnum` (letters and digits).
+
+use std::cmp::Ordering;
+
+/// How specific a pattern was in matching a path. More specific matches win over less specific ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rank {
+    /// The empty pattern matches everything, and loses to every other pattern
+    fallback: bool,
+
+    /// How specific each matched segment was, starting at the root
+    segments: Vec<u8>,
+
+    /// Whether a catch-all segment matched the end of the path
+    catch_all: bool,
+}
+
+impl Ord for Rank {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .fallback
+            .cmp(&self.fallback)
+            .then_with(|| self.segments.cmp(&other.segments))
+            .then_with(|| other.catch_all.cmp(&self.catch_all))
+    }
+}
+
+impl PartialOrd for Rank {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A successful match of a pattern against a path
+#[derive(Debug, Clone)]
+pub(crate) struct RouteMatch {
+    pub(crate) rank: Rank,
+
+    /// The parameters of the pattern, with their values
+    pub(crate) params: Vec<(String, String)>,
+
+    /// How many segments of the path the pattern matched
+    pub(crate) len: usize,
+}
+
+enum Segment<'a> {
+    Static(&'a str),
+    Parameter(&'a str, Option<&'a str>),
+    CatchAll(&'a str),
+}
+
+impl<'a> Segment<'a> {
+    fn parse(segment: &'a str) -> (Self, bool) {
+        let (segment, optional) = match segment.strip_suffix('?') {
+            Some(segment) => (segment, true),
+            None => (segment, false),
+        };
+
+        let parsed = if let Some(name) = segment.strip_prefix('*') {
+            Segment::CatchAll(name)
+        } else if let Some(param) = segment.strip_prefix(':') {
+            match param.split_once('<') {
+                Some((name, constraint)) => {
+                    Segment::Parameter(name, Some(constraint.trim_end_matches('>')))
+                }
+                None => Segment::Parameter(param, None),
+            }
+        } else {
+            Segment::Static(segment)
+        };
+
+        (parsed, optional)
+    }
+
+    /// How specific the segment is: static segments beat constrained parameters, which beat any other parameter
+    fn weight(&self) -> u8 {
+        match self {
+            Segment::Static(_) => 3,
+            Segment::Parameter(_, Some(_)) => 2,
+            Segment::Parameter(_, None) => 1,
+            Segment::CatchAll(_) => 0,
+        }
+    }
+
+    fn matches(&self, value: &str) -> bool {
+        match self {
+            Segment::Static(segment) => *segment == value,
+            Segment::Parameter(_, None) => true,
+            Segment::Parameter(_, Some(constraint)) => satisfies(constraint, value),
+            Segment::CatchAll(_) => true,
+        }
+    }
+}
+
+fn satisfies(constraint: &str, value: &str) -> bool {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+
+    match constraint {
+        "int" => !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()),
+        "alpha" => value.chars().all(char::is_alphabetic),
+        "alnum" => value.chars().all(char::is_alphanumeric),
+        _ => {
+            log::error!("Unknown route constraint <{}>", constraint);
+            false
+        }
+    }
+}
+
+/// Split a path into its segments, ignoring empty ones
+pub(crate) fn path_segments(path: &str) -> Vec<&str> {
+    path.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+/// Match a pattern against the segments of a path, or only the start of them when `allow_prefix` is set.
+///
+/// If there are several ways to match (because of optional segments), the most specific one is returned.
+pub(crate) fn match_route(pattern: &str, path: &[&str], allow_prefix: bool) -> Option<RouteMatch> {
+    if pattern.is_empty() {
+        return Some(RouteMatch {
+            rank: Rank {
+                fallback: true,
+                segments: Vec::new(),
+                catch_all: false,
+            },
+            params: Vec::new(),
+            len: 0,
+        });
+    }
+
+    let pattern: Vec<_> = path_segments(pattern)
+        .into_iter()
+        .map(Segment::parse)
+        .collect();
+
+    let mut best = None;
+    let mut current = RouteMatch {
+        rank: Rank {
+            fallback: false,
+            segments: Vec::new(),
+            catch_all: false,
+        },
+        params: Vec::new(),
+        len: 0,
+    };
+    match_segments(&pattern, path, allow_prefix, &mut current, &mut best);
+    best
+}
+
+fn match_segments(
+    pattern: &[(Segment, bool)],
+    path: &[&str],
+    allow_prefix: bool,
+    current: &mut RouteMatch,
+    best: &mut Option<RouteMatch>,
+) {
+    let ((segment, optional), rest) = match pattern.split_first() {
+        Some(first) => first,
+        None => {
+            if (path.is_empty() || allow_prefix)
+                && !matches!(best, Some(best) if best.rank >= current.rank)
+            {
+                *best = Some(current.clone());
+            }
+            return;
+        }
+    };
+
+    if let Segment::CatchAll(name) = segment {
+        let mut matched = current.clone();
+        if !name.is_empty() {
+            matched.params.push((name.to_string(), path.join("/")));
+        }
+        matched.rank.catch_all = true;
+        matched.len += path.len();
+        return match_segments(&[], &[], allow_prefix, &mut matched, best);
+    }
+
+    if let Some((value, path_rest)) = path.split_first() {
+        if segment.matches(value) {
+            let params = current.params.len();
+            current.rank.segments.push(segment.weight());
+            current.len += 1;
+            if let Segment::Parameter(name, _) = segment {
+                current.params.push((name.to_string(), value.to_string()));
+            }
+
+            match_segments(rest, path_rest, allow_prefix, current, best);
+
+            current.params.truncate(params);
+            current.rank.segments.pop();
+            current.len -= 1;
+        }
+    }
+
+    if *optional {
+        match_segments(rest, path, allow_prefix, current, best);
+    }
+}
@@ -0,0 +1,78 @@
+use dioxus::prelude::*;
+use std::fmt::Display;
+use url::Url;
+
+/// A set of typed routes, usually an enum with [`#[derive(Routable)]`](derive@crate::Routable).
+///
+/// A route can be parsed from a path, written back to one with [`Display`] and rendered by a [`Switch`](crate::Switch).
+/// Since the parameters of each route are fields of the enum, a typo in a link or a parameter of the wrong type is a
+/// compile error instead of a route that silently never matches.
+///
+/// # Example
+///
+/// ```rust, ignore
+/// #[derive(Routable, Clone, PartialEq)]
+/// enum Route {
+///     #[route("/")]
+///     Home,
+///
+///     #[route("/blog/:id")]
+///     BlogPost { id: usize },
+/// }
+///
+/// assert_eq!(Route::from_path("/blog/7"), Some(Route::BlogPost { id: 7 }));
+/// assert_eq!(Route::BlogPost { id: 7 }.to_string(), "/blog/7");
+/// ```
+pub trait Routable: Sized + Clone + Display + 'static {
+    /// Parse a route from a path like `/blog/7`, returning `None` if no route matches.
+    ///
+    /// Like the [`Route`](crate::Route) component, the most specific route wins, and equally specific routes are tried
+    /// in the order they are declared.
+    fn from_path(path: &str) -> Option<Self>;
+
+    /// Parse a route from the path of a [`Url`], ignoring its query and fragment.
+    fn from_url(url: &Url) -> Option<Self> {
+        Self::from_path(url.path())
+    }
+
+    /// Render the component of the route.
+    fn render<'a>(&self, cx: &'a ScopeState) -> Element<'a>;
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use crate::pattern::{match_route, path_segments};
+    use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+    use std::borrow::Cow;
+
+    pub use crate::pattern::Rank;
+
+    /// The characters that can't appear in a path segment unescaped
+    const SEGMENT: &AsciiSet = &CONTROLS
+        .add(b' ')
+        .add(b'"')
+        .add(b'#')
+        .add(b'%')
+        .add(b'/')
+        .add(b'<')
+        .add(b'>')
+        .add(b'?')
+        .add(b'`')
+        .add(b'{')
+        .add(b'}');
+
+    pub fn decode_segment(segment: &str) -> Cow<'_, str> {
+        percent_decode_str(segment).decode_utf8_lossy()
+    }
+
+    pub fn encode_segment(segment: &str) -> Cow<'_, str> {
+        utf8_percent_encode(segment, SEGMENT).into()
+    }
+
+    /// Match a whole path against a route pattern, returning how specific the match was and the (still encoded)
+    /// parameters
+    pub fn match_pattern(pattern: &str, path: &str) -> Option<(Rank, Vec<(String, String)>)> {
+        match_route(pattern, &path_segments(path), false)
+            .map(|matched| (matched.rank, matched.params))
+    }
+}
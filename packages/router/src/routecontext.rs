@@ -1,8 +1,11 @@
+use crate::{RouteGuard, RouteLoader};
+use dioxus::core::{Component, ScopeId};
+
 /// A `RouteContext` is a context that is provided by [`Route`](fn.Route.html) components.
 ///
 /// This signals to all child [`Route`] and [`Link`] components that they are
 /// currently nested under this route.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct RouteContext {
     /// The `declared_route` is the sub-piece of the route that matches this pattern.
     ///
@@ -21,4 +24,36 @@ pub struct RouteContext {
     /// "/level0/level1/:id"
     /// ```
     pub total_route: String,
+
+    /// The route this route is nested in
+    pub(crate) parent: Option<ScopeId>,
+
+    /// The scope of the route itself
+    pub(crate) scope: ScopeId,
+
+    /// The component rendered into the parent's [`Outlet`](crate::Outlet), for layout routes
+    pub(crate) component: Option<Component>,
+
+    /// Whether other routes are nested in this one
+    pub(crate) nested: bool,
+
+    /// Decides whether navigating to a location this route matches goes through
+    pub(crate) guard: Option<RouteGuard>,
+
+    /// Loads the data of this route before it renders
+    pub(crate) loader: Option<RouteLoader>,
+}
+
+impl std::fmt::Debug for RouteContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RouteContext")
+            .field("declared_route", &self.declared_route)
+            .field("total_route", &self.total_route)
+            .field("parent", &self.parent)
+            .field("scope", &self.scope)
+            .field("nested", &self.nested)
+            .field("guard", &self.guard.is_some())
+            .field("loader", &self.loader)
+            .finish()
+    }
 }
@@ -1,19 +1,34 @@
-// todo: how does router work in multi-window contexts?
-// does each window have its own router? probably, lol
-
-use crate::cfg::RouterCfg;
+use crate::{
+    cfg::RouterCfg,
+    pattern::{match_route, path_segments, Rank, RouteMatch},
+    GuardDecision, LoaderArgs, Navigation, NavigationBuilder, Routable, RouteContext,
+    RouterProvider,
+};
 use dioxus::core::{ScopeId, ScopeState, VirtualDom};
-use std::any::Any;
-use std::rc::Weak;
+use futures_channel::mpsc::{unbounded, UnboundedSender};
+use futures_util::{
+    future::{join_all, LocalBoxFuture},
+    StreamExt,
+};
 use std::{
+    any::Any,
     cell::{Cell, RefCell},
     collections::{HashMap, HashSet},
-    rc::Rc,
+    future::Future,
+    rc::{Rc, Weak},
     str::FromStr,
     sync::Arc,
 };
 use url::Url;
 
+/// The maximum number of times guards can redirect a single navigation, to break redirect loops
+const MAX_REDIRECTS: usize = 8;
+
+type NavigationGuard = Rc<dyn Fn(&Navigation) -> GuardDecision>;
+
+/// The data a loader loaded, with the location it was loaded for
+type LoadedData = (Url, Rc<dyn Any>);
+
 /// A clonable handle to the router
 pub type RouterContext = Rc<RouterService>;
 
@@ -41,27 +56,68 @@ pub type RouterContext = Rc<RouterService>;
 ///
 /// # Platform Specific
 ///
-/// - On the web, this is a [`BrowserHistory`](https://docs.rs/gloo/0.3.0/gloo/history/struct.BrowserHistory.html).
-/// - On desktop, mobile, and SSR, this is just a Vec of Strings. Currently on
-///   desktop, there is no way to tap into forward/back for the app unless explicitly set.
+/// - On the web, this is the browser's [`History`](https://developer.mozilla.org/en-US/docs/Web/API/History).
+/// - On desktop, mobile, and SSR, this is a [`MemoryHistory`]. A [`MemoryHistory`] passed in through the
+///   [`RouterCfg`] can be used to move back and forward.
+/// - Any other [`RouterProvider`] can be passed in through the [`RouterCfg`] as well.
+///
+/// Every [`Router`](crate::Router) has its own service with its own history, so apps that run several VirtualDoms,
+/// like a desktop app with a window per document, route each of them independently.
 pub struct RouterService {
-    pub(crate) route_found: Cell<Option<ScopeId>>,
+    /// The route that matched at every level of nesting, keyed by the route it's nested in
+    pub(crate) route_found: RefCell<HashMap<Option<ScopeId>, ScopeId>>,
 
+    /// Every entry of the history the router knows about
     pub(crate) stack: RefCell<Vec<Arc<ParsedRoute>>>,
 
-    pub(crate) slots: Rc<RefCell<HashMap<ScopeId, String>>>,
+    /// The position of the current location in the `stack`
+    pub(crate) cursor: Cell<usize>,
+
+    pub(crate) slots: Rc<RefCell<HashMap<ScopeId, RouteContext>>>,
 
     pub(crate) ordering: Rc<RefCell<Vec<ScopeId>>>,
 
     pub(crate) onchange_listeners: Rc<RefCell<HashSet<ScopeId>>>,
 
-    pub(crate) history: Box<dyn RouterProvider>,
+    pub(crate) history: Rc<dyn RouterProvider>,
 
     pub(crate) regen_any_route: Arc<dyn Fn(ScopeId)>,
 
     pub(crate) router_id: ScopeId,
 
     pub(crate) cfg: RouterCfg,
+
+    /// The path the app is deployed under, like `/apps/billing`, or empty at the root of the domain
+    base_path: String,
+
+    /// The guards registered with [`use_navigation_guard`](crate::use_navigation_guard), with the scope that
+    /// registered them
+    pub(crate) guards: RefCell<Vec<(ScopeId, NavigationGuard)>>,
+
+    /// The data loaded by the loader of every route
+    pub(crate) loaded: RefCell<HashMap<ScopeId, LoadedData>>,
+
+    /// Counts the navigations that waited for loaders, so only the latest one goes through
+    navigation_id: Cell<usize>,
+
+    /// Whether a navigation is waiting for loaders
+    navigating: Cell<bool>,
+
+    /// Runs the loaders of navigations on the router's scope
+    tasks: UnboundedSender<LocalBoxFuture<'static, ()>>,
+
+    this: Weak<RouterService>,
+}
+
+/// How a navigation moves through the history
+#[derive(Debug, Clone, Copy)]
+enum Transition {
+    Push,
+    Replace,
+    /// The router moves through the history
+    Go(isize),
+    /// The history already moved on its own
+    Moved(isize),
 }
 
 /// A route is a combination of window title, saved state, and a URL.
@@ -79,36 +135,72 @@ pub struct ParsedRoute {
 
 impl RouterService {
     pub(crate) fn new(cx: &ScopeState, cfg: RouterCfg) -> RouterContext {
-        #[cfg(feature = "web")]
-        let history = Box::new(web::new());
+        let history: Rc<dyn RouterProvider> = match &cfg.history {
+            Some(history) => history.clone(),
 
-        #[cfg(not(feature = "web"))]
-        let history = Box::new(hash::new());
+            #[cfg(feature = "web")]
+            None => Rc::new(web::new()),
+
+            #[cfg(not(feature = "web"))]
+            None => Rc::new(crate::MemoryHistory::default()),
+        };
+
+        let base_path = cfg
+            .base_url
+            .as_deref()
+            .map(normalize_base)
+            .unwrap_or_default();
 
         let route = match &cfg.initial_url {
-            Some(url) => Arc::new(ParsedRoute {
-                url: Url::from_str(url).unwrap_or_else(|_|
-                    panic!(
-                        "RouterCfg expects a valid initial_url, but got '{}'. Example: '{{scheme}}://{{?authority}}/{{?path}}'",
-                        &url
-                    )
-                ),
-                title: None,
-                serialized_state: None,
-            }),
+            Some(url) => {
+                let location = history.init_location().url;
+                let parsed = match Url::from_str(url) {
+                    Ok(link) if is_deep_link(&link, &location) => {
+                        location.join(&join_base(&base_path, &deep_link_path(&link)))
+                    }
+                    Ok(url) => Ok(url),
+                    Err(_) => location.join(&join_base(&base_path, url)),
+                };
+
+                let route = ParsedRoute {
+                    url: parsed.unwrap_or_else(|_|
+                        panic!(
+                            "RouterCfg expects a valid initial_url, but got '{}'. Example: '{{scheme}}://{{?authority}}/{{?path}}' or '/{{?path}}'",
+                            &url
+                        )
+                    ),
+                    title: None,
+                    serialized_state: None,
+                };
+
+                // Keep the history in line with where the router starts
+                history.replace(&route);
+                Arc::new(route)
+            }
             None => Arc::new(history.init_location()),
         };
 
-        let svc = Rc::new(Self {
+        let (tasks, rx) = unbounded::<LocalBoxFuture<'static, ()>>();
+        cx.push_future(rx.for_each_concurrent(None, |task| task));
+
+        let svc = Rc::new_cyclic(|this| Self {
+            base_path,
             cfg,
             regen_any_route: cx.schedule_update_any(),
             router_id: cx.scope_id(),
-            route_found: Cell::new(None),
+            route_found: Default::default(),
             stack: RefCell::new(vec![route]),
+            cursor: Cell::new(0),
             ordering: Default::default(),
             slots: Default::default(),
             onchange_listeners: Default::default(),
             history,
+            guards: Default::default(),
+            loaded: Default::default(),
+            navigation_id: Cell::new(0),
+            navigating: Cell::new(false),
+            tasks,
+            this: this.clone(),
         });
 
         svc.history.attach_listeners(Rc::downgrade(&svc));
@@ -123,22 +215,47 @@ impl RouterService {
         self.push_route(route, None, None);
     }
 
+    /// Navigate to a link that was opened from outside the app, like a deep link passed in by the operating system.
+    ///
+    /// Links with a custom scheme, like `myapp://users/3?tab=posts`, are deep links and navigate to the path after the
+    /// scheme, `/users/3?tab=posts`. Any other link is navigated to as-is.
+    pub fn open_deep_link(&self, link: &str) {
+        let route = match Url::parse(link) {
+            Ok(url) if is_deep_link(&url, &self.current_location().url) => deep_link_path(&url),
+            _ => link.to_string(),
+        };
+
+        self.push_route(&route, None, None);
+    }
+
+    /// Build a navigation to a path, with a typed query and state.
+    ///
+    /// ```rust, ignore
+    /// router.navigate("/users").query(&Filters { page: 2 })?.push();
+    /// ```
+    pub fn navigate(&self, path: &str) -> NavigationBuilder<'_> {
+        NavigationBuilder::new(self, path)
+    }
+
     /// Push a new route to the history.
     ///
-    /// This will trigger a route change event.
+    /// This will trigger a route change event, once the guards allowed it and the loaders of the routes that match
+    /// the new route are done.
     ///
     /// This does not modify the current route
     pub fn push_route(&self, route: &str, title: Option<String>, serialized_state: Option<String>) {
-        let new_route = Arc::new(ParsedRoute {
-            url: self.current_location().url.join(route).ok().unwrap(),
+        let new_route = ParsedRoute {
+            url: self
+                .current_location()
+                .url
+                .join(&self.with_base(route))
+                .ok()
+                .unwrap(),
             title,
             serialized_state,
-        });
-
-        self.history.push(&new_route);
-        self.stack.borrow_mut().push(new_route);
+        };
 
-        self.regen_routes();
+        self.run_navigation(new_route, Transition::Push);
     }
 
     /// Instead of pushing a new route, replaces the current route.
@@ -148,24 +265,262 @@ impl RouterService {
         title: Option<String>,
         serialized_state: Option<String>,
     ) {
-        let new_route = Arc::new(ParsedRoute {
-            url: self.current_location().url.join(route).ok().unwrap(),
+        let new_route = ParsedRoute {
+            url: self
+                .current_location()
+                .url
+                .join(&self.with_base(route))
+                .ok()
+                .unwrap(),
             title,
             serialized_state,
-        });
-
-        self.history.replace(&new_route);
-        *self.stack.borrow_mut().last_mut().unwrap() = new_route;
+        };
 
-        self.regen_routes();
+        self.run_navigation(new_route, Transition::Replace);
     }
 
-    /// Pop the current route from the history.
+    /// Go back to the previous entry of the history.
+    ///
+    /// Unlike popping an entry off of a stack, the current entry is kept so [`go_forward`](Self::go_forward) can return
+    /// to it.
     pub fn pop_route(&self) {
-        let mut stack = self.stack.borrow_mut();
+        self.go_back();
+    }
 
-        if stack.len() > 1 {
-            stack.pop();
+    /// Go back to the previous entry of the history. Returns `false` if there is none.
+    pub fn go_back(&self) -> bool {
+        self.go(-1)
+    }
+
+    /// Go forward to the next entry of the history. Returns `false` if there is none.
+    pub fn go_forward(&self) -> bool {
+        self.go(1)
+    }
+
+    /// Move `delta` entries through the history, backwards if it's negative.
+    ///
+    /// Returns `false` and does nothing if there's no entry that far away, or if a guard blocked the navigation.
+    pub fn go(&self, delta: isize) -> bool {
+        let target = match self.cursor.get().checked_add_signed(delta) {
+            Some(target) if target < self.stack.borrow().len() => target,
+            _ => return false,
+        };
+
+        if delta == 0 {
+            return true;
+        }
+
+        let route = ParsedRoute::clone(&self.stack.borrow()[target]);
+        self.run_navigation(route, Transition::Go(delta))
+    }
+
+    /// Check whether there's an entry of the history before the current one.
+    ///
+    /// Components that use [`use_route`](crate::use_route) are re-rendered when this changes.
+    pub fn can_go_back(&self) -> bool {
+        self.cursor.get() > 0
+    }
+
+    /// Check whether there's an entry of the history after the current one.
+    ///
+    /// Components that use [`use_route`](crate::use_route) are re-rendered when this changes.
+    pub fn can_go_forward(&self) -> bool {
+        self.cursor.get() + 1 < self.stack.borrow().len()
+    }
+
+    /// Check whether the router is waiting for the loaders of the routes it's navigating to.
+    ///
+    /// Components that use [`use_route`](crate::use_route) are re-rendered when this changes, so they can show that
+    /// the app is loading while the current route stays rendered.
+    pub fn is_navigating(&self) -> bool {
+        self.navigating.get()
+    }
+
+    /// Follow the history after it moved `delta` entries on its own, like when the user pressed the browser's back
+    /// button, landing on `route`.
+    ///
+    /// If the router doesn't know about the entry (for instance because the page was reloaded since), `route`
+    /// replaces the current entry instead. If a guard blocks the navigation, the history is moved back.
+    pub(crate) fn history_moved(&self, delta: isize, route: ParsedRoute) {
+        self.run_navigation(route, Transition::Moved(delta));
+    }
+
+    /// Run the guards of a navigation, then the loaders of the routes it goes to, and then navigate.
+    ///
+    /// Returns `false` if a guard blocked the navigation.
+    fn run_navigation(&self, mut route: ParsedRoute, transition: Transition) -> bool {
+        let mut redirected = false;
+
+        for _ in 0..MAX_REDIRECTS {
+            let navigation = Navigation {
+                from: self.current_location().url.clone(),
+                to: route.url.clone(),
+            };
+
+            match self.check_guards(&navigation) {
+                GuardDecision::Allow => {
+                    self.load_and_commit(route, transition, redirected);
+                    return true;
+                }
+                GuardDecision::Block => {
+                    log::trace!("Navigation to {} was blocked", navigation.to);
+                    if let Transition::Moved(delta) = transition {
+                        self.undo_move(delta);
+                    }
+                    return false;
+                }
+                GuardDecision::Redirect(to) => {
+                    log::trace!("Navigation to {} was redirected to {}", navigation.to, to);
+                    route = ParsedRoute {
+                        url: navigation
+                            .to
+                            .join(&self.with_base(&to))
+                            .unwrap_or_else(|_| {
+                                panic!("A guard redirected to an invalid route: '{}'", to)
+                            }),
+                        title: None,
+                        serialized_state: None,
+                    };
+                    redirected = true;
+                }
+            }
+        }
+
+        log::error!(
+            "Navigation to {} was redirected more than {} times",
+            route.url,
+            MAX_REDIRECTS
+        );
+        if let Transition::Moved(delta) = transition {
+            self.undo_move(delta);
+        }
+        false
+    }
+
+    /// Put the history back where the router is, after it moved on its own to a location a guard blocked
+    fn undo_move(&self, delta: isize) {
+        match delta {
+            0 => self.history.replace(&self.current_location()),
+            delta => self.history.go(-delta),
+        }
+    }
+
+    /// Ask the guards what to do with a navigation: first the ones registered with
+    /// [`use_navigation_guard`](crate::use_navigation_guard), then the ones of the routes that match where it goes.
+    fn check_guards(&self, navigation: &Navigation) -> GuardDecision {
+        // Clone the guards out, since they're free to navigate or register guards themselves
+        let guards: Vec<_> = self
+            .guards
+            .borrow()
+            .iter()
+            .map(|(_, guard)| guard.clone())
+            .collect();
+
+        for guard in guards {
+            match guard(navigation) {
+                GuardDecision::Allow => {}
+                decision => return decision,
+            }
+        }
+
+        let route_guards: Vec<_> = self
+            .matching_routes(&navigation.to)
+            .into_iter()
+            .filter_map(|scope| self.slots.borrow().get(&scope)?.guard)
+            .collect();
+
+        for guard in route_guards {
+            match guard(navigation) {
+                GuardDecision::Allow => {}
+                decision => return decision,
+            }
+        }
+
+        GuardDecision::Allow
+    }
+
+    /// Run the loaders of the routes that match `route` that haven't loaded it yet, and navigate once they're done.
+    fn load_and_commit(&self, route: ParsedRoute, transition: Transition, redirected: bool) {
+        let id = self.navigation_id.get() + 1;
+        self.navigation_id.set(id);
+
+        let loads: Vec<_> = self
+            .matching_routes(&route.url)
+            .into_iter()
+            .filter(|scope| self.needs_loading(*scope, &route.url))
+            .filter_map(|scope| Some((scope, self.load(scope, &route.url)?)))
+            .collect();
+
+        if loads.is_empty() {
+            self.navigating.set(false);
+            self.commit(route, transition, redirected);
+            return;
+        }
+
+        self.navigating.set(true);
+        self.notify_listeners();
+
+        let this = self.this.clone();
+        let _ = self.tasks.unbounded_send(Box::pin(async move {
+            let (scopes, loads): (Vec<_>, Vec<_>) = loads.into_iter().unzip();
+            let loaded = join_all(loads).await;
+
+            let this = match this.upgrade() {
+                Some(this) => this,
+                None => return,
+            };
+
+            // A later navigation took over while we were loading
+            if this.navigation_id.get() != id {
+                return;
+            }
+
+            for (scope, data) in scopes.into_iter().zip(loaded) {
+                this.loaded
+                    .borrow_mut()
+                    .insert(scope, (route.url.clone(), data));
+            }
+
+            this.navigating.set(false);
+            this.commit(route, transition, redirected);
+        }));
+    }
+
+    /// Move the router to `route`, now that the navigation is allowed and its data is loaded
+    fn commit(&self, route: ParsedRoute, transition: Transition, redirected: bool) {
+        let route = Arc::new(route);
+
+        match transition {
+            Transition::Push => {
+                self.history.push(&route);
+
+                // Pushing discards the entries ahead of the current one
+                let mut stack = self.stack.borrow_mut();
+                stack.truncate(self.cursor.get() + 1);
+                stack.push(route);
+                self.cursor.set(stack.len() - 1);
+            }
+            Transition::Replace => {
+                self.history.replace(&route);
+                self.stack.borrow_mut()[self.cursor.get()] = route;
+            }
+            Transition::Go(delta) | Transition::Moved(delta) => {
+                let len = self.stack.borrow().len();
+                let target = match self.cursor.get().checked_add_signed(delta) {
+                    Some(target) if target < len => target,
+                    _ => self.cursor.get(),
+                };
+
+                if let Transition::Go(delta) = transition {
+                    self.history.go(delta);
+                }
+                if redirected {
+                    self.history.replace(&route);
+                }
+
+                self.cursor.set(target);
+                self.stack.borrow_mut()[target] = route;
+            }
         }
 
         self.regen_routes();
@@ -175,22 +530,72 @@ impl RouterService {
     ///
     /// You probably don't need this method
     pub fn regen_routes(&self) {
-        self.route_found.set(None);
+        self.route_found.borrow_mut().clear();
 
         (self.regen_any_route)(self.router_id);
 
-        for listener in self.onchange_listeners.borrow().iter() {
-            (self.regen_any_route)(*listener);
-        }
+        self.notify_listeners();
 
         for route in self.ordering.borrow().iter().rev() {
             (self.regen_any_route)(*route);
         }
     }
 
+    fn notify_listeners(&self) {
+        for listener in self.onchange_listeners.borrow().iter() {
+            (self.regen_any_route)(*listener);
+        }
+    }
+
     /// Get the current location of the Router
     pub fn current_location(&self) -> Arc<ParsedRoute> {
-        self.stack.borrow().last().unwrap().clone()
+        self.stack.borrow()[self.cursor.get()].clone()
+    }
+
+    /// Parse the current location of the Router into a typed route, returning `None` if no route matches.
+    ///
+    /// The [`base_path`](Self::base_path) is not part of the route.
+    pub fn current_route<R: Routable>(&self) -> Option<R> {
+        R::from_path(&self.current_path().join("/"))
+    }
+
+    /// The segments of the current path, without the base url
+    pub(crate) fn current_path(&self) -> Vec<String> {
+        self.route_path(&self.current_location().url)
+    }
+
+    /// The segments of the path of a location, without the base path.
+    ///
+    /// Locations outside of the base path are matched as they are.
+    fn route_path(&self, url: &Url) -> Vec<String> {
+        let segments: Vec<&str> = url
+            .path_segments()
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .collect();
+        let base = path_segments(&self.base_path);
+
+        let path = segments.strip_prefix(base.as_slice()).unwrap_or_else(|| {
+            log::trace!("{} is outside of the base path {}", url, self.base_path);
+            &segments
+        });
+        path.iter().map(|s| s.to_string()).collect()
+    }
+
+    /// Get the path the app is deployed under, set with the `base_url` of the [`RouterCfg`].
+    ///
+    /// The path starts with a slash and doesn't end with one, like `/apps/billing`, and is empty when the app is
+    /// deployed at the root of the domain.
+    pub fn base_path(&self) -> &str {
+        &self.base_path
+    }
+
+    /// Prefix a path of the app with the [`base_path`](Self::base_path), turning it into a path of the domain.
+    ///
+    /// Only paths that start with a slash are prefixed. Relative paths and full URLs are returned as they are.
+    pub fn with_base(&self, path: &str) -> String {
+        join_base(&self.base_path, path)
     }
 
     /// Get the current native location of the Router
@@ -212,152 +617,316 @@ impl RouterService {
         self.onchange_listeners.borrow_mut().remove(&id);
     }
 
-    pub(crate) fn register_total_route(&self, route: String, scope: ScopeId) {
-        let clean = clean_route(route);
-        self.slots.borrow_mut().insert(scope, clean);
+    /// Get the full path of every [`Route`](crate::Route) that is mounted, including the ones that aren't
+    /// currently rendering.
+    ///
+    /// Routes nested inside a route that isn't rendering its children are not included.
+    pub fn registered_routes(&self) -> Vec<String> {
+        let mut routes: Vec<String> = self
+            .slots
+            .borrow()
+            .values()
+            .map(|ctx| ctx.total_route.clone())
+            .collect();
+        routes.sort();
+        routes.dedup();
+        routes
+    }
+
+    pub(crate) fn register_total_route(&self, mut route: RouteContext) {
+        route.total_route = clean_route(route.total_route);
+        let (scope, parent) = (route.scope, route.parent);
         self.ordering.borrow_mut().push(scope);
+        self.slots.borrow_mut().insert(scope, route);
+
+        // A route mounted after the one that matched may match more specifically, in which case the route that
+        // matched before has to stop rendering
+        let found = self.route_found.borrow().get(&parent).copied();
+        if let Some(found) = found {
+            if self.route_match(scope).map(|m| m.rank) > self.route_match(found).map(|m| m.rank) {
+                self.route_found.borrow_mut().insert(parent, scope);
+                (self.regen_any_route)(found);
+            }
+        }
     }
 
-    pub(crate) fn should_render(&self, scope: ScopeId) -> bool {
-        if let Some(root_id) = self.route_found.get() {
-            return root_id == scope;
+    pub(crate) fn set_nested(&self, scope: ScopeId, nested: bool) {
+        if let Some(route) = self.slots.borrow_mut().get_mut(&scope) {
+            route.nested = nested;
         }
+    }
 
-        let roots = self.slots.borrow();
+    pub(crate) fn unregister_route(&self, scope: ScopeId) {
+        self.slots.borrow_mut().remove(&scope);
+        self.ordering.borrow_mut().retain(|id| *id != scope);
+        self.route_found
+            .borrow_mut()
+            .retain(|parent, found| *parent != Some(scope) && *found != scope);
+        self.loaded.borrow_mut().remove(&scope);
+    }
+
+    pub(crate) fn should_render(&self, scope: ScopeId) -> bool {
+        let parent = match self.slots.borrow().get(&scope) {
+            Some(route) => route.parent,
+            None => return false,
+        };
 
-        if let Some(route) = roots.get(&scope) {
-            let cur = &self.current_location().url;
-            log::trace!("Checking if {} matches {}", cur, route);
+        self.matched_route(parent) == Some(scope)
+    }
+
+    /// Find the route nested directly in `parent` that matches the current location most specifically.
+    ///
+    /// Routes that match equally well are picked in the order they were mounted.
+    pub(crate) fn matched_route(&self, parent: Option<ScopeId>) -> Option<ScopeId> {
+        if let Some(found) = self.route_found.borrow().get(&parent) {
+            return Some(*found);
+        }
 
-            if route_matches_path(cur, route, self.cfg.base_url.as_ref()) || route.is_empty() {
-                self.route_found.set(Some(scope));
-                true
-            } else {
-                false
+        // Routes nested in a route that didn't match can't match either
+        if let Some(parent) = parent {
+            if !self.should_render(parent) {
+                return None;
             }
-        } else {
-            false
         }
+
+        let path = self.current_path();
+        let path: Vec<&str> = path.iter().map(String::as_str).collect();
+        let found = self.best_route(parent, &path)?;
+        self.route_found.borrow_mut().insert(parent, found);
+        Some(found)
     }
-}
 
-/// Get the router service from an existing VirtualDom.
-///
-/// Takes an optional target_scope parameter to specify the scope to use if ScopeId is not the component
-/// that owns the router.
-///
-/// This might change in the future.
-pub fn get_router_from_vdom(dom: &VirtualDom, target_scope: ScopeId) -> Option<RouterContext> {
-    dom.get_scope(target_scope)
-        .and_then(|scope| scope.consume_context::<RouterContext>())
-}
+    /// Find the route nested directly in `parent` that matches `path` most specifically, ignoring whether `parent`
+    /// matches.
+    fn best_route(&self, parent: Option<ScopeId>, path: &[&str]) -> Option<ScopeId> {
+        let mut best: Option<(ScopeId, Rank)> = None;
+        for scope in self.ordering.borrow().iter().copied() {
+            if self.slots.borrow()[&scope].parent != parent {
+                continue;
+            }
 
-fn clean_route(route: String) -> String {
-    if route.as_str() == "/" {
-        return route;
-    }
-    route.trim_end_matches('/').to_string()
-}
+            if let Some(matched) = self.match_path(scope, path) {
+                if !matches!(&best, Some((_, rank)) if *rank >= matched.rank) {
+                    best = Some((scope, matched.rank));
+                }
+            }
+        }
 
-fn clean_path(path: &str) -> &str {
-    if path == "/" {
-        return path;
+        best.map(|(scope, _)| scope)
     }
-    let sub = path.trim_end_matches('/');
 
-    if sub.starts_with('/') {
-        &path[1..]
-    } else {
-        sub
+    /// Find the routes that would render at a location, from the outermost to the innermost.
+    ///
+    /// Only routes that are mounted are known, so routes nested in a route that isn't rendering (and isn't a layout
+    /// route) are missing.
+    pub(crate) fn matching_routes(&self, url: &Url) -> Vec<ScopeId> {
+        let path = self.route_path(url);
+        let path: Vec<&str> = path.iter().map(String::as_str).collect();
+
+        let mut routes = Vec::new();
+        let mut parent = None;
+        while let Some(found) = self.best_route(parent, &path) {
+            routes.push(found);
+            parent = Some(found);
+        }
+        routes
     }
-}
-
-fn route_matches_path(cur: &Url, attempt: &str, base_url: Option<&String>) -> bool {
-    let cur_piece_iter = cur.path_segments().unwrap();
 
-    let mut cur_pieces = match base_url {
-        // baseurl is naive right now and doesn't support multiple nesting levels
-        Some(_) => cur_piece_iter.skip(1).collect::<Vec<_>>(),
-        None => cur_piece_iter.collect::<Vec<_>>(),
-    };
-
-    if attempt == "/" && cur_pieces.len() == 1 && cur_pieces[0].is_empty() {
-        return true;
+    /// Match a route against the current location, ignoring the routes around it
+    pub(crate) fn route_match(&self, scope: ScopeId) -> Option<RouteMatch> {
+        let path = self.current_path();
+        let path: Vec<&str> = path.iter().map(String::as_str).collect();
+        self.match_path(scope, &path)
     }
 
-    // allow slashes at the end of the path
-    if cur_pieces.last() == Some(&"") {
-        cur_pieces.pop();
+    /// Match a route against the segments of a path
+    fn match_path(&self, scope: ScopeId, path: &[&str]) -> Option<RouteMatch> {
+        let slots = self.slots.borrow();
+        let route = slots.get(&scope)?;
+        log::trace!(
+            "Checking if /{} matches {}",
+            path.join("/"),
+            route.total_route
+        );
+
+        // Routes with nested routes match every path under them
+        match_route(&route.total_route, path, route.nested)
     }
 
-    let attempt_pieces = clean_path(attempt).split('/').collect::<Vec<_>>();
+    /// Check whether a route has a loader that hasn't loaded the data for `url` yet
+    pub(crate) fn needs_loading(&self, scope: ScopeId, url: &Url) -> bool {
+        let has_loader =
+            matches!(self.slots.borrow().get(&scope), Some(route) if route.loader.is_some());
+        let loaded = matches!(self.loaded.borrow().get(&scope), Some((loaded, _)) if loaded == url);
+        has_loader && !loaded
+    }
 
-    if attempt_pieces.len() != cur_pieces.len() {
-        return false;
+    /// Start the loader of a route for a location
+    fn load(&self, scope: ScopeId, url: &Url) -> Option<LocalBoxFuture<'static, Rc<dyn Any>>> {
+        let loader = self.slots.borrow().get(&scope)?.loader.clone()?;
+
+        let path = self.route_path(url);
+        let path: Vec<&str> = path.iter().map(String::as_str).collect();
+        let params = self
+            .match_path(scope, &path)
+            .map(|matched| matched.params)
+            .unwrap_or_default();
+
+        Some(loader.load(LoaderArgs {
+            url: url.clone(),
+            params,
+        }))
     }
 
-    for (i, r) in attempt_pieces.iter().enumerate() {
-        // If this is a parameter then it matches as long as there's
-        // _any_thing in that spot in the path.
-        if r.starts_with(':') {
-            continue;
+    /// Load the data of a route for the current location, and render the route again once it's there.
+    ///
+    /// This is how routes that render before their data was loaded (like when the app starts) suspend.
+    pub(crate) fn load_current(&self, scope: ScopeId) -> impl Future<Output = ()> {
+        let url = self.current_location().url.clone();
+        let load = self.load(scope, &url);
+        let this = self.this.clone();
+
+        async move {
+            let data = match load {
+                Some(load) => load.await,
+                None => return,
+            };
+
+            if let Some(this) = this.upgrade() {
+                this.loaded.borrow_mut().insert(scope, (url, data));
+                (this.regen_any_route)(scope);
+            }
         }
+    }
 
-        if cur_pieces[i] != *r {
-            return false;
+    /// Get the data the loader of a route loaded for the current location
+    pub(crate) fn loaded_data(&self, scope: ScopeId) -> Option<Rc<dyn Any>> {
+        let location = self.current_location();
+        match self.loaded.borrow().get(&scope) {
+            Some((url, data)) if *url == location.url => Some(data.clone()),
+            _ => None,
         }
     }
 
-    true
-}
+    /// Resolve a path relative to the route it's used in, into a path of the app without the base path.
+    ///
+    /// Paths that start with a slash, and paths used outside of a route, are returned as they are.
+    pub(crate) fn resolve_route(&self, route: Option<&RouteContext>, to: &str) -> String {
+        let route = match route {
+            Some(route) if !to.starts_with('/') => route,
+            _ => return to.to_string(),
+        };
 
-pub(crate) trait RouterProvider {
-    fn push(&self, route: &ParsedRoute);
-    fn replace(&self, route: &ParsedRoute);
-    fn native_location(&self) -> Box<dyn Any>;
-    fn init_location(&self) -> ParsedRoute;
-    fn attach_listeners(&self, svc: Weak<RouterService>);
+        // The part of the current path the route matched
+        let depth = self
+            .route_match(route.scope)
+            .map_or(0, |matched| matched.len);
+        let path = self.current_path();
+        let mut segments: Vec<&str> = path.iter().map(String::as_str).take(depth).collect();
+
+        for segment in to.split('/') {
+            match segment {
+                "" | "." => {}
+                ".." => _ = segments.pop(),
+                segment => segments.push(segment),
+            }
+        }
+
+        format!("/{}", segments.join("/"))
+    }
 }
 
-#[cfg(not(feature = "web"))]
-mod hash {
-    use super::*;
+/// Join a route onto the route it's nested in
+pub(crate) fn join_route(parent: &str, route: &str) -> String {
+    let parent = parent.trim_end_matches('/');
+    let route = route.trim_start_matches('/');
 
-    pub fn new() -> HashRouter {
-        HashRouter {}
+    match (parent.is_empty(), route.is_empty()) {
+        (true, true) => "/".to_string(),
+        (false, true) => parent.to_string(),
+        _ => format!("{}/{}", parent, route),
     }
+}
 
-    /// a simple cross-platform hash-based router
-    pub struct HashRouter {}
+/// Get the router service from an existing VirtualDom.
+///
+/// Takes an optional target_scope parameter to specify the scope to use if ScopeId is not the component
+/// that owns the router.
+///
+/// This might change in the future.
+pub fn get_router_from_vdom(dom: &VirtualDom, target_scope: ScopeId) -> Option<RouterContext> {
+    dom.get_scope(target_scope)
+        .and_then(|scope| scope.consume_context::<RouterContext>())
+}
 
-    impl RouterProvider for HashRouter {
-        fn push(&self, _route: &ParsedRoute) {}
+/// Turn a base url (a path, or a full URL) into a path like `/apps/billing`
+fn normalize_base(base_url: &str) -> String {
+    let path = match Url::parse(base_url) {
+        Ok(url) => url.path().to_string(),
+        Err(_) => base_url.to_string(),
+    };
 
-        fn native_location(&self) -> Box<dyn Any> {
-            Box::new(())
-        }
+    path_segments(&path)
+        .into_iter()
+        .map(|segment| format!("/{}", segment))
+        .collect()
+}
 
-        fn init_location(&self) -> ParsedRoute {
-            ParsedRoute {
-                url: Url::parse("app:///").unwrap(),
-                title: None,
-                serialized_state: None,
-            }
-        }
+fn join_base(base_path: &str, path: &str) -> String {
+    match path.starts_with('/') && !path.starts_with("//") {
+        true if !base_path.is_empty() => match path {
+            "/" => base_path.to_string(),
+            path => format!("{}{}", base_path, path),
+        },
+        _ => path.to_string(),
+    }
+}
 
-        fn replace(&self, _route: &ParsedRoute) {}
+/// Whether a link is a deep link into the app rather than a location of it: one with a custom scheme, which isn't the
+/// scheme of the router's own locations
+fn is_deep_link(link: &Url, location: &Url) -> bool {
+    link.scheme() != location.scheme() && !matches!(link.scheme(), "http" | "https")
+}
 
-        fn attach_listeners(&self, _svc: Weak<RouterService>) {}
+/// The path in the app a deep link points at, where the host of the link is the first segment of the path
+fn deep_link_path(link: &Url) -> String {
+    let mut path: String = link
+        .host_str()
+        .into_iter()
+        .chain(link.path().split('/'))
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| format!("/{}", segment))
+        .collect();
+
+    if path.is_empty() {
+        path.push('/');
+    }
+    if let Some(query) = link.query() {
+        path.push('?');
+        path.push_str(query);
+    }
+    if let Some(fragment) = link.fragment() {
+        path.push('#');
+        path.push_str(fragment);
     }
+
+    path
+}
+
+fn clean_route(route: String) -> String {
+    if route.as_str() == "/" {
+        return route;
+    }
+    route.trim_end_matches('/').to_string()
 }
 
 #[cfg(feature = "web")]
 mod web {
-    use super::RouterProvider;
-    use crate::ParsedRoute;
+    use crate::{ParsedRoute, RouterProvider};
 
     use gloo_events::EventListener;
-    use std::{any::Any, cell::Cell};
+    use std::{any::Any, cell::Cell, rc::Rc};
+    use wasm_bindgen::JsValue;
     use web_sys::History;
 
     pub struct WebRouter {
@@ -366,6 +935,9 @@ mod web {
 
         window: web_sys::Window,
         history: History,
+
+        /// The position of the current entry in the browser's history, as far as we know
+        index: Rc<Cell<usize>>,
     }
 
     impl RouterProvider for WebRouter {
@@ -376,8 +948,10 @@ mod web {
                 serialized_state,
             } = route;
 
+            self.index.set(self.index.get() + 1);
+
             let _ = self.history.push_state_with_url(
-                &wasm_bindgen::JsValue::from_str(serialized_state.as_deref().unwrap_or("")),
+                &entry_state(self.index.get(), serialized_state.as_deref()),
                 title.as_deref().unwrap_or(""),
                 Some(url.as_str()),
             );
@@ -391,47 +965,119 @@ mod web {
             } = route;
 
             let _ = self.history.replace_state_with_url(
-                &wasm_bindgen::JsValue::from_str(serialized_state.as_deref().unwrap_or("")),
+                &entry_state(self.index.get(), serialized_state.as_deref()),
                 title.as_deref().unwrap_or(""),
                 Some(url.as_str()),
             );
         }
 
+        fn go(&self, delta: isize) {
+            // The popstate this causes finds the index already moved, so it doesn't navigate the router again
+            self.index
+                .set(self.index.get().saturating_add_signed(delta));
+            let _ = self.history.go_with_delta(delta as i32);
+        }
+
         fn native_location(&self) -> Box<dyn Any> {
             Box::new(self.window.location())
         }
 
         fn init_location(&self) -> ParsedRoute {
-            ParsedRoute {
-                url: url::Url::parse(&web_sys::window().unwrap().location().href().unwrap())
-                    .unwrap(),
-                title: web_sys::window()
-                    .unwrap()
-                    .document()
-                    .unwrap()
-                    .title()
-                    .into(),
-                serialized_state: None,
-            }
+            current_route(&JsValue::NULL)
         }
 
         fn attach_listeners(&self, svc: std::rc::Weak<crate::RouterService>) {
+            let index = self.index.clone();
+
             self._listener.set(Some(EventListener::new(
                 &web_sys::window().unwrap(),
                 "popstate",
                 move |_| {
-                    if let Some(svc) = svc.upgrade() {
-                        svc.pop_route();
+                    let svc = match svc.upgrade() {
+                        Some(svc) => svc,
+                        None => return,
+                    };
+
+                    let state = web_sys::window()
+                        .unwrap()
+                        .history()
+                        .and_then(|history| history.state())
+                        .unwrap_or(JsValue::NULL);
+
+                    match entry_index(&state) {
+                        // The router already moved here
+                        Some(new) if new == index.get() => {}
+                        Some(new) => {
+                            let delta = new as isize - index.get() as isize;
+                            index.set(new);
+                            svc.history_moved(delta, current_route(&state));
+                        }
+                        // An entry we didn't write, like a changed fragment
+                        None => svc.history_moved(0, current_route(&state)),
                     }
                 },
             )));
         }
     }
 
+    /// The state stored with every entry we write, so a popstate can tell how far the history moved
+    fn entry_state(index: usize, serialized_state: Option<&str>) -> JsValue {
+        let state = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&state, &"index".into(), &(index as f64).into());
+        let _ = js_sys::Reflect::set(
+            &state,
+            &"state".into(),
+            &serialized_state.unwrap_or("").into(),
+        );
+        state.into()
+    }
+
+    fn entry_index(state: &JsValue) -> Option<usize> {
+        if !state.is_object() {
+            return None;
+        }
+
+        let index = js_sys::Reflect::get(state, &"index".into()).ok()?;
+        index.as_f64().map(|index| index as usize)
+    }
+
+    /// The route the browser is at, with the state of its history entry
+    fn current_route(state: &JsValue) -> ParsedRoute {
+        let window = web_sys::window().unwrap();
+
+        let serialized_state = match state.is_object() {
+            true => js_sys::Reflect::get(state, &"state".into())
+                .ok()
+                .and_then(|state| state.as_string())
+                .filter(|state| !state.is_empty()),
+            false => None,
+        };
+
+        ParsedRoute {
+            url: url::Url::parse(&window.location().href().unwrap()).unwrap(),
+            title: window.document().unwrap().title().into(),
+            serialized_state,
+        }
+    }
+
     pub(crate) fn new() -> WebRouter {
+        let window = web_sys::window().unwrap();
+        let history = window.history().unwrap();
+
+        // Pick up where we left off if the page was reloaded, and mark the first entry otherwise
+        let state = history.state().unwrap_or(JsValue::NULL);
+        let index = match entry_index(&state) {
+            Some(index) => index,
+            None => {
+                let _ = history.replace_state(&entry_state(0, None), "");
+                0
+            }
+        };
+
         WebRouter {
-            history: web_sys::window().unwrap().history().unwrap(),
-            window: web_sys::window().unwrap(),
+            history,
+            window,
+            index: Rc::new(Cell::new(index)),
             _listener: Cell::new(None),
         }
     }
@@ -0,0 +1,52 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_router::*;
+use std::rc::Rc;
+
+#[test]
+fn serves_the_app_under_a_base_path() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            Router {
+                Route { to: "/", Link { to: "/users/7", "user 7" } }
+                Route { to: "/users/:id", User {} }
+            }
+        })
+    }
+
+    fn User(cx: Scope) -> Element {
+        let id = use_route(cx).segment("id").unwrap_or_default().to_string();
+        cx.render(rsx! {
+            p { "user {id}" }
+            Link { to: "..", "users" }
+        })
+    }
+
+    let history = Rc::new(MemoryHistory::default().with_initial_path("/apps/billing/v2/"));
+    let mut dom = VirtualDom::new(app).with_root_context(RouterCfg {
+        base_url: Some("https://example.com/apps/billing/v2/".to_string()),
+        history: Some(history.clone()),
+        ..Default::default()
+    });
+    _ = dom.rebuild();
+    let router = get_router_from_vdom(&dom, ScopeId(1)).unwrap();
+    assert_eq!(router.base_path(), "/apps/billing/v2");
+
+    // The base path is stripped before matching and added to links
+    assert!(dioxus_ssr::render(&dom).starts_with("<a href=\"/apps/billing/v2/users/7\""));
+
+    router.navigate_to("/users/7");
+    dom.process_events();
+    _ = dom.render_immediate();
+    let html = dioxus_ssr::render(&dom);
+    assert!(html.starts_with("<p>user 7</p><a href=\"/apps/billing/v2/users\""));
+    assert_eq!(history.location().url.path(), "/apps/billing/v2/users/7");
+
+    // Relative paths and full URLs are left alone
+    assert_eq!(router.with_base("edit"), "edit");
+    assert_eq!(
+        router.with_base("https://dioxuslabs.com/"),
+        "https://dioxuslabs.com/"
+    );
+}
@@ -0,0 +1,77 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_router::*;
+use std::{cell::Cell, rc::Rc};
+
+#[test]
+fn guards_block_and_redirect_navigation() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            Router {
+                Route { to: "/", "home" }
+                Route { to: "/login", "login" }
+                Route { to: "/admin", guard: logged_in, "admin" }
+                Route { to: "/edit", Editor {} }
+            }
+        })
+    }
+
+    fn logged_in(nav: &Navigation) -> GuardDecision {
+        match nav.to.query() {
+            Some("token") => GuardDecision::Allow,
+            _ => GuardDecision::Redirect("/login".to_string()),
+        }
+    }
+
+    fn Editor(cx: Scope) -> Element {
+        let unsaved = cx.consume_context::<Rc<Cell<bool>>>().unwrap();
+        use_navigation_guard(cx, move |_| match unsaved.get() {
+            true => GuardDecision::Block,
+            false => GuardDecision::Allow,
+        });
+
+        cx.render(rsx! { "editor" })
+    }
+
+    let history = Rc::new(MemoryHistory::default());
+    let unsaved = Rc::new(Cell::new(true));
+    let mut dom = VirtualDom::new(app)
+        .with_root_context(RouterCfg {
+            history: Some(history.clone()),
+            ..Default::default()
+        })
+        .with_root_context(unsaved.clone());
+    _ = dom.rebuild();
+    let router = get_router_from_vdom(&dom, ScopeId(1)).unwrap();
+
+    let render = |dom: &mut VirtualDom| {
+        dom.process_events();
+        _ = dom.render_immediate();
+        dioxus_ssr::render(dom)
+    };
+
+    router.navigate_to("/admin");
+    assert_eq!(render(&mut dom), "login");
+    assert_eq!(history.location().url.path(), "/login");
+
+    router.navigate_to("/admin?token");
+    assert_eq!(render(&mut dom), "admin");
+
+    // The editor blocks leaving it while there are unsaved changes
+    router.navigate_to("/edit");
+    assert_eq!(render(&mut dom), "editor");
+    router.navigate_to("/");
+    assert!(!history.back());
+    assert_eq!(render(&mut dom), "editor");
+    assert_eq!(history.location().url.path(), "/edit");
+
+    unsaved.set(false);
+    assert!(history.back());
+    assert_eq!(render(&mut dom), "admin");
+
+    // The guard went away with the editor
+    unsaved.set(true);
+    router.navigate_to("/");
+    assert_eq!(render(&mut dom), "home");
+}
@@ -0,0 +1,167 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_router::*;
+use std::rc::Rc;
+
+fn route(path: &str) -> ParsedRoute {
+    ParsedRoute {
+        url: url::Url::parse("app:///").unwrap().join(path).unwrap(),
+        title: None,
+        serialized_state: None,
+    }
+}
+
+fn path(history: &MemoryHistory) -> String {
+    history.location().url.path().to_string()
+}
+
+#[test]
+fn moves_back_and_forward() {
+    let history = MemoryHistory::default().with_initial_path("/users");
+    assert_eq!(path(&history), "/users");
+    assert!(!history.can_go_back());
+
+    history.push(&route("/users/1"));
+    history.push(&route("/users/2"));
+    assert!(history.can_go_back());
+    assert!(!history.can_go_forward());
+
+    assert!(history.go(-2));
+    assert_eq!(path(&history), "/users");
+    assert!(history.can_go_forward());
+
+    assert!(history.forward());
+    assert_eq!(path(&history), "/users/1");
+
+    // Out of range moves are ignored
+    assert!(!history.go(5));
+    assert!(!history.go(-5));
+    assert_eq!(path(&history), "/users/1");
+}
+
+#[test]
+fn push_discards_forward_entries() {
+    let history = MemoryHistory::new();
+    history.push(&route("/a"));
+    history.push(&route("/b"));
+    history.back();
+
+    history.push(&route("/c"));
+    assert!(!history.can_go_forward());
+
+    history.back();
+    assert_eq!(path(&history), "/a");
+}
+
+#[test]
+fn replace_keeps_the_position() {
+    let history = MemoryHistory::new();
+    history.push(&route("/a"));
+    history.replace(&route("/b"));
+
+    assert_eq!(path(&history), "/b");
+    history.back();
+    assert_eq!(path(&history), "/");
+}
+
+#[test]
+fn navigates_with_a_memory_history() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            Router {
+                Route { to: "/admin", p { "dashboard" } }
+                Route { to: "/admin/users/:id", component: UserDetail }
+            }
+        })
+    }
+
+    fn UserDetail(cx: Scope) -> Element {
+        let id = use_route(cx).segment("id").unwrap_or_default().to_string();
+        cx.render(rsx! { p { "user {id}" } })
+    }
+
+    let history = Rc::new(MemoryHistory::default().with_initial_path("/admin"));
+    let mut dom = VirtualDom::new(app).with_root_context(RouterCfg {
+        history: Some(history.clone()),
+        ..Default::default()
+    });
+    _ = dom.rebuild();
+    assert_eq!(dioxus_ssr::render(&dom), "<p>dashboard</p>");
+
+    let router = get_router_from_vdom(&dom, ScopeId(1)).unwrap();
+    router.push_route("/admin/users/3", None, None);
+    dom.process_events();
+    _ = dom.render_immediate();
+    assert_eq!(dioxus_ssr::render(&dom), "<p>user 3</p>");
+
+    // Moving through the history navigates the router
+    assert!(history.back());
+    dom.process_events();
+    _ = dom.render_immediate();
+    assert_eq!(dioxus_ssr::render(&dom), "<p>dashboard</p>");
+
+    assert!(history.forward());
+    dom.process_events();
+    _ = dom.render_immediate();
+    assert_eq!(dioxus_ssr::render(&dom), "<p>user 3</p>");
+    assert!(!history.can_go_forward());
+}
+
+#[test]
+fn traverses_the_history() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            Router {
+                Nav {}
+                Route { to: "/", "home" }
+                Route { to: "/a", "a" }
+                Route { to: "/b", "b" }
+            }
+        })
+    }
+
+    fn Nav(cx: Scope) -> Element {
+        use_route(cx);
+        let router = use_router(cx);
+        let back = router.can_go_back();
+        let forward = router.can_go_forward();
+
+        cx.render(rsx! { "{back} {forward} " })
+    }
+
+    let mut dom = VirtualDom::new(app).with_root_context(RouterCfg {
+        initial_url: Some("app:///".to_string()),
+        ..Default::default()
+    });
+    _ = dom.rebuild();
+    let router = get_router_from_vdom(&dom, ScopeId(1)).unwrap();
+
+    let render = |dom: &mut VirtualDom| {
+        dom.process_events();
+        _ = dom.render_immediate();
+        dioxus_ssr::render(dom)
+    };
+
+    assert_eq!(render(&mut dom), "false false home");
+
+    router.navigate_to("/a");
+    router.navigate_to("/b");
+    assert_eq!(render(&mut dom), "true false b");
+
+    assert!(router.go(-2));
+    assert_eq!(render(&mut dom), "false true home");
+
+    assert!(router.go_forward());
+    assert_eq!(render(&mut dom), "true true a");
+
+    // Out of range moves are ignored
+    assert!(!router.go(2));
+    assert_eq!(render(&mut dom), "true true a");
+
+    // Navigating discards the entries ahead
+    router.navigate_to("/");
+    assert_eq!(render(&mut dom), "true false home");
+    assert!(router.go_back());
+    assert_eq!(render(&mut dom), "true true a");
+}
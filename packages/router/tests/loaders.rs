@@ -0,0 +1,66 @@
+#![cfg(not(target_arch = "wasm32"))]
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_router::*;
+use std::time::Duration;
+
+#[tokio::test]
+async fn loads_route_data_before_rendering() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            Router {
+                Route { to: "/", "home" }
+                Route { to: "/users/:id", loader: RouteLoader::new(load_user), User {} }
+            }
+        })
+    }
+
+    async fn load_user(args: LoaderArgs) -> String {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        format!("user {}", args.segment("id").unwrap())
+    }
+
+    fn User(cx: Scope) -> Element {
+        let user = use_loader_data::<String>(cx).unwrap();
+        cx.render(rsx! { p { "{user}" } })
+    }
+
+    async fn settle(dom: &mut VirtualDom, router: &RouterContext) -> String {
+        dom.process_events();
+        _ = dom.render_immediate();
+        while router.is_navigating() || dom.has_suspended_work() {
+            dom.wait_for_work().await;
+            _ = dom.render_immediate();
+        }
+        dioxus_ssr::render(dom)
+    }
+
+    let mut dom = VirtualDom::new(app).with_root_context(RouterCfg {
+        initial_url: Some("app:///".to_string()),
+        ..Default::default()
+    });
+    _ = dom.rebuild();
+    let router = get_router_from_vdom(&dom, ScopeId(1)).unwrap();
+
+    // The current route stays rendered while the next one loads
+    router.navigate_to("/users/1");
+    dom.process_events();
+    _ = dom.render_immediate();
+    assert!(router.is_navigating());
+    assert_eq!(dioxus_ssr::render(&dom), "home");
+    assert_eq!(router.current_location().url.path(), "/");
+
+    assert_eq!(settle(&mut dom, &router).await, "<p>user 1</p>");
+    assert_eq!(router.current_location().url.path(), "/users/1");
+
+    // A route that renders before its data is loaded suspends until it is
+    let mut dom = VirtualDom::new(app).with_root_context(RouterCfg {
+        initial_url: Some("app:///users/2".to_string()),
+        ..Default::default()
+    });
+    _ = dom.rebuild();
+    assert!(dom.has_suspended_work());
+    let router = get_router_from_vdom(&dom, ScopeId(1)).unwrap();
+    assert_eq!(settle(&mut dom, &router).await, "<p>user 2</p>");
+}
@@ -0,0 +1,159 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_router::*;
+use std::{cell::Cell, rc::Rc};
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx! {
+        Router {
+            Route { to: "/", h1 { "home" } }
+            Route { to: "/admin", component: AdminLayout,
+                Route { to: "/", component: Dashboard }
+                Route { to: "users", component: UsersLayout,
+                    Route { to: ":id", component: UserDetail }
+                }
+            }
+        }
+    })
+}
+
+fn AdminLayout(cx: Scope) -> Element {
+    // Count how often the layout is mounted
+    cx.use_hook(|| {
+        let mounts = cx.consume_context::<Rc<Cell<usize>>>().unwrap();
+        mounts.set(mounts.get() + 1);
+    });
+
+    cx.render(rsx! {
+        nav { Link { to: "users", "users" } }
+        Outlet {}
+    })
+}
+
+fn Dashboard(cx: Scope) -> Element {
+    cx.render(rsx! { p { "dashboard" } })
+}
+
+fn UsersLayout(cx: Scope) -> Element {
+    cx.render(rsx! {
+        h2 { "users" }
+        Outlet {}
+    })
+}
+
+fn UserDetail(cx: Scope) -> Element {
+    let id = use_route(cx).segment("id").unwrap_or_default().to_string();
+
+    cx.render(rsx! {
+        p { "user {id}" }
+        Link { to: "..", "back" }
+    })
+}
+
+fn dom_at(url: &str) -> (VirtualDom, Rc<Cell<usize>>) {
+    let mounts = Rc::new(Cell::new(0usize));
+    let mut dom = VirtualDom::new(app)
+        .with_root_context(RouterCfg {
+            initial_url: Some(format!("app://{}", url)),
+            ..Default::default()
+        })
+        .with_root_context(mounts.clone());
+    _ = dom.rebuild();
+    (dom, mounts)
+}
+
+#[test]
+fn renders_nested_layouts() {
+    let (dom, _) = dom_at("/admin/users/7");
+    let html = dioxus_ssr::render(&dom);
+
+    assert!(html.starts_with("<nav><a href=\"/admin/users\""));
+    assert!(html.contains("users</a></nav><h2>users</h2><p>user 7</p>"));
+}
+
+#[test]
+fn resolves_relative_links() {
+    let (dom, _) = dom_at("/admin/users/7");
+    let html = dioxus_ssr::render(&dom);
+
+    // `..` from inside `/admin/users/:id` goes up to `/admin/users`
+    let back = html.split("<p>user 7</p>").nth(1).unwrap();
+    assert!(back.starts_with("<a href=\"/admin/users\""));
+}
+
+#[test]
+fn renders_index_routes() {
+    let (dom, _) = dom_at("/admin");
+    let html = dioxus_ssr::render(&dom);
+
+    assert!(html.contains("<p>dashboard</p>"));
+    assert!(!html.contains("home"));
+    assert!(!html.contains("<h2>users</h2>"));
+}
+
+#[test]
+fn layouts_match_paths_under_them() {
+    let (dom, mounts) = dom_at("/");
+
+    assert_eq!(dioxus_ssr::render(&dom), "<h1>home</h1>");
+    assert_eq!(mounts.get(), 0);
+
+    // The layout matches everything under it, even if none of its nested routes do
+    let (dom, _) = dom_at("/admin/settings");
+    let html = dioxus_ssr::render(&dom);
+    assert!(html.starts_with("<nav>"));
+    assert!(html.ends_with("</nav>"));
+}
+
+#[test]
+fn layouts_persist_across_navigation() {
+    let (mut dom, mounts) = dom_at("/admin");
+    assert_eq!(mounts.get(), 1);
+
+    let router = get_router_from_vdom(&dom, ScopeId(1)).unwrap();
+    router.push_route("/admin/users/3", None, None);
+    dom.process_events();
+    _ = dom.render_immediate();
+
+    assert!(dioxus_ssr::render(&dom).contains("<p>user 3</p>"));
+    assert_eq!(mounts.get(), 1);
+}
+
+#[test]
+fn nests_routes_in_place() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            Router {
+                Route { to: "/", "home" }
+                Route { to: "/blog",
+                    p { "blog" }
+                    Route { to: ":post", Post {} }
+                }
+                Route { to: "", "not found" }
+            }
+        })
+    }
+
+    fn Post(cx: Scope) -> Element {
+        let post = use_route(cx)
+            .segment("post")
+            .unwrap_or_default()
+            .to_string();
+        cx.render(rsx! { "post {post}" })
+    }
+
+    let render = |url: &str| {
+        let mut dom = VirtualDom::new(app).with_root_context(RouterCfg {
+            initial_url: Some(format!("app://{}", url)),
+            ..Default::default()
+        });
+        _ = dom.rebuild();
+        dioxus_ssr::render(&dom)
+    };
+
+    assert_eq!(render("/blog/hello"), "<p>blog</p>post hello");
+    assert_eq!(render("/blog"), "<p>blog</p>");
+    assert_eq!(render("/"), "home");
+    assert_eq!(render("/about"), "not found");
+}
@@ -0,0 +1,62 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_router::*;
+
+#[test]
+fn navigates_with_a_typed_query_and_state() {
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Filters {
+        search: String,
+        page: usize,
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Scroll {
+        top: u32,
+    }
+
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            Router {
+                Route { to: "/users", Users {} }
+            }
+        })
+    }
+
+    fn Users(cx: Scope) -> Element {
+        let route = use_route(cx);
+        let filters = route.query::<Filters>();
+        let scroll = route.state::<Scroll>();
+
+        cx.render(rsx! { "{filters:?} {scroll:?}" })
+    }
+
+    let mut dom = VirtualDom::new(app).with_root_context(RouterCfg {
+        initial_url: Some("app:///users?search=old".to_string()),
+        ..Default::default()
+    });
+    _ = dom.rebuild();
+    let router = get_router_from_vdom(&dom, ScopeId(1)).unwrap();
+
+    router
+        .navigate("/users?search=old")
+        .query(&Filters {
+            search: "ada lovelace".to_string(),
+            page: 2,
+        })
+        .unwrap()
+        .state(&Scroll { top: 120 })
+        .unwrap()
+        .push();
+    dom.process_events();
+    _ = dom.render_immediate();
+
+    let location = router.current_location();
+    assert_eq!(location.url.query(), Some("search=ada+lovelace&page=2"));
+    assert_eq!(location.serialized_state.as_deref(), Some("{\"top\":120}"));
+    assert_eq!(
+        dioxus_ssr::render(&dom),
+        "Some(Filters { search: &quot;ada lovelace&quot;, page: 2 }) Some(Scroll { top: 120 })"
+    );
+}
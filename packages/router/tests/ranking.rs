@@ -0,0 +1,68 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_router::*;
+
+#[test]
+fn ranks_routes_by_specificity() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            Router {
+                Route { to: "/users/:id", User {} }
+                Route { to: "/users/new", "new user" }
+                Route { to: "/posts/:id<int>/:tab?", Post {} }
+                Route { to: "/posts/:slug", "post by slug" }
+                Route { to: "/files/*path", File {} }
+                Route { to: "*", "not found" }
+            }
+        })
+    }
+
+    fn User(cx: Scope) -> Element {
+        let id = use_route(cx).segment("id").unwrap_or_default().to_string();
+        cx.render(rsx! { "user {id}" })
+    }
+
+    fn Post(cx: Scope) -> Element {
+        let route = use_route(cx);
+        let id = route.segment("id").unwrap_or_default().to_string();
+        let tab = route.segment("tab").unwrap_or("overview").to_string();
+        cx.render(rsx! { "post {id}: {tab}" })
+    }
+
+    fn File(cx: Scope) -> Element {
+        let path = use_route(cx)
+            .segment("path")
+            .unwrap_or_default()
+            .to_string();
+        cx.render(rsx! { "file {path}" })
+    }
+
+    let render = |url: &str| {
+        let mut dom = VirtualDom::new(app).with_root_context(RouterCfg {
+            initial_url: Some(format!("app://{}", url)),
+            ..Default::default()
+        });
+        _ = dom.rebuild();
+
+        // A more specific route declared later takes over on the next update
+        dom.process_events();
+        _ = dom.render_immediate();
+        dioxus_ssr::render(&dom)
+    };
+
+    assert_eq!(render("/users/new"), "new user");
+    assert_eq!(render("/users/7"), "user 7");
+
+    // Constraints and optional segments
+    assert_eq!(render("/posts/3"), "post 3: overview");
+    assert_eq!(render("/posts/3/comments"), "post 3: comments");
+    assert_eq!(render("/posts/hello-world"), "post by slug");
+
+    // Catch-all segments
+    assert_eq!(render("/files/docs/readme.md"), "file docs/readme.md");
+    assert_eq!(render("/files"), "file ");
+
+    assert_eq!(render("/"), "not found");
+    assert_eq!(render("/users/7/edit"), "not found");
+}
@@ -0,0 +1,209 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_router::{get_router_from_vdom, Link, Routable, Router, RouterCfg, Switch};
+
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum Route {
+    #[route("/")]
+    Home,
+
+    #[route("/blog/:id")]
+    BlogPost { id: usize },
+
+    #[route("/users/:name/posts/:post", UserPostPage)]
+    UserPost { name: String, post: u32 },
+
+    #[route("/*path")]
+    NotFound { path: String },
+
+    // Declared after the route it overlaps with, but more specific
+    #[route("/blog/new")]
+    NewBlogPost,
+}
+
+fn Home(cx: Scope) -> Element {
+    cx.render(rsx!("home"))
+}
+
+#[inline_props]
+fn BlogPost(cx: Scope, id: usize) -> Element {
+    cx.render(rsx!("blog post {id}"))
+}
+
+#[inline_props]
+fn UserPostPage(cx: Scope, name: String, post: u32) -> Element {
+    cx.render(rsx!("{name}: {post}"))
+}
+
+#[inline_props]
+fn NotFound(cx: Scope, path: String) -> Element {
+    cx.render(rsx!("{path} was not found"))
+}
+
+fn NewBlogPost(cx: Scope) -> Element {
+    cx.render(rsx!("new blog post"))
+}
+
+#[test]
+fn parses_paths() {
+    assert_eq!(Route::from_path("/"), Some(Route::Home));
+    assert_eq!(Route::from_path(""), Some(Route::Home));
+    assert_eq!(Route::from_path("/blog/7"), Some(Route::BlogPost { id: 7 }));
+    assert_eq!(
+        Route::from_path("/blog/7/"),
+        Some(Route::BlogPost { id: 7 })
+    );
+    assert_eq!(
+        Route::from_path("/users/jane%20doe/posts/3"),
+        Some(Route::UserPost {
+            name: "jane doe".to_string(),
+            post: 3
+        })
+    );
+}
+
+#[test]
+fn falls_back_to_catch_all() {
+    let not_found = |path: &str| {
+        Some(Route::NotFound {
+            path: path.to_string(),
+        })
+    };
+
+    // Parameters of the wrong type don't match
+    assert_eq!(Route::from_path("/blog/latest"), not_found("blog/latest"));
+
+    assert_eq!(Route::from_path("/blog"), not_found("blog"));
+    assert_eq!(
+        Route::from_path("/blog/7/comments"),
+        not_found("blog/7/comments")
+    );
+    assert_eq!(Route::from_path("/about%20us"), not_found("about us"));
+}
+
+#[test]
+fn ranks_static_segments_over_parameters() {
+    assert_eq!(Route::from_path("/blog/new"), Some(Route::NewBlogPost));
+    assert_eq!(Route::from_path("/blog/3"), Some(Route::BlogPost { id: 3 }));
+}
+
+#[test]
+fn displays_paths() {
+    assert_eq!(Route::Home.to_string(), "/");
+    assert_eq!(Route::BlogPost { id: 7 }.to_string(), "/blog/7");
+
+    let route = Route::UserPost {
+        name: "jane doe/admin".to_string(),
+        post: 3,
+    };
+    assert_eq!(route.to_string(), "/users/jane%20doe%2Fadmin/posts/3");
+    assert_eq!(Route::from_path(&route.to_string()), Some(route));
+
+    let route = Route::NotFound {
+        path: "docs/getting started".to_string(),
+    };
+    assert_eq!(route.to_string(), "/docs/getting%20started");
+    assert_eq!(Route::from_path(&route.to_string()), Some(route));
+}
+
+#[derive(Routable, Clone, Debug, PartialEq)]
+enum Constrained {
+    #[route("/users/:id<int>", ConstrainedUser)]
+    User { id: i64 },
+
+    #[route("/users/:name<alpha>", ConstrainedName)]
+    Name { name: String },
+
+    #[route("/users/:handle", ConstrainedHandle)]
+    Handle { handle: String },
+}
+
+#[inline_props]
+fn ConstrainedUser(cx: Scope, id: i64) -> Element {
+    cx.render(rsx!("user {id}"))
+}
+
+#[inline_props]
+fn ConstrainedName(cx: Scope, name: String) -> Element {
+    cx.render(rsx!("name {name}"))
+}
+
+#[inline_props]
+fn ConstrainedHandle(cx: Scope, handle: String) -> Element {
+    cx.render(rsx!("handle {handle}"))
+}
+
+#[test]
+fn ranks_constrained_parameters_like_the_router() {
+    // Constrained parameters beat plain ones, whatever order they are declared in
+    assert_eq!(
+        Constrained::from_path("/users/-7"),
+        Some(Constrained::User { id: -7 })
+    );
+    assert_eq!(
+        Constrained::from_path("/users/jane"),
+        Some(Constrained::Name {
+            name: "jane".to_string()
+        })
+    );
+    assert_eq!(
+        Constrained::from_path("/users/jane_7"),
+        Some(Constrained::Handle {
+            handle: "jane_7".to_string()
+        })
+    );
+
+    // The constraint isn't part of the path
+    assert_eq!(Constrained::User { id: 3 }.to_string(), "/users/3");
+}
+
+#[derive(Routable, Clone, PartialEq)]
+enum TypedRoute {
+    #[route("/")]
+    Index,
+
+    #[route("/posts/:id", PostPage)]
+    Post { id: usize },
+}
+
+fn Index(cx: Scope) -> Element {
+    let post = TypedRoute::Post { id: 3 };
+
+    cx.render(rsx! { Link { to: "{post}", "post 3" } })
+}
+
+#[inline_props]
+fn PostPage(cx: Scope, id: usize) -> Element {
+    cx.render(rsx! { p { "post {id}" } })
+}
+
+#[test]
+fn renders_typed_routes() {
+    fn app(cx: Scope) -> Element {
+        cx.render(rsx! {
+            Router {
+                Switch::<TypedRoute> {}
+            }
+        })
+    }
+
+    let mut dom = VirtualDom::new(app).with_root_context(RouterCfg {
+        initial_url: Some("app:///".to_string()),
+        ..Default::default()
+    });
+    _ = dom.rebuild();
+    assert!(dioxus_ssr::render(&dom).starts_with("<a href=\"/posts/3\""));
+
+    let router = get_router_from_vdom(&dom, ScopeId(1)).unwrap();
+    router.navigate_to(&TypedRoute::Post { id: 3 }.to_string());
+    dom.process_events();
+    _ = dom.render_immediate();
+    assert_eq!(dioxus_ssr::render(&dom), "<p>post 3</p>");
+
+    // Nothing is rendered when no route matches
+    router.navigate_to("/posts/latest");
+    dom.process_events();
+    _ = dom.render_immediate();
+    assert_eq!(dioxus_ssr::render(&dom), "");
+}
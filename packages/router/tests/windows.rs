@@ -0,0 +1,58 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_router::*;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx! {
+        Router {
+            Route { to: "/admin", p { "dashboard" } }
+            Route { to: "/admin/users/:id", component: UserDetail }
+        }
+    })
+}
+
+fn UserDetail(cx: Scope) -> Element {
+    let id = use_route(cx).segment("id").unwrap_or_default().to_string();
+    cx.render(rsx! { p { "user {id}" } })
+}
+
+fn dom_at(url: &str) -> VirtualDom {
+    let mut dom = VirtualDom::new(app).with_root_context(RouterCfg {
+        initial_url: Some(url.to_string()),
+        ..Default::default()
+    });
+    _ = dom.rebuild();
+    dom
+}
+
+#[test]
+fn routes_every_window_independently() {
+    // Like the windows of a desktop app, every VirtualDom has a router of its own
+    let mut first = dom_at("app:///admin");
+    let mut second = dom_at("myapp://admin/users/3?tab=posts");
+
+    // A deep link starts the router at the path after its scheme
+    let router = get_router_from_vdom(&second, ScopeId(1)).unwrap();
+    assert_eq!(
+        router.current_location().url.as_str(),
+        "app:///admin/users/3?tab=posts"
+    );
+    assert_eq!(dioxus_ssr::render(&second), "<p>user 3</p>");
+
+    router.open_deep_link("myapp://admin/users/5");
+    second.process_events();
+    _ = second.render_immediate();
+    assert_eq!(dioxus_ssr::render(&second), "<p>user 5</p>");
+
+    // The first window didn't move along
+    first.process_events();
+    _ = first.render_immediate();
+    assert_eq!(dioxus_ssr::render(&first), "<p>dashboard</p>");
+    let router = get_router_from_vdom(&first, ScopeId(1)).unwrap();
+    assert!(!router.can_go_back());
+
+    // A plain path works as an initial url as well
+    let third = dom_at("/admin/users/9");
+    assert_eq!(dioxus_ssr::render(&third), "<p>user 9</p>");
+}
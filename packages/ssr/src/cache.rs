@@ -16,6 +16,10 @@ pub struct StringChain {
 pub enum Segment {
     Attr(usize),
     Node(usize),
+    /// Where the hydration id of the template root with this index goes
+    RootNodeMarker(usize),
+    /// Where the hydration id of an element with dynamic attributes goes, by the index of its first dynamic attribute
+    AttributeNodeMarker(usize),
     PreRendered(String),
 }
 
@@ -30,6 +34,31 @@ impl std::fmt::Write for StringChain {
     }
 }
 
+/// The rendered output of a single scope, with holes left where its child scopes go.
+///
+/// Since children are only referenced, a scope's output stays valid when its children re-render.
+#[derive(Default)]
+pub struct ScopeCache {
+    pub segments: Vec<ScopeSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScopeSegment {
+    PreRendered(String),
+    Scope(ScopeId),
+}
+
+impl std::fmt::Write for ScopeCache {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        match self.segments.last_mut() {
+            Some(ScopeSegment::PreRendered(s2)) => s2.push_str(s),
+            _ => self.segments.push(ScopeSegment::PreRendered(s.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
 impl StringCache {
     pub fn from_template(template: &VNode) -> Result<Self, std::fmt::Error> {
         let mut chain = StringChain::default();
@@ -61,6 +90,18 @@ impl StringCache {
             } => {
                 cur_path.push(root_idx);
                 write!(chain, "<{}", tag)?;
+
+                // Roots and elements with dynamic attributes are given an id when the template is created
+                let first_dynamic_attr = attrs.iter().find_map(|attr| match attr {
+                    TemplateAttribute::Dynamic { id } => Some(*id),
+                    _ => None,
+                });
+                if cur_path.len() == 1 {
+                    chain.segments.push(Segment::RootNodeMarker(root_idx));
+                } else if let Some(id) = first_dynamic_attr {
+                    chain.segments.push(Segment::AttributeNodeMarker(id));
+                }
+
                 for attr in *attrs {
                     match attr {
                         TemplateAttribute::Static { name, value, .. } => {
@@ -3,7 +3,14 @@
 mod cache;
 pub mod config;
 pub mod renderer;
+#[cfg(feature = "router")]
+mod routing;
+#[cfg(feature = "router")]
+pub mod server;
+#[cfg(feature = "ssg")]
+pub mod ssg;
 pub mod template;
+pub mod verify;
 use dioxus_core::{Element, LazyNodes, Scope, VirtualDom};
 use std::cell::Cell;
 
@@ -45,6 +52,21 @@ pub fn render(dom: &VirtualDom) -> String {
     Renderer::new().render(dom)
 }
 
+/// A convenience function to render an existing VirtualDom to a complete HTML document
+///
+/// To collect `<title>`, `<meta>` and other head entries, build the VirtualDom with a [`dioxus_html::head::HeadContext`]
+/// as a root context.
+///
+/// ```rust, ignore
+/// let mut dom = VirtualDom::new(app).with_root_context(HeadContext::new());
+/// let _ = dom.rebuild();
+///
+/// let html = dioxus_ssr::render_document(&dom);
+/// ```
+pub fn render_document(dom: &VirtualDom) -> String {
+    Renderer::new().render_document(dom)
+}
+
 /// A convenience function to pre-render an existing VirtualDom to a string
 ///
 /// We generally recommend creating a new `Renderer` to take advantage of template caching.
@@ -1,6 +1,7 @@
 use super::cache::Segment;
-use crate::cache::StringCache;
-use dioxus_core::{prelude::*, AttributeValue, DynamicNode, RenderReturn};
+use crate::cache::{ScopeCache, ScopeSegment, StringCache};
+use dioxus_core::{prelude::*, AttributeValue, DynamicNode, Mutations, RenderReturn};
+use dioxus_html::{head::HeadContext, hydration};
 use std::collections::HashMap;
 use std::fmt::Write;
 use std::rc::Rc;
@@ -24,8 +25,19 @@ pub struct Renderer {
     // Don't proceed onto new components. Instead, put the name of the component.
     pub skip_components: bool,
 
+    /// Scripts appended to the end of `<body>` when rendering a full document, like the loader for the client bundle
+    pub body_scripts: Vec<String>,
+
+    /// Keep the rendered output of every scope between renders, only re-rendering the scopes that changed.
+    ///
+    /// Pass the mutations from every render of the VirtualDom to [`Renderer::invalidate`] before rendering it again.
+    pub incremental: bool,
+
     /// A cache of templates that have been rendered
     template_cache: HashMap<&'static str, Rc<StringCache>>,
+
+    /// A cache of the output of each scope, used when rendering incrementally
+    scope_cache: HashMap<ScopeId, Rc<ScopeCache>>,
 }
 
 impl Renderer {
@@ -43,11 +55,107 @@ impl Renderer {
         self.render_scope(buf, dom, ScopeId(0))
     }
 
+    /// Forget the cached output of the scopes that were diffed, created, or removed in these mutations.
+    ///
+    /// Only matters when rendering [`Renderer::incremental`]ly.
+    ///
+    /// ```rust, ignore
+    /// let mut renderer = Renderer::new();
+    /// renderer.incremental = true;
+    ///
+    /// let html = renderer.render(&dom);
+    ///
+    /// dom.handle_event("click", data, id, true);
+    /// let mutations = dom.render_immediate();
+    /// renderer.invalidate(&mutations);
+    ///
+    /// // Only the scopes that changed are rendered again
+    /// let html = renderer.render(&dom);
+    /// ```
+    pub fn invalidate(&mut self, mutations: &Mutations) {
+        for scope in mutations.dirty_scopes.iter() {
+            self.scope_cache.remove(scope);
+        }
+    }
+
+    /// Forget the cached output of every scope, for instance after changing how the renderer formats its output.
+    pub fn clear_scope_cache(&mut self) {
+        self.scope_cache.clear();
+    }
+
+    /// Render the VirtualDom into a complete HTML document.
+    ///
+    /// The `<head>` is filled with the entries collected by the [`HeadContext`] at the root of the VirtualDom, if one
+    /// was provided. The app is rendered into `<div id="main">`, followed by [`Renderer::body_scripts`]. Enable
+    /// [`Renderer::pre_render`] to include the markers the client needs to hydrate the page.
+    pub fn render_document(&mut self, dom: &VirtualDom) -> String {
+        let mut buf = String::new();
+        self.render_document_to(&mut buf, dom).unwrap();
+        buf
+    }
+
+    /// Render the VirtualDom into a complete HTML document, writing it into a buffer.
+    ///
+    /// See [`Renderer::render_document`] for what the document contains.
+    pub fn render_document_to(
+        &mut self,
+        buf: &mut impl Write,
+        dom: &VirtualDom,
+    ) -> std::fmt::Result {
+        write!(buf, "<!DOCTYPE html><html><head>")?;
+
+        if let Some(head) = dom.base_scope().has_context::<HeadContext>() {
+            head.render_to(buf)?;
+        }
+
+        write!(buf, "</head><body><div id=\"main\">")?;
+        self.render_to(buf, dom)?;
+        write!(buf, "</div>")?;
+
+        for script in &self.body_scripts {
+            write!(buf, "{}", script)?;
+        }
+
+        write!(buf, "</body></html>")
+    }
+
     pub fn render_scope(
         &mut self,
         buf: &mut impl Write,
         dom: &VirtualDom,
         scope: ScopeId,
+    ) -> std::fmt::Result {
+        if !self.incremental {
+            return self.render_scope_contents(&mut Direct(buf), dom, scope);
+        }
+
+        let cached = match self.scope_cache.get(&scope) {
+            Some(cached) => cached.clone(),
+            None => {
+                let mut cached = ScopeCache::default();
+                self.render_scope_contents(&mut cached, dom, scope)?;
+
+                let cached = Rc::new(cached);
+                self.scope_cache.insert(scope, cached.clone());
+                cached
+            }
+        };
+
+        for segment in cached.segments.iter() {
+            match segment {
+                ScopeSegment::PreRendered(contents) => buf.write_str(contents)?,
+                ScopeSegment::Scope(child) => self.render_scope(buf, dom, *child)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn render_scope_contents(
+        &mut self,
+        buf: &mut impl RenderTarget,
+        dom: &VirtualDom,
+        scope: ScopeId,
     ) -> std::fmt::Result {
         // We should never ever run into async or errored nodes in SSR
         // Error boundaries and suspense boundaries will convert these to sync
@@ -60,7 +168,7 @@ impl Renderer {
 
     fn render_template(
         &mut self,
-        buf: &mut impl Write,
+        buf: &mut impl RenderTarget,
         dom: &VirtualDom,
         template: &VNode,
     ) -> std::fmt::Result {
@@ -75,7 +183,11 @@ impl Renderer {
                 Segment::Attr(idx) => {
                     let attr = &template.dynamic_attrs[*idx];
                     match attr.value {
-                        AttributeValue::Text(value) => write!(buf, " {}=\"{}\"", attr.name, value)?,
+                        AttributeValue::Text(value) => {
+                            write!(buf, " {}=\"", attr.name)?;
+                            escape_into(buf, value)?;
+                            write!(buf, "\"")?;
+                        }
                         AttributeValue::Bool(value) => write!(buf, " {}={}", attr.name, value)?,
                         _ => {}
                     };
@@ -85,30 +197,19 @@ impl Renderer {
                         if self.skip_components {
                             write!(buf, "<{}><{}/>", node.name, node.name)?;
                         } else {
-                            let id = node.scope.get().unwrap();
-                            let scope = dom.get_scope(id).unwrap();
-                            let node = scope.root_node();
-                            match node {
-                                RenderReturn::Sync(Ok(node)) => {
-                                    self.render_template(buf, dom, node)?
-                                }
-                                _ => todo!(
-                                    "generally, scopes should be sync, only if being traversed"
-                                ),
-                            }
+                            buf.write_scope(self, dom, node.scope.get().unwrap())?;
                         }
                     }
                     DynamicNode::Text(text) => {
                         // in SSR, we are concerned that we can't hunt down the right text node since they might get merged
                         if self.pre_render {
-                            write!(buf, "<!--#-->")?;
+                            hydration::write_text_start(buf, text.id.get())?;
                         }
 
-                        // todo: escape the text
-                        write!(buf, "{}", text.value)?;
+                        escape_into(buf, text.value)?;
 
                         if self.pre_render {
-                            write!(buf, "<!--#-->")?;
+                            hydration::write_text_end(buf)?;
                         }
                     }
                     DynamicNode::Fragment(nodes) => {
@@ -117,13 +218,27 @@ impl Renderer {
                         }
                     }
 
-                    DynamicNode::Placeholder(_el) => {
+                    DynamicNode::Placeholder(el) => {
+                        // The same hidden element the web renderer creates, so it can be hydrated in place
                         if self.pre_render {
-                            write!(buf, "<pre><pre/>")?;
+                            hydration::write_placeholder(buf, el.get())?;
                         }
                     }
                 },
 
+                Segment::RootNodeMarker(idx) => {
+                    if self.pre_render {
+                        hydration::write_node_id(buf, template.root_ids[*idx].get())?;
+                    }
+                }
+
+                Segment::AttributeNodeMarker(idx) => {
+                    if self.pre_render {
+                        let id = template.dynamic_attrs[*idx].mounted_element.get();
+                        hydration::write_node_id(buf, id)?;
+                    }
+                }
+
                 Segment::PreRendered(contents) => write!(buf, "{}", contents)?,
             }
         }
@@ -132,13 +247,72 @@ impl Renderer {
     }
 }
 
+/// Write text so it can't be read as markup, inside of an element or an attribute value
+pub(crate) fn escape_into(out: &mut impl Write, text: &str) -> std::fmt::Result {
+    for c in text.chars() {
+        match c {
+            '&' => out.write_str("&amp;")?,
+            '<' => out.write_str("&lt;")?,
+            '>' => out.write_str("&gt;")?,
+            '"' => out.write_str("&quot;")?,
+            '\'' => out.write_str("&#39;")?,
+            c => out.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+/// Somewhere the renderer can write a template to
+trait RenderTarget: Write {
+    /// Write out the contents of a child scope
+    fn write_scope(
+        &mut self,
+        renderer: &mut Renderer,
+        dom: &VirtualDom,
+        scope: ScopeId,
+    ) -> std::fmt::Result;
+}
+
+/// Writes straight into a buffer, rendering child scopes in place
+struct Direct<'a, W>(&'a mut W);
+
+impl<W: Write> Write for Direct<'_, W> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.0.write_str(s)
+    }
+}
+
+impl<W: Write> RenderTarget for Direct<'_, W> {
+    fn write_scope(
+        &mut self,
+        renderer: &mut Renderer,
+        dom: &VirtualDom,
+        scope: ScopeId,
+    ) -> std::fmt::Result {
+        renderer.render_scope(self.0, dom, scope)
+    }
+}
+
+/// Leaves a hole for child scopes, so they can be cached separately
+impl RenderTarget for ScopeCache {
+    fn write_scope(
+        &mut self,
+        _renderer: &mut Renderer,
+        _dom: &VirtualDom,
+        scope: ScopeId,
+    ) -> std::fmt::Result {
+        self.segments.push(ScopeSegment::Scope(scope));
+        Ok(())
+    }
+}
+
 #[test]
 fn to_string_works() {
     use dioxus::prelude::*;
 
     fn app(cx: Scope) -> Element {
         let dynamic = 123;
-        let dyn2 = "</diiiiiiiiv>"; // todo: escape this
+        let dyn2 = "</diiiiiiiiv>";
 
         render! {
             div { class: "asdasdasd", class: "asdasdasd", id: "id-{dynamic}",
@@ -163,7 +337,9 @@ fn to_string_works() {
             assert_eq!(
                 item.1.segments,
                 vec![
-                    PreRendered("<div class=\"asdasdasd\" class=\"asdasdasd\"".into(),),
+                    PreRendered("<div".into(),),
+                    RootNodeMarker(0,),
+                    PreRendered(" class=\"asdasdasd\" class=\"asdasdasd\"".into(),),
                     Attr(0,),
                     PreRendered(">Hello world 1 -->".into(),),
                     Node(0,),
@@ -180,5 +356,5 @@ fn to_string_works() {
 
     use Segment::*;
 
-    assert_eq!(out, "<div class=\"asdasdasd\" class=\"asdasdasd\" id=\"id-123\">Hello world 1 -->123<-- Hello world 2<div>nest 1</div><div></div><div>nest 2</div></diiiiiiiiv><div>finalize 0</div><div>finalize 1</div><div>finalize 2</div><div>finalize 3</div><div>finalize 4</div></div>");
+    assert_eq!(out, "<div class=\"asdasdasd\" class=\"asdasdasd\" id=\"id-123\">Hello world 1 -->123<-- Hello world 2<div>nest 1</div><div></div><div>nest 2</div>&lt;/diiiiiiiiv&gt;<div>finalize 0</div><div>finalize 1</div><div>finalize 2</div><div>finalize 3</div><div>finalize 4</div></div>");
 }
@@ -0,0 +1,53 @@
+//! Helpers shared by the renderers of routed apps.
+
+use dioxus_core::{prelude::*, DynamicNode, RenderReturn};
+use dioxus_router::RouterContext;
+
+/// The maximum number of render passes we'll run on a page before giving up on it settling.
+///
+/// Redirects and route changes schedule new renders, so a page may take a few passes to settle.
+const MAX_PASSES: usize = 16;
+
+/// Let redirects, route changes and loaders settle, and wait for suspense to resolve
+pub(crate) async fn settle(dom: &mut VirtualDom) {
+    for _ in 0..MAX_PASSES {
+        dom.process_events();
+        let unchanged = dom
+            .render_with_deadline(std::future::pending::<()>())
+            .await
+            .edits
+            .is_empty();
+
+        // Loaders of a navigation don't suspend the page, so wait for them separately
+        if matches!(find_router(dom, ScopeId(0)), Some(router) if router.is_navigating()) {
+            dom.wait_for_work().await;
+            continue;
+        }
+
+        if unchanged && !dom.has_suspended_work() {
+            break;
+        }
+    }
+}
+
+/// Walk the rendered tree looking for the first scope that can see a router
+pub(crate) fn find_router(dom: &VirtualDom, scope: ScopeId) -> Option<RouterContext> {
+    let scope = dom.get_scope(scope)?;
+
+    if let Some(router) = scope.has_context::<RouterContext>() {
+        return Some(router);
+    }
+
+    match scope.try_root_node()? {
+        RenderReturn::Sync(Ok(node)) => find_router_in_node(dom, node),
+        _ => None,
+    }
+}
+
+fn find_router_in_node(dom: &VirtualDom, node: &VNode) -> Option<RouterContext> {
+    node.dynamic_nodes.iter().find_map(|child| match child {
+        DynamicNode::Component(comp) => find_router(dom, comp.scope.get()?),
+        DynamicNode::Fragment(nodes) => nodes.iter().find_map(|n| find_router_in_node(dom, n)),
+        _ => None,
+    })
+}
@@ -0,0 +1,98 @@
+//! Server-side rendering for apps built on `dioxus-router`.
+//!
+//! The [`ServerRenderer`] renders the app for the path of an incoming request with a [`ServerHistory`], waits for
+//! redirects, loaders and suspense to settle, and reports what the server should respond with: the page, a 404 when no
+//! route matched, or a real redirect when the router ended up somewhere else.
+//!
+//! ```rust, ignore
+//! let mut renderer = ServerRenderer::new(app);
+//!
+//! async fn handler(uri: Uri) -> Response {
+//!     let response = renderer.render(&uri.to_string()).await;
+//!
+//!     match response.location {
+//!         Some(location) => Redirect::to(&location).into_response(),
+//!         None => (StatusCode::from_u16(response.status).unwrap(), Html(response.html)).into_response(),
+//!     }
+//! }
+//! ```
+
+use crate::{routing::settle, Renderer};
+use dioxus_core::{prelude::*, Component};
+use dioxus_html::head::HeadContext;
+use dioxus_router::{RouterCfg, ServerHistory};
+use std::rc::Rc;
+
+/// Renders a routed app for the paths of incoming requests.
+pub struct ServerRenderer {
+    app: Component,
+    renderer: Renderer,
+}
+
+/// What the server should respond with to a request, from [`ServerRenderer::render`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerResponse {
+    /// The HTTP status: 302 for redirects, 404 when no route matched, and 200 otherwise
+    pub status: u16,
+
+    /// The path the router was redirected to, for the `Location` header
+    pub location: Option<String>,
+
+    /// The full pattern of the innermost route that matched, like `/users/:id`
+    pub route: Option<String>,
+
+    /// The rendered document, which is empty for redirects
+    pub html: String,
+}
+
+impl ServerRenderer {
+    /// Create a new renderer for the given app.
+    ///
+    /// Pages are pre-rendered with hydration markers so the client can pick them up.
+    pub fn new(app: Component) -> Self {
+        let mut renderer = Renderer::new();
+        renderer.pre_render = true;
+
+        Self { app, renderer }
+    }
+
+    /// Use a custom renderer for every page, for instance to add [`Renderer::body_scripts`].
+    pub fn with_renderer(mut self, renderer: Renderer) -> Self {
+        self.renderer = renderer;
+        self
+    }
+
+    /// Render the app for the path of a request, including its query, like `/users/3?tab=posts`.
+    pub async fn render(&mut self, path: &str) -> ServerResponse {
+        let history = Rc::new(ServerHistory::new(path));
+
+        let mut dom = VirtualDom::new(self.app)
+            .with_root_context(RouterCfg {
+                history: Some(history.clone()),
+                ..Default::default()
+            })
+            .with_root_context(HeadContext::new());
+
+        let _ = dom.rebuild();
+        settle(&mut dom).await;
+
+        if let Some(location) = history.redirect() {
+            return ServerResponse {
+                status: 302,
+                location: Some(location),
+                route: history.matched_route(),
+                html: String::new(),
+            };
+        }
+
+        ServerResponse {
+            status: match history.is_not_found() {
+                true => 404,
+                false => 200,
+            },
+            location: None,
+            route: history.matched_route(),
+            html: self.renderer.render_document(&dom),
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! Static site generation for apps built on `dioxus-router`.
+//!
+//! The [`StaticSiteGenerator`] renders the app once per route with [`RouterCfg::initial_url`] pointed at that route,
+//! waits for any suspended components to resolve, and writes the resulting document to `<out_dir>/<route>/index.html`.
+//!
+//! Routes come from two places: paths added with [`StaticSiteGenerator::with_route`], and (unless disabled) every
+//! static `Route` the router registers while rendering. Routes with dynamic segments like `/blog/:id` can't be guessed,
+//! so add their concrete URLs by hand.
+//!
+//! ```rust, ignore
+//! let written = StaticSiteGenerator::new(app, "./dist")
+//!     .with_routes(posts.iter().map(|post| format!("/blog/{}", post.slug)))
+//!     .generate()
+//!     .await?;
+//! ```
+
+use crate::{
+    routing::{find_router, settle},
+    Renderer,
+};
+use dioxus_core::{prelude::*, Component};
+use dioxus_html::head::HeadContext;
+use dioxus_router::RouterCfg;
+use std::{
+    collections::{BTreeSet, HashSet, VecDeque},
+    path::{Component as PathComponent, PathBuf},
+};
+
+/// Renders every route of a routed app into a directory of static HTML files.
+pub struct StaticSiteGenerator {
+    app: Component,
+    out_dir: PathBuf,
+    routes: Vec<String>,
+    crawl: bool,
+    renderer: Renderer,
+}
+
+/// A single page produced by the [`StaticSiteGenerator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticPage {
+    /// The route that was requested
+    pub route: String,
+
+    /// The route the router ended up at, if it was redirected
+    pub redirect: Option<String>,
+
+    /// The rendered document
+    pub html: String,
+}
+
+impl StaticSiteGenerator {
+    /// Create a new generator for the given app, writing into `out_dir`.
+    ///
+    /// Pages are pre-rendered with hydration markers so the client can pick them up.
+    pub fn new(app: Component, out_dir: impl Into<PathBuf>) -> Self {
+        let mut renderer = Renderer::new();
+        renderer.pre_render = true;
+
+        Self {
+            app,
+            out_dir: out_dir.into(),
+            routes: vec!["/".to_string()],
+            crawl: true,
+            renderer,
+        }
+    }
+
+    /// Add a route to render, like `/blog/hello-world`.
+    pub fn with_route(mut self, route: impl Into<String>) -> Self {
+        self.routes.push(route.into());
+        self
+    }
+
+    /// Add several routes to render.
+    pub fn with_routes(mut self, routes: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.routes.extend(routes.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set whether the static routes registered by the router should be rendered too. Enabled by default.
+    pub fn crawl_routes(mut self, crawl: bool) -> Self {
+        self.crawl = crawl;
+        self
+    }
+
+    /// Use a custom renderer for every page, for instance to add [`Renderer::body_scripts`].
+    pub fn with_renderer(mut self, renderer: Renderer) -> Self {
+        self.renderer = renderer;
+        self
+    }
+
+    /// Render every page and write it to disk, returning the paths of the written files.
+    ///
+    /// Fails with [`std::io::ErrorKind::InvalidInput`] if a route doesn't map to a directory inside `out_dir`.
+    pub async fn generate(&mut self) -> std::io::Result<Vec<PathBuf>> {
+        let mut written = Vec::new();
+
+        for page in self.render_pages().await {
+            let dir = self.page_dir(&page.route)?;
+            std::fs::create_dir_all(&dir)?;
+
+            let file = dir.join("index.html");
+            std::fs::write(&file, &page.html)?;
+            written.push(file);
+        }
+
+        Ok(written)
+    }
+
+    /// The directory a route is written to, which is always inside `out_dir`
+    fn page_dir(&self, route: &str) -> std::io::Result<PathBuf> {
+        let mut dir = self.out_dir.clone();
+
+        for segment in normalize(route).split('/').filter(|s| !s.is_empty()) {
+            let mut components = std::path::Path::new(segment).components();
+            match (components.next(), components.next()) {
+                (Some(PathComponent::Normal(name)), None) => dir.push(name),
+                _ => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!("the route {:?} can't be written as a static page", route),
+                    ))
+                }
+            }
+        }
+
+        Ok(dir)
+    }
+
+    /// Render every page without writing anything to disk.
+    pub async fn render_pages(&mut self) -> Vec<StaticPage> {
+        let mut queue: VecDeque<String> = self.routes.iter().map(|r| normalize(r)).collect();
+        let mut seen: HashSet<String> = queue.iter().cloned().collect();
+        let mut pages = Vec::new();
+
+        while let Some(route) = queue.pop_front() {
+            let (page, discovered) = self.render_page(&route).await;
+
+            if self.crawl {
+                for found in discovered {
+                    if seen.insert(found.clone()) {
+                        queue.push_back(found);
+                    }
+                }
+            }
+
+            pages.push(page);
+        }
+
+        pages
+    }
+
+    /// Render a single route, returning the page and any static routes the router registered along the way.
+    pub async fn render_page(&mut self, route: &str) -> (StaticPage, BTreeSet<String>) {
+        let route = normalize(route);
+        let head = HeadContext::new();
+
+        let mut dom = VirtualDom::new(self.app)
+            .with_root_context(RouterCfg {
+                initial_url: Some(format!("app://{}", route)),
+                ..Default::default()
+            })
+            .with_root_context(head);
+
+        let _ = dom.rebuild();
+        settle(&mut dom).await;
+
+        let mut discovered = BTreeSet::new();
+        let mut redirect = None;
+        let mut redirect_href = None;
+
+        if let Some(router) = find_router(&dom, ScopeId(0)) {
+            for registered in router.registered_routes() {
+                let dynamic = registered.is_empty()
+                    || registered.split('/').any(|segment| {
+                        segment.starts_with(':')
+                            || segment.starts_with('*')
+                            || segment.ends_with('?')
+                    });
+                if !dynamic {
+                    discovered.insert(normalize(&registered));
+                }
+            }
+
+            // Pages are named after routes of the app, which don't include the base path
+            let location = router.current_location();
+            let path = location.url.path();
+            let landed = normalize(path.strip_prefix(router.base_path()).unwrap_or(path));
+            if landed != route {
+                redirect_href = Some(router.with_base(&landed));
+                redirect = Some(landed);
+            }
+        }
+
+        let html = match &redirect_href {
+            Some(target) => redirect_document(target),
+            None => self.renderer.render_document(&dom),
+        };
+
+        (
+            StaticPage {
+                route,
+                redirect,
+                html,
+            },
+            discovered,
+        )
+    }
+}
+
+/// Turn a route into an absolute path, resolving `.` and `..` segments so it can never climb above the root
+fn normalize(route: &str) -> String {
+    let mut segments = Vec::new();
+
+    for segment in route.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+
+    format!("/{}", segments.join("/"))
+}
+
+/// A static page can't send a 3xx, so redirected routes get a page that forwards the browser instead
+fn redirect_document(target: &str) -> String {
+    let mut escaped = String::new();
+    crate::renderer::escape_into(&mut escaped, target).unwrap();
+
+    format!(
+        "<!DOCTYPE html><html><head><meta http-equiv=\"refresh\" content=\"0; url={target}\"></head><body><a href=\"{target}\">{target}</a></body></html>",
+        target = escaped
+    )
+}
+
+#[test]
+fn routes_never_climb_above_the_root() {
+    assert_eq!(normalize("/../../etc/passwd"), "/etc/passwd");
+    assert_eq!(normalize("blog/./post/../other/"), "/blog/other");
+    assert_eq!(normalize("//.."), "/");
+}
+
+#[test]
+fn redirect_targets_are_escaped() {
+    let html = redirect_document("/a\"><script>alert(1)</script>");
+    assert!(!html.contains("<script>"));
+    assert!(html.contains("url=/a&quot;&gt;&lt;script&gt;"));
+}
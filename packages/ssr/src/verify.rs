@@ -0,0 +1,261 @@
+//! Check pre-rendered HTML against a VirtualDom without a browser.
+//!
+//! Hydration only works if the HTML the client finds matches, node for node and id for id, what the VirtualDom renders
+//! on the client. [`verify_hydration`] renders the VirtualDom with the hydration markers from
+//! [`dioxus_html::hydration`] and compares it against the HTML, so mismatches can be caught in `cargo test`:
+//!
+//! ```rust, ignore
+//! let html = render_page_on_server();
+//!
+//! let mut dom = VirtualDom::new(app);
+//! let _ = dom.rebuild();
+//!
+//! dioxus_ssr::verify::verify_hydration(&html, &dom).unwrap();
+//! ```
+//!
+//! The comparison is structural: whitespace inside tags, attribute order and quoting don't matter, but text, tags,
+//! attribute values and markers do.
+
+use crate::Renderer;
+use dioxus_core::VirtualDom;
+use std::fmt;
+
+/// Where pre-rendered HTML stops matching the VirtualDom.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HydrationMismatch {
+    /// The elements leading up to the mismatch, like `div > ul > li`
+    pub path: String,
+
+    /// What the VirtualDom renders
+    pub expected: String,
+
+    /// What the pre-rendered HTML contains instead
+    pub found: String,
+}
+
+impl fmt::Display for HydrationMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = match self.path.is_empty() {
+            true => "the root",
+            false => &self.path,
+        };
+
+        write!(
+            f,
+            "hydration mismatch at {}: expected {} but found {}",
+            path, self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for HydrationMismatch {}
+
+/// Check that pre-rendered HTML (the contents of the root element) can be hydrated by the given VirtualDom.
+///
+/// The VirtualDom must have been rebuilt.
+pub fn verify_hydration(html: &str, dom: &VirtualDom) -> Result<(), HydrationMismatch> {
+    let mut renderer = Renderer::new();
+    renderer.pre_render = true;
+
+    compare(&renderer.render(dom), html)
+}
+
+/// Structurally compare two pieces of HTML
+fn compare(expected: &str, found: &str) -> Result<(), HydrationMismatch> {
+    let snippet = |html: &str, offset: usize| {
+        format!("{:?}", html[offset..].chars().take(20).collect::<String>())
+    };
+
+    // Dynamic text is escaped, but the static text of a template is rendered as written and might still be markup
+    let expected = tokenize(expected).map_err(|offset| HydrationMismatch {
+        path: String::new(),
+        expected: "well-formed HTML".to_string(),
+        found: format!(
+            "{} in the HTML rendered by the VirtualDom",
+            snippet(expected, offset)
+        ),
+    })?;
+    let found = tokenize(found).map_err(|offset| HydrationMismatch {
+        path: String::new(),
+        expected: "well-formed HTML".to_string(),
+        found: snippet(found, offset),
+    })?;
+
+    let mut path = Vec::new();
+
+    for idx in 0..expected.len().max(found.len()) {
+        match (expected.get(idx), found.get(idx)) {
+            (Some(left), Some(right)) if left == right => match left {
+                Token::Open { tag, .. } => path.push(*tag),
+                Token::Close(_) => _ = path.pop(),
+                _ => {}
+            },
+            (left, right) => {
+                let describe = |token: Option<&Token>| match token {
+                    Some(token) => token.to_string(),
+                    None => "the end of the HTML".to_string(),
+                };
+
+                return Err(HydrationMismatch {
+                    path: path.join(" > "),
+                    expected: describe(left),
+                    found: describe(right),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Open {
+        tag: &'a str,
+        attrs: Vec<(&'a str, &'a str)>,
+    },
+    Close(&'a str),
+    Text(&'a str),
+    Comment(&'a str),
+}
+
+impl fmt::Display for Token<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Open { tag, attrs } => {
+                write!(f, "<{}", tag)?;
+                for (name, value) in attrs {
+                    write!(f, " {}=\"{}\"", name, value)?;
+                }
+                write!(f, ">")
+            }
+            Token::Close(tag) => write!(f, "</{}>", tag),
+            Token::Text(text) => write!(f, "the text {:?}", text),
+            Token::Comment(comment) => write!(f, "<!--{}-->", comment),
+        }
+    }
+}
+
+/// Split HTML into tokens, returning the offset of the first malformed piece on failure.
+///
+/// This only understands the subset of HTML the renderer writes: elements, attributes, text and comments.
+fn tokenize(html: &str) -> Result<Vec<Token<'_>>, usize> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while pos < html.len() {
+        let rest = &html[pos..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment.find("-->").ok_or(pos)?;
+            tokens.push(Token::Comment(&comment[..end]));
+            pos += 4 + end + 3;
+        } else if let Some(close) = rest.strip_prefix("</") {
+            let end = close.find('>').ok_or(pos)?;
+            let tag = close[..end].trim();
+
+            // Void elements are closed as soon as they're opened
+            if !is_void(tag) {
+                tokens.push(Token::Close(tag));
+            }
+            pos += 2 + end + 1;
+        } else if rest.starts_with('<') {
+            let (token, len) = tokenize_tag(rest).ok_or(pos)?;
+            let closed = len >= 2 && rest[..len].ends_with("/>");
+
+            if let Token::Open { tag, .. } = token {
+                tokens.push(token);
+                if closed || is_void(tag) {
+                    tokens.push(Token::Close(tag));
+                }
+            }
+            pos += len;
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(Token::Text(&rest[..end]));
+            pos += end;
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Read an opening tag, returning it along with its length
+fn tokenize_tag(html: &str) -> Option<(Token<'_>, usize)> {
+    let is_name_end = |c: char| c.is_whitespace() || c == '/' || c == '>' || c == '=';
+
+    let mut pos = 1;
+    let name_len = html[pos..].find(is_name_end)?;
+    let tag = &html[pos..pos + name_len];
+    pos += name_len;
+
+    let mut attrs = Vec::new();
+
+    loop {
+        let rest = &html[pos..];
+        let trimmed = rest.trim_start();
+        pos += rest.len() - trimmed.len();
+
+        if trimmed.starts_with("/>") {
+            pos += 2;
+            break;
+        }
+        if trimmed.starts_with('>') {
+            pos += 1;
+            break;
+        }
+
+        let name_len = trimmed.find(is_name_end)?;
+        if name_len == 0 {
+            return None;
+        }
+        let name = &trimmed[..name_len];
+        pos += name_len;
+
+        // Attributes without a value, like `hidden`
+        let value = match html[pos..].strip_prefix('=') {
+            Some(rest) => {
+                pos += 1;
+                match rest.chars().next()? {
+                    quote @ ('"' | '\'') => {
+                        let end = rest[1..].find(quote)?;
+                        pos += end + 2;
+                        &rest[1..end + 1]
+                    }
+                    _ => {
+                        let end = rest.find(|c: char| c.is_whitespace() || c == '>')?;
+                        pos += end;
+                        &rest[..end]
+                    }
+                }
+            }
+            None => "",
+        };
+
+        attrs.push((name, value));
+    }
+
+    attrs.sort_unstable();
+
+    Some((Token::Open { tag, attrs }, pos))
+}
+
+fn is_void(tag: &str) -> bool {
+    matches!(
+        tag,
+        "area"
+            | "base"
+            | "br"
+            | "col"
+            | "embed"
+            | "hr"
+            | "img"
+            | "input"
+            | "link"
+            | "meta"
+            | "param"
+            | "source"
+            | "track"
+            | "wbr"
+    )
+}
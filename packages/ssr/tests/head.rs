@@ -0,0 +1,87 @@
+use dioxus::prelude::*;
+use dioxus_html::head::{HeadContext, HeadEntry};
+
+#[test]
+fn renders_document_with_head() {
+    fn app(cx: Scope) -> Element {
+        use_title(cx, "Home");
+        use_meta(cx, "description", "the homepage");
+        use_stylesheet(cx, "/main.css");
+
+        render! { div { "hello!" } }
+    }
+
+    let mut dom = VirtualDom::new(app).with_root_context(HeadContext::new());
+    _ = dom.rebuild();
+
+    assert_eq!(
+        dioxus_ssr::render_document(&dom),
+        concat!(
+            "<!DOCTYPE html><html><head>",
+            "<title>Home</title>",
+            "<meta name=\"description\" content=\"the homepage\" data-dioxus-head>",
+            "<link rel=\"stylesheet\" href=\"/main.css\" data-dioxus-head>",
+            "</head><body><div id=\"main\"><div>hello!</div></div></body></html>"
+        )
+    );
+}
+
+#[test]
+fn children_override_parents() {
+    fn app(cx: Scope) -> Element {
+        use_title(cx, "Site");
+        use_meta(cx, "description", "the site");
+
+        render! { Child {} }
+    }
+
+    #[allow(non_snake_case)]
+    fn Child(cx: Scope) -> Element {
+        use_title(cx, "Page <1>");
+        use_meta(cx, "description", "the page");
+
+        render! { "page" }
+    }
+
+    let head = HeadContext::new();
+    let mut dom = VirtualDom::new(app).with_root_context(head.clone());
+    _ = dom.rebuild();
+
+    assert_eq!(head.title(), Some("Page <1>".to_string()));
+    assert_eq!(
+        head.render(),
+        "<title>Page &lt;1&gt;</title><meta name=\"description\" content=\"the page\" data-dioxus-head>"
+    );
+    assert!(head.take_changed());
+    assert!(!head.take_changed());
+}
+
+#[test]
+fn no_head_context() {
+    fn app(cx: Scope) -> Element {
+        use_title(cx, "ignored");
+
+        render! { div {} }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+
+    assert_eq!(
+        dioxus_ssr::render_document(&dom),
+        "<!DOCTYPE html><html><head></head><body><div id=\"main\"><div></div></div></body></html>"
+    );
+}
+
+#[test]
+fn styles_cannot_close_their_element() {
+    let mut out = String::new();
+    HeadEntry::Style("a::after { content: \"</STYLE><script>\" } </p>".to_string())
+        .render_to(&mut out)
+        .unwrap();
+
+    assert_eq!(
+        out,
+        "<style data-dioxus-head>a::after { content: \"<\\/STYLE><script>\" } </p></style>"
+    );
+}
@@ -0,0 +1,99 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_ssr::verify::verify_hydration;
+
+fn app(cx: Scope) -> Element {
+    let items = ["a", "b"];
+
+    render! {
+        div { class: "list",
+            ul {
+                items.iter().map(|item| rsx! { li { key: "{item}", "item {item}" } })
+            }
+            Footer {}
+        }
+    }
+}
+
+fn Footer(cx: Scope) -> Element {
+    render! { p { onclick: |_| {}, "footer" } }
+}
+
+fn rebuilt() -> VirtualDom {
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+    dom
+}
+
+#[test]
+fn pre_rendered_html_matches() {
+    let dom = rebuilt();
+    let html = dioxus_ssr::pre_render(&dom);
+
+    assert_eq!(verify_hydration(&html, &dom), Ok(()));
+}
+
+#[test]
+fn formatting_does_not_matter() {
+    let dom = rebuilt();
+    let html = concat!(
+        "<div class=list  data-node-hydration='1'><ul>",
+        "<li data-node-hydration=\"2\"><!--node-id3-->item a<!--#--></li>",
+        "<li data-node-hydration=\"4\" ><!--node-id5-->item b<!--#--></li>",
+        "</ul><p data-node-hydration=\"6\">footer</p></div>"
+    );
+
+    assert_eq!(verify_hydration(html, &dom), Ok(()));
+}
+
+#[test]
+fn reports_the_first_mismatch() {
+    let dom = rebuilt();
+    let html = dioxus_ssr::pre_render(&dom).replace("item b", "item c");
+
+    let err = verify_hydration(&html, &dom).unwrap_err();
+    assert_eq!(err.path, "div > ul > li");
+    assert_eq!(err.expected, "the text \"item b\"");
+    assert_eq!(err.found, "the text \"item c\"");
+}
+
+#[test]
+fn html_without_markers_does_not_match() {
+    let dom = rebuilt();
+    let html = dioxus_ssr::render(&dom);
+
+    let err = verify_hydration(&html, &dom).unwrap_err();
+    assert_eq!(err.path, "");
+    assert!(err
+        .to_string()
+        .starts_with("hydration mismatch at the root"));
+}
+
+#[test]
+fn malformed_html_is_reported_by_characters() {
+    let dom = rebuilt();
+    let html = format!("<div{}", "é".repeat(30));
+
+    let err = verify_hydration(&html, &dom).unwrap_err();
+    assert_eq!(err.expected, "well-formed HTML");
+    assert_eq!(
+        err.found,
+        format!("{:?}", format!("<div{}", "é".repeat(16)))
+    );
+}
+
+#[test]
+fn text_with_markup_verifies() {
+    fn Comparison(cx: Scope) -> Element {
+        let text = "use <a href=\"/docs for help";
+        render! { p { "{text}" } }
+    }
+
+    let mut dom = VirtualDom::new(Comparison);
+    let _ = dom.rebuild();
+    let html = dioxus_ssr::pre_render(&dom);
+
+    assert!(html.contains("use &lt;a href=&quot;/docs for help"));
+    assert_eq!(verify_hydration(&html, &dom), Ok(()));
+}
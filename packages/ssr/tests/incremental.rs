@@ -0,0 +1,74 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_ssr::Renderer;
+use std::{cell::Cell, rc::Rc};
+
+fn app(cx: Scope) -> Element {
+    render! {
+        div { "header" }
+        Counter {}
+        div { "footer" }
+    }
+}
+
+fn Counter(cx: Scope) -> Element {
+    let count = cx.consume_context::<Rc<Cell<usize>>>().unwrap().get();
+
+    render! { span { "count: {count}" } }
+}
+
+#[test]
+fn rerenders_only_dirty_scopes() {
+    let count = Rc::new(Cell::new(0usize));
+    let mut dom = VirtualDom::new(app).with_root_context(count.clone());
+
+    let mut renderer = Renderer::new();
+    renderer.incremental = true;
+
+    let mutations = dom.rebuild();
+    renderer.invalidate(&mutations);
+    assert_eq!(
+        renderer.render(&dom),
+        "<div>header</div><span>count: 0</span><div>footer</div>"
+    );
+
+    // Re-render only the counter
+    count.set(1);
+    dom.mark_dirty(ScopeId(1));
+    let mutations = dom.render_immediate();
+
+    assert!(mutations.dirty_scopes.contains(&ScopeId(1)));
+    assert!(!mutations.dirty_scopes.contains(&ScopeId(0)));
+
+    renderer.invalidate(&mutations);
+    assert_eq!(
+        renderer.render(&dom),
+        "<div>header</div><span>count: 1</span><div>footer</div>"
+    );
+}
+
+#[test]
+fn keeps_output_until_invalidated() {
+    let count = Rc::new(Cell::new(0usize));
+    let mut dom = VirtualDom::new(app).with_root_context(count.clone());
+
+    let mut renderer = Renderer::new();
+    renderer.incremental = true;
+
+    _ = dom.rebuild();
+    let before = renderer.render(&dom);
+
+    count.set(1);
+    dom.mark_dirty(ScopeId(1));
+    _ = dom.render_immediate();
+
+    // Without invalidating, the cached output of the counter is reused
+    assert_eq!(renderer.render(&dom), before);
+
+    renderer.clear_scope_cache();
+    assert_eq!(
+        renderer.render(&dom),
+        "<div>header</div><span>count: 1</span><div>footer</div>"
+    );
+}
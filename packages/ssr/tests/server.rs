@@ -0,0 +1,52 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_router::*;
+use dioxus_ssr::server::ServerRenderer;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx! {
+        Router {
+            Route { to: "/", h1 { "Home" } }
+            Route { to: "/users/:id", User {} }
+            Redirect { from: "/old-users/:id", to: "/users/1?from=old" }
+            Route { to: "*", h1 { "Not found" } }
+        }
+    })
+}
+
+fn User(cx: Scope) -> Element {
+    let id = use_route(cx).segment("id").unwrap_or_default().to_string();
+    cx.render(rsx! { h1 { "User {id}" } })
+}
+
+#[tokio::test]
+async fn renders_requested_routes() {
+    let mut renderer = ServerRenderer::new(app);
+
+    let response = renderer.render("/users/7?tab=posts").await;
+    assert_eq!(response.status, 200);
+    assert_eq!(response.location, None);
+    assert_eq!(response.route.as_deref(), Some("/users/:id"));
+    assert!(response.html.contains("User 7"));
+}
+
+#[tokio::test]
+async fn reports_missing_routes() {
+    let mut renderer = ServerRenderer::new(app);
+
+    let response = renderer.render("/nowhere").await;
+    assert_eq!(response.status, 404);
+    assert_eq!(response.route.as_deref(), Some("*"));
+    assert!(response.html.contains("Not found"));
+}
+
+#[tokio::test]
+async fn reports_redirects() {
+    let mut renderer = ServerRenderer::new(app);
+
+    let response = renderer.render("/old-users/3").await;
+    assert_eq!(response.status, 302);
+    assert_eq!(response.location.as_deref(), Some("/users/1?from=old"));
+    assert!(response.html.is_empty());
+}
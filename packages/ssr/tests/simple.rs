@@ -74,3 +74,24 @@ fn fragments() {
         "<div></div>"
     );
 }
+
+#[test]
+fn pre_render_markers() {
+    fn app(cx: Scope) -> Element {
+        let name = "world";
+        render! {
+            div {
+                "hello {name}"
+                None::<Element>
+            }
+        }
+    }
+
+    let mut dom = VirtualDom::new(app);
+    _ = dom.rebuild();
+
+    assert_eq!(
+        dioxus_ssr::pre_render(&dom),
+        "<div data-node-hydration=\"1\"><!--node-id2-->hello world<!--#--><pre hidden data-node-hydration=\"3\"></pre></div>"
+    );
+}
@@ -0,0 +1,112 @@
+#![allow(non_snake_case)]
+
+use dioxus::prelude::*;
+use dioxus_router::*;
+use dioxus_ssr::ssg::StaticSiteGenerator;
+use std::time::Duration;
+
+fn app(cx: Scope) -> Element {
+    cx.render(rsx! {
+        Router {
+            Route { to: "/", Home {} }
+            Route { to: "/about", About {} }
+            Route { to: "/blog/:id", BlogPost {} }
+            Route { to: "/slow", Slow {} }
+            Redirect { from: "/old-about", to: "/about" }
+        }
+    })
+}
+
+fn Home(cx: Scope) -> Element {
+    use_title(cx, "Home");
+    cx.render(rsx! { h1 { "Home" } })
+}
+
+fn About(cx: Scope) -> Element {
+    use_title(cx, "About");
+    cx.render(rsx! { h1 { "About" } })
+}
+
+fn BlogPost(cx: Scope) -> Element {
+    let id = use_route(cx).segment("id").unwrap_or_default().to_string();
+    cx.render(rsx! { h1 { "Post {id}" } })
+}
+
+fn Slow(cx: Scope) -> Element {
+    cx.render(rsx! { SlowInner {} })
+}
+
+async fn SlowInner(cx: Scope<'_>) -> Element {
+    use_future!(cx, || tokio::time::sleep(Duration::from_millis(10))).await;
+    cx.render(rsx! { h1 { "Loaded" } })
+}
+
+#[tokio::test]
+async fn renders_every_route() {
+    let mut ssg = StaticSiteGenerator::new(app, "unused").with_route("/blog/hello");
+    let pages = ssg.render_pages().await;
+
+    let routes = pages.iter().map(|p| p.route.as_str()).collect::<Vec<_>>();
+    assert_eq!(
+        routes,
+        ["/", "/blog/hello", "/about", "/old-about", "/slow"]
+    );
+
+    let page = |route: &str| pages.iter().find(|p| p.route == route).unwrap();
+
+    assert!(page("/").html.contains("<title>Home</title>"));
+    assert!(page("/").html.contains(">Home</h1>"));
+    assert!(!page("/").html.contains(">About</h1>"));
+
+    assert!(page("/about").html.contains("<title>About</title>"));
+    assert!(page("/blog/hello")
+        .html
+        .contains("-->Post hello<!--#--></h1>"));
+    assert!(page("/slow").html.contains(">Loaded</h1>"));
+
+    assert_eq!(page("/old-about").redirect.as_deref(), Some("/about"));
+    assert!(page("/old-about").html.contains("url=/about"));
+}
+
+#[tokio::test]
+async fn writes_index_files() {
+    let out_dir = std::env::temp_dir().join(format!("dioxus-ssg-test-{}", std::process::id()));
+
+    let written = StaticSiteGenerator::new(app, &out_dir)
+        .crawl_routes(false)
+        .with_route("/about")
+        .generate()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        written,
+        [out_dir.join("index.html"), out_dir.join("about/index.html")]
+    );
+    assert!(std::fs::read_to_string(out_dir.join("about/index.html"))
+        .unwrap()
+        .starts_with("<!DOCTYPE html>"));
+
+    std::fs::remove_dir_all(out_dir).unwrap();
+}
+
+#[tokio::test]
+async fn pages_stay_inside_the_output_directory() {
+    let root = std::env::temp_dir().join(format!("dioxus-ssg-escape-{}", std::process::id()));
+    let out_dir = root.join("dist");
+
+    let written = StaticSiteGenerator::new(app, &out_dir)
+        .crawl_routes(false)
+        .with_route("/../../about")
+        .generate()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        written,
+        [out_dir.join("index.html"), out_dir.join("about/index.html")]
+    );
+    assert!(!root.join("about").exists());
+
+    std::fs::remove_dir_all(root).unwrap();
+}
@@ -68,7 +68,7 @@ pub fn launch(app: Component<()>) {
 }
 
 pub fn launch_cfg(app: Component<()>, cfg: Config) {
-    let mut dom = VirtualDom::new(app);
+    let mut dom = VirtualDom::new(app).with_layout_effects();
 
     let (handler, state, register_event) = RinkInputHandler::new();
 
@@ -103,6 +103,7 @@ pub fn launch_cfg(app: Component<()>, cfg: Config) {
         let mut rdom = rdom.borrow_mut();
         let mutations = dom.rebuild();
         let (to_update, _) = rdom.apply_mutations(mutations);
+        dom.run_layout_effects();
         let mut any_map = SendAnyMap::new();
         any_map.insert(taffy.clone());
         let _to_rerender = rdom.update_state(to_update, any_map);
@@ -253,6 +254,7 @@ fn render_vdom(
                     handler.prune(&mutations, &rdom);
                     // updates the dom's nodes
                     let (to_update, dirty) = rdom.apply_mutations(mutations);
+                    vdom.run_layout_effects();
                     // update the style and layout
                     let mut any_map = SendAnyMap::new();
                     any_map.insert(taffy.clone());
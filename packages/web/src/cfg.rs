@@ -35,10 +35,12 @@ impl Config {
 
     /// Enable SSR hydration
     ///
-    /// This enables Dioxus to pick up work from a pre-renderd HTML file. Hydration will completely skip over any async
-    /// work and suspended nodes.
+    /// This enables Dioxus to pick up work from a pre-rendered HTML file. The root element must contain the output of
+    /// `dioxus_ssr::pre_render` (or a `Renderer` with `pre_render` enabled) for the same app, so Dioxus can adopt the
+    /// existing nodes and attach listeners to them instead of creating the page again.
     ///
-    /// Dioxus will load up all the elements with the `dio_el` data attribute into memory when the page is loaded.
+    /// If the pre-rendered HTML doesn't match what the app renders on the client, the mismatch is logged as a
+    /// [`crate::RehydrationError`] and the app is built from scratch.
     pub fn hydrate(mut self, f: bool) -> Self {
         self.hydrate = f;
         self
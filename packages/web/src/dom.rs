@@ -7,7 +7,7 @@
 //! - tests to ensure dyn_into works for various event types.
 //! - Partial delegation?>
 
-use dioxus_core::{Mutation, Template};
+use dioxus_core::{ElementId, Mutation, ScopeId, Template};
 use dioxus_html::{event_bubbles, CompositionData, FormData};
 use dioxus_interpreter_js::Interpreter;
 use futures_channel::mpsc;
@@ -18,9 +18,9 @@ use web_sys::{Document, Element, Event};
 use crate::Config;
 
 pub struct WebsysDom {
-    interpreter: Interpreter,
-    handler: Closure<dyn FnMut(&Event)>,
-    _root: Element,
+    pub(crate) interpreter: Interpreter,
+    pub(crate) handler: Closure<dyn FnMut(&Event)>,
+    pub(crate) root: Element,
 }
 
 impl WebsysDom {
@@ -35,7 +35,7 @@ impl WebsysDom {
 
         Self {
             interpreter: Interpreter::new(root.clone()),
-            _root: root,
+            root,
             handler: Closure::wrap(Box::new(move |event: &web_sys::Event| {
                 let _ = event_channel.unbounded_send(event.clone());
             })),
@@ -46,6 +46,11 @@ impl WebsysDom {
         self.interpreter.MountToRoot();
     }
 
+    /// Remove everything inside the root element, like pre-rendered HTML that couldn't be hydrated
+    pub fn clear(&mut self) {
+        self.root.set_text_content(None);
+    }
+
     pub fn load_templates(&mut self, templates: &[Template]) {
         log::debug!("Loading templates {:?}", templates);
 
@@ -96,6 +101,202 @@ impl WebsysDom {
     }
 }
 
+/// An owned copy of a set of edits.
+///
+/// Hydration needs to read the VirtualDom after rebuilding it, so the edits of the rebuild are saved in case the
+/// pre-rendered HTML doesn't match and the app has to be built from scratch.
+pub struct SavedEdits(Vec<SavedEdit>);
+
+enum SavedEdit {
+    AppendChildren {
+        id: ElementId,
+        m: usize,
+    },
+    AssignId {
+        path: &'static [u8],
+        id: ElementId,
+    },
+    CreatePlaceholder {
+        id: ElementId,
+    },
+    CreateTextNode {
+        value: String,
+        id: ElementId,
+    },
+    HydrateText {
+        path: &'static [u8],
+        value: String,
+        id: ElementId,
+    },
+    LoadTemplate {
+        name: &'static str,
+        index: usize,
+        id: ElementId,
+    },
+    ReplaceWith {
+        id: ElementId,
+        m: usize,
+    },
+    ReplacePlaceholder {
+        path: &'static [u8],
+        m: usize,
+    },
+    InsertAfter {
+        id: ElementId,
+        m: usize,
+    },
+    InsertBefore {
+        id: ElementId,
+        m: usize,
+    },
+    SetAttribute {
+        name: String,
+        value: String,
+        id: ElementId,
+        ns: Option<String>,
+    },
+    SetBoolAttribute {
+        name: String,
+        value: bool,
+        id: ElementId,
+    },
+    SetText {
+        value: String,
+        id: ElementId,
+    },
+    NewEventListener {
+        name: String,
+        scope: ScopeId,
+        id: ElementId,
+    },
+    RemoveEventListener {
+        name: String,
+        id: ElementId,
+    },
+    Remove {
+        id: ElementId,
+    },
+    PushRoot {
+        id: ElementId,
+    },
+}
+
+impl SavedEdits {
+    pub fn new(edits: &[Mutation]) -> Self {
+        use Mutation::*;
+        Self(
+            edits
+                .iter()
+                .map(|edit| match *edit {
+                    AppendChildren { id, m } => SavedEdit::AppendChildren { id, m },
+                    AssignId { path, id } => SavedEdit::AssignId { path, id },
+                    CreatePlaceholder { id } => SavedEdit::CreatePlaceholder { id },
+                    CreateTextNode { value, id } => SavedEdit::CreateTextNode {
+                        value: value.to_string(),
+                        id,
+                    },
+                    HydrateText { path, value, id } => SavedEdit::HydrateText {
+                        path,
+                        value: value.to_string(),
+                        id,
+                    },
+                    LoadTemplate { name, index, id } => SavedEdit::LoadTemplate { name, index, id },
+                    ReplaceWith { id, m } => SavedEdit::ReplaceWith { id, m },
+                    ReplacePlaceholder { path, m } => SavedEdit::ReplacePlaceholder { path, m },
+                    InsertAfter { id, m } => SavedEdit::InsertAfter { id, m },
+                    InsertBefore { id, m } => SavedEdit::InsertBefore { id, m },
+                    SetAttribute {
+                        name,
+                        value,
+                        id,
+                        ns,
+                    } => SavedEdit::SetAttribute {
+                        name: name.to_string(),
+                        value: value.to_string(),
+                        id,
+                        ns: ns.map(str::to_string),
+                    },
+                    SetBoolAttribute { name, value, id } => SavedEdit::SetBoolAttribute {
+                        name: name.to_string(),
+                        value,
+                        id,
+                    },
+                    SetText { value, id } => SavedEdit::SetText {
+                        value: value.to_string(),
+                        id,
+                    },
+                    NewEventListener { name, scope, id } => SavedEdit::NewEventListener {
+                        name: name.to_string(),
+                        scope,
+                        id,
+                    },
+                    RemoveEventListener { name, id } => SavedEdit::RemoveEventListener {
+                        name: name.to_string(),
+                        id,
+                    },
+                    Remove { id } => SavedEdit::Remove { id },
+                    PushRoot { id } => SavedEdit::PushRoot { id },
+                })
+                .collect(),
+        )
+    }
+
+    /// Borrow the saved edits so they can be applied
+    pub fn edits(&self) -> Vec<Mutation<'_>> {
+        use Mutation::*;
+        self.0
+            .iter()
+            .map(|edit| match edit {
+                SavedEdit::AppendChildren { id, m } => AppendChildren { id: *id, m: *m },
+                SavedEdit::AssignId { path, id } => AssignId { path, id: *id },
+                SavedEdit::CreatePlaceholder { id } => CreatePlaceholder { id: *id },
+                SavedEdit::CreateTextNode { value, id } => CreateTextNode { value, id: *id },
+                SavedEdit::HydrateText { path, value, id } => HydrateText {
+                    path,
+                    value,
+                    id: *id,
+                },
+                SavedEdit::LoadTemplate { name, index, id } => LoadTemplate {
+                    name,
+                    index: *index,
+                    id: *id,
+                },
+                SavedEdit::ReplaceWith { id, m } => ReplaceWith { id: *id, m: *m },
+                SavedEdit::ReplacePlaceholder { path, m } => ReplacePlaceholder { path, m: *m },
+                SavedEdit::InsertAfter { id, m } => InsertAfter { id: *id, m: *m },
+                SavedEdit::InsertBefore { id, m } => InsertBefore { id: *id, m: *m },
+                SavedEdit::SetAttribute {
+                    name,
+                    value,
+                    id,
+                    ns,
+                } => SetAttribute {
+                    name,
+                    value,
+                    id: *id,
+                    ns: ns.as_deref(),
+                },
+                SavedEdit::SetBoolAttribute { name, value, id } => SetBoolAttribute {
+                    name,
+                    value: *value,
+                    id: *id,
+                },
+                SavedEdit::SetText { value, id } => SetText { value, id: *id },
+                SavedEdit::NewEventListener { name, scope, id } => NewEventListener {
+                    name,
+                    scope: *scope,
+                    id: *id,
+                },
+                SavedEdit::RemoveEventListener { name, id } => {
+                    RemoveEventListener { name, id: *id }
+                }
+                SavedEdit::Remove { id } => Remove { id: *id },
+                SavedEdit::PushRoot { id } => PushRoot { id: *id },
+            })
+            .collect()
+    }
+}
+
 // todo: some of these events are being casted to the wrong event type.
 // We need tests that simulate clicks/etc and make sure every event type works.
 pub fn virtual_event_from_websys_event(event: web_sys::Event, target: Element) -> Rc<dyn Any> {
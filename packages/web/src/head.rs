@@ -0,0 +1,31 @@
+//! Keep the document `<head>` in sync with the entries collected by the [`HeadContext`].
+
+use dioxus_html::head::{HeadContext, HEAD_MARKER};
+use web_sys::Document;
+
+/// Replace the managed elements in the document head and update the title.
+///
+/// Any elements carrying the head marker are removed first, including the ones written by SSR. When no mounted
+/// component sets a title anymore, the document gets back the title it had before the app started.
+pub(crate) fn apply_head(document: &Document, head: &HeadContext, default_title: &str) {
+    document.set_title(&head.title().unwrap_or_else(|| default_title.to_string()));
+
+    let head_el = match document.head() {
+        Some(head_el) => head_el,
+        None => return,
+    };
+
+    if let Ok(managed) = head_el.query_selector_all(&format!("[{}]", HEAD_MARKER)) {
+        for idx in 0..managed.length() {
+            if let Some(node) = managed.item(idx) {
+                if let Some(parent) = node.parent_node() {
+                    _ = parent.remove_child(&node);
+                }
+            }
+        }
+    }
+
+    if let Err(err) = head_el.insert_adjacent_html("beforeend", &head.render_elements()) {
+        log::error!("Failed to update the document head: {:?}", err);
+    }
+}
@@ -55,15 +55,18 @@
 
 pub use crate::cfg::Config;
 use crate::dom::virtual_event_from_websys_event;
+pub use crate::rehydrate::RehydrationError;
 pub use crate::util::use_eval;
 use dioxus_core::{Element, ElementId, Scope, VirtualDom};
+use dioxus_html::head::HeadContext;
 use futures_util::{pin_mut, FutureExt, StreamExt};
 
 mod cache;
 mod cfg;
 mod dom;
+mod head;
 mod hot_reload;
-// mod rehydrate;
+mod rehydrate;
 mod ric_raf;
 mod util;
 
@@ -161,7 +164,10 @@ pub fn launch_with_props<T: 'static>(
 pub async fn run_with_props<T: 'static>(root: fn(Scope<T>) -> Element, root_props: T, cfg: Config) {
     log::info!("Starting up");
 
-    let mut dom = VirtualDom::new_with_props(root, root_props);
+    let head = HeadContext::new();
+    let mut dom = VirtualDom::new_with_props(root, root_props)
+        .with_root_context(head.clone())
+        .with_layout_effects();
 
     #[cfg(feature = "panic_hook")]
     if cfg.default_panic_hook {
@@ -177,23 +183,49 @@ pub async fn run_with_props<T: 'static>(root: fn(Scope<T>) -> Element, root_prop
         wasm_bindgen::intern(s);
     }
 
-    let _should_hydrate = cfg.hydrate;
+    let should_hydrate = cfg.hydrate;
 
     let (tx, mut rx) = futures_channel::mpsc::unbounded();
 
     let mut websys_dom = dom::WebsysDom::new(cfg, tx);
 
+    // The title the page is restored to once no component sets one, read before the app touches the document
+    let document = dom::load_document();
+    let default_title = document.title();
+
     log::info!("rebuilding app");
 
-    // if should_hydrate {
-    // } else {
-    let edits = dom.rebuild();
+    if should_hydrate {
+        // Keep the edits in case the pre-rendered nodes can't be adopted and the app has to be built from scratch
+        let edits = dom.rebuild();
+        websys_dom.load_templates(&edits.templates);
+        let edits = dom::SavedEdits::new(&edits.edits);
+
+        // Adopt the pre-rendered nodes instead of creating them, falling back to building the app from scratch
+        if let Err(err) = websys_dom.rehydrate(&dom) {
+            log::error!(
+                "Rehydration failed: {}. Rebuilding the app from scratch",
+                err
+            );
+            websys_dom.clear();
+            websys_dom.apply_edits(edits.edits());
+            websys_dom.mount();
+        }
+    } else {
+        let edits = dom.rebuild();
+
+        websys_dom.load_templates(&edits.templates);
+        websys_dom.apply_edits(edits.edits);
+
+        // the mutations come back with nothing - we need to actually mount them
+        websys_dom.mount();
+    }
 
-    websys_dom.load_templates(&edits.templates);
-    websys_dom.apply_edits(edits.edits);
+    dom.run_layout_effects();
 
-    // the mutations come back with nothing - we need to actually mount them
-    websys_dom.mount();
+    if head.take_changed() {
+        head::apply_head(&document, &head, &default_title);
+    }
 
     let mut work_loop = ric_raf::RafLoop::new();
 
@@ -246,6 +278,11 @@ pub async fn run_with_props<T: 'static>(root: fn(Scope<T>) -> Element, root_prop
 
         websys_dom.load_templates(&edits.templates);
         websys_dom.apply_edits(edits.edits);
+        dom.run_layout_effects();
+
+        if head.take_changed() {
+            head::apply_head(&document, &head, &default_title);
+        }
     }
 }
 
@@ -271,30 +308,3 @@ fn walk_event_for_id(event: &web_sys::Event) -> Option<(ElementId, web_sys::Elem
         }
     }
 }
-
-// if should_hydrate {
-//     // todo: we need to split rebuild and initialize into two phases
-//     // it's a waste to produce edits just to get the vdom loaded
-//     let _ = dom.rebuild();
-
-//     #[cfg(feature = "hydrate")]
-//     #[allow(unused_variables)]
-//     if let Err(err) = websys_dom.rehydrate(&dom) {
-//         log::error!(
-//             "Rehydration failed {:?}. Rebuild DOM into element from scratch",
-//             &err
-//         );
-
-//         websys_dom.root.set_text_content(None);
-
-//         // errrrr we should split rebuild into two phases
-//         // one that initializes things and one that produces edits
-//         let edits = dom.rebuild();
-
-//         websys_dom.apply_edits(edits.edits);
-//     }
-// } else {
-//     let edits = dom.rebuild();
-//     websys_dom.apply_edits(edits.template_mutations);
-//     websys_dom.apply_edits(edits.edits);
-// }
@@ -1,174 +1,465 @@
+//! Adopt the HTML written by `dioxus_ssr::pre_render` instead of creating the app's nodes from scratch.
+//!
+//! The VirtualDom is rebuilt as usual, but instead of applying the edits, we walk every template alongside the
+//! existing DOM and collect the nodes the edits would have created. The markers the pre-renderer writes (see
+//! [`dioxus_html::hydration`]) are checked against the ids the VirtualDom assigned, and removed along the way.
+//!
+//! The walk happens on a copy of the pre-rendered nodes. Only once the whole app matched are the nodes swapped into the
+//! document and handed to the interpreter, with their listeners attached, so a failed hydration leaves both the
+//! document and the interpreter untouched.
+
 use crate::dom::WebsysDom;
-use dioxus_core::{VNode, VirtualDom};
+use dioxus_core::{
+    Attribute, AttributeValue, DynamicNode, ElementId, RenderReturn, ScopeId, TemplateAttribute,
+    TemplateNode, VNode, VirtualDom,
+};
+use dioxus_html::{event_bubbles, hydration};
+use std::fmt;
 use wasm_bindgen::JsCast;
 use web_sys::{Comment, Element, Node, Text};
 
-#[derive(Debug)]
+/// The reason pre-rendered HTML couldn't be hydrated.
+///
+/// When hydration fails, the pre-rendered nodes are thrown away and the app is built from scratch.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RehydrationError {
-    NodeTypeMismatch,
-    NodeNotFound,
+    /// The pre-rendered HTML ran out of nodes where the VirtualDom expected one
+    NodeNotFound {
+        /// A description of the node the VirtualDom expected
+        expected: String,
+    },
+
+    /// A node in the pre-rendered HTML isn't the one the VirtualDom expected
+    NodeTypeMismatch {
+        /// A description of the node the VirtualDom expected
+        expected: String,
+
+        /// A description of the node that was found instead
+        found: String,
+    },
+
+    /// A node in the pre-rendered HTML was written for a different node of the VirtualDom
+    NodeIdMismatch {
+        /// The id the VirtualDom assigned to the node
+        expected: ElementId,
+
+        /// The id in the pre-rendered HTML, if it had one
+        found: Option<ElementId>,
+    },
+
+    /// The VirtualDom hasn't been rebuilt, so there are no nodes to hydrate
     VNodeNotInitialized,
+
+    /// A component was still suspended on the client, so its pre-rendered nodes can't be matched up
+    ComponentSuspended(ScopeId),
 }
 use RehydrationError::*;
 
+impl fmt::Display for RehydrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeNotFound { expected } => write!(f, "expected {} but found nothing", expected),
+            NodeTypeMismatch { expected, found } => {
+                write!(f, "expected {} but found {}", expected, found)
+            }
+            NodeIdMismatch {
+                expected,
+                found: Some(found),
+            } => write!(f, "expected node {} but found node {}", expected.0, found.0),
+            NodeIdMismatch {
+                expected,
+                found: None,
+            } => write!(f, "expected node {} but found no hydration id", expected.0),
+            VNodeNotInitialized => write!(f, "the VirtualDom has not been rebuilt"),
+            ComponentSuspended(scope) => write!(f, "component {:?} is suspended", scope),
+        }
+    }
+}
+
+impl std::error::Error for RehydrationError {}
+
 impl WebsysDom {
-    // we're streaming in patches, but the nodes already exist
-    // so we're just going to write the correct IDs to the node and load them in
+    /// Walk the VirtualDom alongside the pre-rendered children of the root element, registering every node the
+    /// initial edits would have created and attaching listeners.
+    ///
+    /// The VirtualDom must have been rebuilt, and the edits from the rebuild must not be applied if this succeeds. If
+    /// this fails, nothing was changed.
     pub fn rehydrate(&mut self, dom: &VirtualDom) -> Result<(), RehydrationError> {
-        let root = self
+        let copy = self
             .root
-            .clone()
-            .dyn_into::<Node>()
-            .map_err(|_| NodeTypeMismatch)?;
-
-        let root_scope = dom.base_scope();
-        let root_node = root_scope.root_node();
-
-        let mut nodes = vec![root];
-        let mut counter = vec![0];
-
-        let mut last_node_was_text = false;
-
-        todo!()
-        // // Recursively rehydrate the dom from the VirtualDom
-        // self.rehydrate_single(
-        //     &mut nodes,
-        //     &mut counter,
-        //     dom,
-        //     root_node,
-        //     &mut last_node_was_text,
-        // )
+            .clone_node_with_deep(true)
+            .map_err(|_| NodeNotFound {
+                expected: "the pre-rendered app".to_string(),
+            })?;
+
+        let mut hydration = Hydration::default();
+        let mut cursor = copy.first_child();
+
+        hydration.rehydrate_scope(dom, ScopeId(0), &copy, &mut cursor)?;
+
+        if let Some(extra) = cursor {
+            return Err(mismatch("the end of the app", &extra));
+        }
+
+        // Everything matched, so swap the hydrated nodes into the document
+        self.root.set_text_content(None);
+        while let Some(child) = copy.first_child() {
+            _ = self.root.append_child(&child);
+        }
+
+        for found in hydration.found {
+            match found {
+                Found::Node(id, node) => self.interpreter.SetNode(id.0 as u32, node),
+                Found::Listener(name, id) => self.interpreter.NewEventListener(
+                    &name,
+                    id.0 as u32,
+                    event_bubbles(&name),
+                    self.handler.as_ref().unchecked_ref(),
+                ),
+            }
+        }
+
+        Ok(())
     }
+}
+
+/// Something the walk found, which is registered with the interpreter once the whole app matched
+enum Found {
+    Node(ElementId, Node),
+    Listener(String, ElementId),
+}
 
-    fn rehydrate_single(
+#[derive(Default)]
+struct Hydration {
+    found: Vec<Found>,
+}
+
+impl Hydration {
+    fn rehydrate_scope(
         &mut self,
-        nodes: &mut Vec<Node>,
-        place: &mut Vec<u32>,
         dom: &VirtualDom,
-        node: &VNode,
-        last_node_was_text: &mut bool,
+        scope: ScopeId,
+        parent: &Node,
+        cursor: &mut Option<Node>,
     ) -> Result<(), RehydrationError> {
-        match node {
-            VNode::Text(t) => {
-                let node_id = t.id.get().ok_or(VNodeNotInitialized)?;
+        let scope_state = dom.get_scope(scope).ok_or(VNodeNotInitialized)?;
 
-                let cur_place = place.last_mut().unwrap();
+        match scope_state.try_root_node().ok_or(VNodeNotInitialized)? {
+            RenderReturn::Sync(Ok(node)) => self.rehydrate_vnode(dom, node, parent, cursor),
+            RenderReturn::Sync(Err(_)) => Ok(()),
+            RenderReturn::Async(_) => Err(ComponentSuspended(scope)),
+        }
+    }
 
-                // skip over the comment element
-                if *last_node_was_text {
-                    if cfg!(debug_assertions) {
-                        let node = nodes.last().unwrap().child_nodes().get(*cur_place).unwrap();
-                        let node_text = node.dyn_into::<Comment>().unwrap();
-                        assert_eq!(node_text.data(), "spacer");
-                    }
-                    *cur_place += 1;
+    fn rehydrate_vnode(
+        &mut self,
+        dom: &VirtualDom,
+        vnode: &VNode,
+        parent: &Node,
+        cursor: &mut Option<Node>,
+    ) -> Result<(), RehydrationError> {
+        for (root_idx, root) in vnode.template.roots.iter().enumerate() {
+            let id = vnode.root_ids[root_idx].get();
+
+            // Dynamic roots register their own nodes
+            if let Some(node) =
+                self.rehydrate_template_node(dom, vnode, root, Some(id), parent, cursor)?
+            {
+                self.found.push(Found::Node(id, node));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Hydrate a node of the template, returning it if it's static
+    fn rehydrate_template_node(
+        &mut self,
+        dom: &VirtualDom,
+        vnode: &VNode,
+        node: &TemplateNode,
+        root_id: Option<ElementId>,
+        parent: &Node,
+        cursor: &mut Option<Node>,
+    ) -> Result<Option<Node>, RehydrationError> {
+        match node {
+            TemplateNode::Element {
+                tag,
+                attrs,
+                children,
+                ..
+            } => {
+                let expected = || format!("<{}>", tag);
+                let node = cursor.take().ok_or_else(|| NodeNotFound {
+                    expected: expected(),
+                })?;
+
+                let el = match node.dyn_ref::<Element>() {
+                    Some(el) if el.tag_name().eq_ignore_ascii_case(tag) => el,
+                    _ => return Err(mismatch(&expected(), &node)),
+                };
+
+                // Roots and elements with dynamic attributes carry the id they were given on the server
+                let first_dynamic_attr = attrs.iter().find_map(|attr| match attr {
+                    TemplateAttribute::Dynamic { id } => Some(*id),
+                    _ => None,
+                });
+                let id = root_id.or_else(|| {
+                    first_dynamic_attr.map(|id| vnode.dynamic_attrs[id].mounted_element.get())
+                });
+                if let Some(id) = id {
+                    take_node_id(el, id)?;
                 }
 
-                let node = nodes
-                    .last()
-                    .unwrap()
-                    .child_nodes()
-                    .get(*cur_place)
-                    .ok_or(NodeNotFound)?;
+                *cursor = node.next_sibling();
 
-                let _text_el = node.dyn_ref::<Text>().ok_or(NodeTypeMismatch)?;
+                for attr in attrs.iter() {
+                    if let TemplateAttribute::Dynamic { id } = attr {
+                        self.rehydrate_attribute(&vnode.dynamic_attrs[*id], &node);
+                    }
+                }
 
-                // in debug we make sure the text is the same
-                if cfg!(debug_assertions) {
-                    let contents = _text_el.node_value().unwrap();
-                    assert_eq!(t.text, contents);
+                let mut child_cursor = node.first_child();
+                for child in children.iter() {
+                    self.rehydrate_template_node(
+                        dom,
+                        vnode,
+                        child,
+                        None,
+                        &node,
+                        &mut child_cursor,
+                    )?;
                 }
 
-                *last_node_was_text = true;
+                if let Some(extra) = child_cursor {
+                    return Err(mismatch(&format!("the end of {}", expected()), &extra));
+                }
 
-                self.interpreter.SetNode(node_id.0, node);
+                Ok(Some(node))
+            }
 
-                *cur_place += 1;
+            TemplateNode::Text { text } => {
+                let node = take_text(parent, cursor, text)?;
+                Ok(Some(node.into()))
             }
 
-            VNode::Element(vel) => {
-                let node_id = vel.id.get().ok_or(VNodeNotInitialized)?;
+            TemplateNode::Dynamic { id } | TemplateNode::DynamicText { id } => {
+                self.rehydrate_dynamic_node(dom, &vnode.dynamic_nodes[*id], parent, cursor)?;
+                Ok(None)
+            }
+        }
+    }
 
-                let cur_place = place.last_mut().unwrap();
+    fn rehydrate_dynamic_node(
+        &mut self,
+        dom: &VirtualDom,
+        node: &DynamicNode,
+        parent: &Node,
+        cursor: &mut Option<Node>,
+    ) -> Result<(), RehydrationError> {
+        match node {
+            DynamicNode::Text(text) => {
+                take_text_start(parent, cursor, text.id.get())?;
 
-                let node = nodes.last().unwrap().child_nodes().get(*cur_place).unwrap();
+                // Empty text doesn't make it into the HTML, so create the node ourselves
+                let node = match cursor.as_ref().and_then(|node| node.dyn_ref::<Text>()) {
+                    Some(_) => take_text(parent, cursor, text.value)?,
+                    None => insert_text(parent, cursor, "")?,
+                };
 
-                self.interpreter.SetNode(node_id.0, node.clone());
+                take_text_end(parent, cursor)?;
 
-                *cur_place += 1;
+                self.found.push(Found::Node(text.id.get(), node.into()));
+            }
 
-                nodes.push(node.clone());
+            DynamicNode::Placeholder(slot) => {
+                let node = cursor.take().ok_or_else(|| NodeNotFound {
+                    expected: "a placeholder".to_string(),
+                })?;
+
+                match node.dyn_ref::<Element>() {
+                    Some(el)
+                        if el
+                            .tag_name()
+                            .eq_ignore_ascii_case(hydration::PLACEHOLDER_TAG) =>
+                    {
+                        take_node_id(el, slot.get())?
+                    }
+                    _ => return Err(mismatch("a placeholder", &node)),
+                }
 
-                place.push(0);
+                *cursor = node.next_sibling();
+                self.found.push(Found::Node(slot.get(), node));
+            }
 
-                // we cant have the last node be text
-                let mut last_node_was_text = false;
-                for child in vel.children {
-                    self.rehydrate_single(nodes, place, dom, child, &mut last_node_was_text)?;
+            DynamicNode::Fragment(nodes) => {
+                for node in nodes.iter() {
+                    self.rehydrate_vnode(dom, node, parent, cursor)?;
                 }
+            }
 
-                for listener in vel.listeners {
-                    let id = listener.mounted_node.get().unwrap();
-                    self.interpreter.NewEventListener(
-                        listener.event,
-                        Some(id.as_u64()),
-                        self.handler.as_ref().unchecked_ref(),
-                        event_bubbles(listener.event),
-                    );
-                }
+            DynamicNode::Component(component) => {
+                let scope = component.scope.get().ok_or(VNodeNotInitialized)?;
+                self.rehydrate_scope(dom, scope, parent, cursor)?;
+            }
+        }
+
+        Ok(())
+    }
 
-                if !vel.listeners.is_empty() {
-                    use smallstr::SmallString;
-                    use std::fmt::Write;
+    fn rehydrate_attribute(&mut self, attr: &Attribute, node: &Node) {
+        let id = attr.mounted_element.get();
+        self.found.push(Found::Node(id, node.clone()));
 
-                    // 8 digits is enough, yes?
-                    // 12 million nodes in one page?
-                    let mut s: SmallString<[u8; 8]> = smallstr::SmallString::new();
-                    write!(s, "{}", node_id).unwrap();
+        // The rest of the attributes were written by the pre-renderer, but listeners only exist on the client
+        if let AttributeValue::Listener(_) = attr.value {
+            self.found
+                .push(Found::Listener(attr.name[2..].to_string(), id));
+        }
+    }
+}
 
-                    node.dyn_ref::<Element>()
-                        .unwrap()
-                        .set_attribute("dioxus-id", s.as_str())
-                        .unwrap();
-                }
+/// Take the text node under the cursor.
+///
+/// The browser merges neighboring text into a single node, so any text past `expected` is split off into its own node.
+fn take_text(
+    parent: &Node,
+    cursor: &mut Option<Node>,
+    expected: &str,
+) -> Result<Text, RehydrationError> {
+    let node = match cursor.take() {
+        Some(node) => node,
+        None if expected.is_empty() => return insert_text(parent, cursor, ""),
+        None => {
+            return Err(NodeNotFound {
+                expected: describe_text(expected),
+            })
+        }
+    };
 
-                place.pop();
-                nodes.pop();
+    let text = match node.dyn_into::<Text>() {
+        Ok(text) => text,
+        Err(node) if expected.is_empty() => {
+            *cursor = Some(node);
+            return insert_text(parent, cursor, "");
+        }
+        Err(node) => return Err(mismatch(&describe_text(expected), &node)),
+    };
+
+    // DOM offsets are counted in UTF-16 code units
+    let len = expected.encode_utf16().count() as u32;
+    if text.length() > len {
+        text.split_text(len)
+            .map_err(|_| mismatch(&describe_text(expected), &text))?;
+    }
 
-                if cfg!(debug_assertions) {
-                    let el = node.dyn_ref::<Element>().unwrap();
-                    let name = el.tag_name().to_lowercase();
-                    assert_eq!(name, vel.tag);
-                }
-            }
+    *cursor = text.next_sibling();
+    Ok(text)
+}
+
+/// Insert a new text node in front of the cursor
+fn insert_text(
+    parent: &Node,
+    cursor: &mut Option<Node>,
+    value: &str,
+) -> Result<Text, RehydrationError> {
+    let text = Text::new_with_data(value).map_err(|_| NodeNotFound {
+        expected: describe_text(value),
+    })?;
+
+    parent
+        .insert_before(&text, cursor.as_ref())
+        .map_err(|_| NodeNotFound {
+            expected: describe_text(value),
+        })?;
+
+    Ok(text)
+}
 
-            VNode::Placeholder(el) => {
-                let node_id = el.id.get().ok_or(VNodeNotInitialized)?;
+/// Check the hydration id of an element and remove it
+fn take_node_id(el: &Element, expected: ElementId) -> Result<(), RehydrationError> {
+    let found = el
+        .get_attribute(hydration::NODE_ID_ATTRIBUTE)
+        .and_then(|value| hydration::parse_node_id(&value));
 
-                let cur_place = place.last_mut().unwrap();
-                let node = nodes.last().unwrap().child_nodes().get(*cur_place).unwrap();
+    if found != Some(expected) {
+        return Err(NodeIdMismatch { expected, found });
+    }
 
-                self.interpreter.SetNode(node_id.0, node);
+    _ = el.remove_attribute(hydration::NODE_ID_ATTRIBUTE);
+    Ok(())
+}
 
-                // self.nodes[node_id.0] = Some(node);
+/// Take the comment the pre-renderer writes in front of dynamic text, checking its id
+fn take_text_start(
+    parent: &Node,
+    cursor: &mut Option<Node>,
+    expected: ElementId,
+) -> Result<(), RehydrationError> {
+    let comment = take_comment(parent, cursor, "the start of dynamic text")?;
+
+    match hydration::parse_text_start(&comment) {
+        Some(found) if found == expected => Ok(()),
+        found => Err(NodeIdMismatch { expected, found }),
+    }
+}
 
-                *cur_place += 1;
-            }
+/// Take the comment the pre-renderer writes after dynamic text
+fn take_text_end(parent: &Node, cursor: &mut Option<Node>) -> Result<(), RehydrationError> {
+    let expected = format!("<!--{}-->", hydration::TEXT_END);
 
-            VNode::Fragment(el) => {
-                for el in el.children {
-                    self.rehydrate_single(nodes, place, dom, el, last_node_was_text)?;
-                }
-            }
+    match take_comment(parent, cursor, &expected)? {
+        comment if comment == hydration::TEXT_END => Ok(()),
+        comment => Err(NodeTypeMismatch {
+            expected,
+            found: format!("<!--{}-->", comment),
+        }),
+    }
+}
 
-            VNode::Component(el) => {
-                let scope = dom.get_scope(el.scope.get().unwrap()).unwrap();
-                let node = scope.root_node();
-                todo!()
-                // self.rehydrate_single(nodes, place, dom, node, last_node_was_text)?;
-            }
-            VNode::TemplateRef(_) => todo!(),
-        }
-        Ok(())
+/// Take the comment under the cursor, removing it from the DOM
+fn take_comment(
+    parent: &Node,
+    cursor: &mut Option<Node>,
+    expected: &str,
+) -> Result<String, RehydrationError> {
+    let node = cursor.take().ok_or_else(|| NodeNotFound {
+        expected: expected.to_string(),
+    })?;
+
+    let data = match node.dyn_ref::<Comment>() {
+        Some(comment) => comment.data(),
+        None => return Err(mismatch(expected, &node)),
+    };
+
+    *cursor = node.next_sibling();
+    _ = parent.remove_child(&node);
+
+    Ok(data)
+}
+
+fn mismatch(expected: &str, found: &Node) -> RehydrationError {
+    NodeTypeMismatch {
+        expected: expected.to_string(),
+        found: describe_node(found),
+    }
+}
+
+fn describe_text(text: &str) -> String {
+    format!("the text {:?}", text)
+}
+
+fn describe_node(node: &Node) -> String {
+    if let Some(el) = node.dyn_ref::<Element>() {
+        return format!("<{}>", el.tag_name().to_lowercase());
+    }
+
+    if let Some(comment) = node.dyn_ref::<Comment>() {
+        return format!("<!--{}-->", comment.data());
+    }
+
+    match node.text_content() {
+        Some(text) => describe_text(&text),
+        None => node.node_name(),
     }
 }
@@ -1,5 +1,6 @@
 use dioxus::prelude::*;
 use dioxus_web::Config;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_test::wasm_bindgen_test;
 use web_sys::window;
 
@@ -50,7 +51,7 @@ fn rehydrates() {
 
     let mut dom = VirtualDom::new(app);
     let _ = dom.rebuild();
-    let out = dioxus_ssr::render(&dom);
+    let out = dioxus_ssr::pre_render(&dom);
 
     window()
         .unwrap()
@@ -62,3 +63,88 @@ fn rehydrates() {
 
     dioxus_web::launch_cfg(app, Config::new().hydrate(true));
 }
+
+fn main_element() -> web_sys::Element {
+    window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .get_element_by_id("main")
+        .unwrap()
+}
+
+fn counter(cx: Scope) -> Element {
+    let count = use_state(cx, || 0);
+
+    cx.render(rsx! {
+        h1 { "Count: {count}" }
+        button { onclick: move |_| count.modify(|c| c + 1), "increment" }
+    })
+}
+
+#[wasm_bindgen_test]
+async fn adopts_matching_html() {
+    let mut dom = VirtualDom::new(counter);
+    let _ = dom.rebuild();
+    let out = dioxus_ssr::pre_render(&dom);
+
+    window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .body()
+        .unwrap()
+        .set_inner_html(&format!("<div id='main'>{}</div>", out));
+    let heading = main_element().first_element_child().unwrap();
+
+    dioxus_web::launch_cfg(counter, Config::new().hydrate(true));
+    gloo_timers::future::TimeoutFuture::new(50).await;
+
+    // The pre-rendered nodes are kept, without their hydration markers
+    let main = main_element();
+    assert!(main
+        .first_element_child()
+        .unwrap()
+        .is_same_node(Some(&heading)));
+    assert!(!main.inner_html().contains("data-node-hydration"));
+    assert!(!main.inner_html().contains("<!--"));
+
+    // And the listeners work
+    let button: web_sys::HtmlElement = main
+        .query_selector("button")
+        .unwrap()
+        .unwrap()
+        .dyn_into()
+        .unwrap();
+    button.click();
+    gloo_timers::future::TimeoutFuture::new(50).await;
+    assert_eq!(
+        main_element().first_element_child().unwrap().text_content(),
+        Some("Count: 1".to_string())
+    );
+}
+
+#[wasm_bindgen_test]
+async fn rebuilds_when_the_html_does_not_match() {
+    window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .body()
+        .unwrap()
+        .set_inner_html(
+            "<div id='main'><h1 data-node-hydration=\"1\">Stale</h1><p>extra</p></div>",
+        );
+
+    dioxus_web::launch_cfg(counter, Config::new().hydrate(true));
+    gloo_timers::future::TimeoutFuture::new(50).await;
+
+    // The app is built once, replacing the pre-rendered nodes
+    let main = main_element();
+    assert_eq!(main.children().length(), 2);
+    assert_eq!(
+        main.first_element_child().unwrap().text_content(),
+        Some("Count: 0".to_string())
+    );
+    assert!(!main.inner_html().contains("Stale"));
+}